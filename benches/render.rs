@@ -0,0 +1,116 @@
+//! Benchmarks of the CPU scale and pixel format conversion paths
+//!
+//! Frames are drawn with `PixelRenderer::render_to_vec`, which runs the same
+//! copy as a window present without needing a window. Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use webview::{FilterMode, PixelFormat, PixelRenderer, ScaleMode};
+
+/// Bytes per pixel of a source format
+fn bytes_per_pixel(format: PixelFormat) -> usize {
+  match format {
+    PixelFormat::Rgb | PixelFormat::Bgr => 3,
+    PixelFormat::Gray8 | PixelFormat::Indexed8 => 1,
+    _ => 4,
+  }
+}
+
+/// Benchmarks drawing a `width` x `height` buffer into an `out_width` x `out_height` frame
+fn bench_render(
+  c: &mut Criterion,
+  name: &str,
+  format: PixelFormat,
+  (width, height): (u32, u32),
+  (out_width, out_height): (u32, u32),
+  configure: impl FnOnce(&mut PixelRenderer),
+) {
+  let mut renderer = PixelRenderer::new(width, height).unwrap();
+  renderer.set_pixel_format(format);
+  configure(&mut renderer);
+  let len = width as usize * height as usize * bytes_per_pixel(format);
+  let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+  c.bench_function(name, |b| {
+    b.iter_batched(
+      || data.clone().into(),
+      |buffer| {
+        renderer
+          .render_to_vec(buffer, out_width, out_height)
+          .unwrap()
+      },
+      BatchSize::LargeInput,
+    )
+  });
+}
+
+/// 1:1 copies, where RGBA and BGRA rows are copied (and swizzled) directly
+fn unscaled(c: &mut Criterion) {
+  let size = (1920, 1080);
+  bench_render(
+    c,
+    "unscaled 1080p rgba",
+    PixelFormat::Rgba,
+    size,
+    size,
+    |_| {},
+  );
+  bench_render(
+    c,
+    "unscaled 1080p bgra",
+    PixelFormat::Bgra,
+    size,
+    size,
+    |_| {},
+  );
+  // Mirroring rules out the row copy, so this is the per-pixel path
+  bench_render(
+    c,
+    "unscaled 1080p bgra per-pixel",
+    PixelFormat::Bgra,
+    size,
+    size,
+    |r| r.set_flip_horizontal(true),
+  );
+}
+
+/// Upscaling a 640x480 buffer to fit a 1080p frame
+fn fit(c: &mut Criterion) {
+  for (name, filter) in [
+    ("fit 480p to 1080p nearest", FilterMode::Nearest),
+    ("fit 480p to 1080p bilinear", FilterMode::Bilinear),
+  ] {
+    bench_render(c, name, PixelFormat::Rgba, (640, 480), (1920, 1080), |r| {
+      r.set_scale_mode(ScaleMode::Fit);
+      r.set_filter_mode(filter);
+    });
+  }
+}
+
+/// Downscaling a 1080p buffer to a quarter of its size
+fn downscale(c: &mut Criterion) {
+  for (name, filter) in [
+    ("downscale 1080p nearest", FilterMode::Nearest),
+    ("downscale 1080p bilinear", FilterMode::Bilinear),
+    ("downscale 1080p area-average", FilterMode::AreaAverage),
+    ("downscale 1080p lanczos", FilterMode::Lanczos),
+  ] {
+    bench_render(c, name, PixelFormat::Rgba, (1920, 1080), (480, 270), |r| {
+      r.set_scale_mode(ScaleMode::Stretch);
+      r.set_filter_mode(filter);
+    });
+  }
+}
+
+/// Unscaled copies of formats converted to RGBA pixel by pixel
+fn convert(c: &mut Criterion) {
+  let size = (1920, 1080);
+  for (name, format) in [
+    ("convert 1080p rgb", PixelFormat::Rgb),
+    ("convert 1080p gray8", PixelFormat::Gray8),
+    ("convert 1080p native-argb", PixelFormat::NativeArgb),
+  ] {
+    bench_render(c, name, format, size, size, |_| {});
+  }
+}
+
+criterion_group!(benches, unscaled, fit, downscale, convert);
+criterion_main!(benches);
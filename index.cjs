@@ -594,47 +594,82 @@ module.exports.EventLoopBuilder = nativeBinding.EventLoopBuilder
 module.exports.EventLoopProxy = nativeBinding.EventLoopProxy
 module.exports.EventLoopWindowTarget = nativeBinding.EventLoopWindowTarget
 module.exports.PixelRenderer = nativeBinding.PixelRenderer
+module.exports.RenderHandle = nativeBinding.RenderHandle
 module.exports.WebContext = nativeBinding.WebContext
 module.exports.Webview = nativeBinding.Webview
 module.exports.WebView = nativeBinding.WebView
 module.exports.WebViewBuilder = nativeBinding.WebViewBuilder
 module.exports.Window = nativeBinding.Window
 module.exports.WindowBuilder = nativeBinding.WindowBuilder
+module.exports.AlphaMode = nativeBinding.AlphaMode
+module.exports.Anchor = nativeBinding.Anchor
 module.exports.availableMonitors = nativeBinding.availableMonitors
+module.exports.availableScalers = nativeBinding.availableScalers
+module.exports.Backend = nativeBinding.Backend
 module.exports.BackgroundThrottlingPolicy = nativeBinding.BackgroundThrottlingPolicy
 module.exports.BadIcon = nativeBinding.BadIcon
+module.exports.cachedRenderWindows = nativeBinding.cachedRenderWindows
+module.exports.clearRenderCache = nativeBinding.clearRenderCache
+module.exports.clearRenderCaches = nativeBinding.clearRenderCaches
+module.exports.CompositeMode = nativeBinding.CompositeMode
 module.exports.ControlFlow = nativeBinding.ControlFlow
 module.exports.CursorIcon = nativeBinding.CursorIcon
 module.exports.DeviceEventFilter = nativeBinding.DeviceEventFilter
+module.exports.DitherMode = nativeBinding.DitherMode
 module.exports.DragDropEvent = nativeBinding.DragDropEvent
+module.exports.DrawCommandKind = nativeBinding.DrawCommandKind
 module.exports.ElementState = nativeBinding.ElementState
 module.exports.Error = nativeBinding.Error
+module.exports.FilterMode = nativeBinding.FilterMode
+module.exports.flushRenderThread = nativeBinding.flushRenderThread
+module.exports.forceX11Backend = nativeBinding.forceX11Backend
 module.exports.FullscreenType = nativeBinding.FullscreenType
+module.exports.getClipboardImage = nativeBinding.getClipboardImage
 module.exports.getWebviewVersion = nativeBinding.getWebviewVersion
 module.exports.ImeState = nativeBinding.ImeState
+module.exports.isTransientRenderError = nativeBinding.isTransientRenderError
 module.exports.Key = nativeBinding.Key
 module.exports.KeyCode = nativeBinding.KeyCode
 module.exports.KeyLocation = nativeBinding.KeyLocation
 module.exports.ModifiersState = nativeBinding.ModifiersState
+module.exports.monitors = nativeBinding.monitors
+module.exports.monotonicTimeMicros = nativeBinding.monotonicTimeMicros
 module.exports.MouseButtonState = nativeBinding.MouseButtonState
 module.exports.NewWindowResponse = nativeBinding.NewWindowResponse
+module.exports.Origin = nativeBinding.Origin
 module.exports.PageLoadEvent = nativeBinding.PageLoadEvent
+module.exports.PixelFormat = nativeBinding.PixelFormat
+module.exports.premultiplyAlpha = nativeBinding.premultiplyAlpha
+module.exports.PresentOutcome = nativeBinding.PresentOutcome
 module.exports.primaryMonitor = nativeBinding.primaryMonitor
 module.exports.ProgressBarStatus = nativeBinding.ProgressBarStatus
 module.exports.ProgressState = nativeBinding.ProgressState
+module.exports.RawHandleKind = nativeBinding.RawHandleKind
+module.exports.renderCacheLen = nativeBinding.renderCacheLen
+module.exports.renderCacheStats = nativeBinding.renderCacheStats
+module.exports.rendererInfo = nativeBinding.rendererInfo
+module.exports.RenderError = nativeBinding.RenderError
 module.exports.renderPixels = nativeBinding.renderPixels
 module.exports.ResizeDirection = nativeBinding.ResizeDirection
+module.exports.Rotation = nativeBinding.Rotation
 module.exports.ScaleMode = nativeBinding.ScaleMode
+module.exports.scaleModeFromString = nativeBinding.scaleModeFromString
+module.exports.scaleModeToString = nativeBinding.scaleModeToString
+module.exports.setClipboardImage = nativeBinding.setClipboardImage
 module.exports.StartCause = nativeBinding.StartCause
+module.exports.supportedPixelFormats = nativeBinding.supportedPixelFormats
+module.exports.supportedScaleModes = nativeBinding.supportedScaleModes
 module.exports.TaoControlFlow = nativeBinding.TaoControlFlow
 module.exports.TaoFullscreenType = nativeBinding.TaoFullscreenType
 module.exports.TaoTheme = nativeBinding.TaoTheme
 module.exports.taoVersion = nativeBinding.taoVersion
 module.exports.Theme = nativeBinding.Theme
 module.exports.TouchPhase = nativeBinding.TouchPhase
+module.exports.unpremultiplyAlpha = nativeBinding.unpremultiplyAlpha
 module.exports.UserAttentionType = nativeBinding.UserAttentionType
 module.exports.WebviewApplicationEvent = nativeBinding.WebviewApplicationEvent
 module.exports.webviewVersion = nativeBinding.webviewVersion
 module.exports.WindowEvent = nativeBinding.WindowEvent
 module.exports.WindowLevel = nativeBinding.WindowLevel
+module.exports.WrapMode = nativeBinding.WrapMode
 module.exports.WryTheme = nativeBinding.WryTheme
@@ -86,27 +86,637 @@ export declare class EventLoopWindowTarget {
  * Resources are cached per-window and reused across all PixelRenderer instances.
  */
 export declare class PixelRenderer {
-  /** Creates a new pixel renderer with the given buffer dimensions */
+  /**
+   * Creates a new pixel renderer with the given buffer dimensions
+   *
+   * Fails if either dimension is 0.
+   */
   constructor(bufferWidth: number, bufferHeight: number)
-  /** Creates a new pixel renderer with options */
+  /**
+   * Creates a new pixel renderer with options
+   *
+   * Fails if either buffer dimension is 0, or if an option holds a value its
+   * setter would reject. List options must have their documented length, e.g.
+   * 3 (RGB, opaque) or 4 (RGBA) values for `background_color`, 2 for
+   * `tile_offset` and 4 for `padding`.
+   */
   static withOptions(options: RenderOptions): PixelRenderer
+  /** Sets the scaling mode and returns the renderer for chaining */
+  withScaleMode(mode: ScaleMode): this
+  /** Sets the background color and returns the renderer for chaining */
+  withBackgroundColor(r: number, g: number, b: number, a: number): this
+  /**
+   * Sets the background color from one 0xRRGGBBAA number and returns the
+   * renderer for chaining
+   */
+  withBackgroundColorPacked(rgba: number): this
+  /** Sets the sampling filter and returns the renderer for chaining */
+  withFilterMode(mode: FilterMode): this
+  /** Sets the letterbox anchor and returns the renderer for chaining */
+  withAnchor(anchor: Anchor): this
+  /** Sets the source pixel format and returns the renderer for chaining */
+  withPixelFormat(format: PixelFormat): this
+  /** Sets the source row order and returns the renderer for chaining */
+  withOrigin(origin: Origin): this
+  /** Sets the rotation and returns the renderer for chaining */
+  withRotation(rotation: Rotation): this
+  /** Sets the horizontal and vertical flips and returns the renderer for chaining */
+  withFlip(horizontal: boolean, vertical: boolean): this
+  /** Sets the dithering mode and returns the renderer for chaining */
+  withDitherMode(mode: DitherMode): this
+  /** Sets how alpha is written and returns the renderer for chaining */
+  withAlphaMode(mode: AlphaMode): this
+  /** Sets how the buffer combines with the window and returns the renderer for chaining */
+  withCompositeMode(mode: CompositeMode): this
+  /**
+   * Sets the scale factor and returns the renderer for chaining
+   *
+   * Fails like `setScaleFactor`.
+   */
+  withScaleFactor(scaleFactor: number): this
+  /**
+   * Sets the gamma and returns the renderer for chaining
+   *
+   * Fails like `setGamma`.
+   */
+  withGamma(gamma?: number | undefined | null): this
   /** Sets the scaling mode */
   setScaleMode(mode: ScaleMode): void
+  /**
+   * Sets the aspect ratio tolerance used by `ScaleMode.Auto`
+   *
+   * `tolerance` is the relative difference (0.05 = 5%) between the buffer and
+   * window aspect ratios below which the buffer is stretched instead of fitted.
+   */
+  setAutoAspectTolerance(tolerance: number): void
+  /**
+   * Returns the scale mode actually used for the window at its current size
+   *
+   * Resolves `ScaleMode.Auto` and `ScaleMode.FitDownStretchUp` against the
+   * window (or viewport) size; other modes are returned unchanged.
+   */
+  resolvedScaleMode(window: Window): ScaleMode
+  /**
+   * Sets how many window pixels each buffer pixel covers before scaling
+   *
+   * Pass the window's `scaleFactor()` when the buffer is authored at logical
+   * resolution. The buffer then behaves as if it were `scale_factor` times
+   * larger, e.g. `ScaleMode.None` shows it at its logical size instead of a
+   * quarter of the area on a 2x display. Call again when the window moves to a
+   * monitor with a different scale factor.
+   */
+  setScaleFactor(scaleFactor: number): void
+  /**
+   * Sets where the image sits in the window when it doesn't fill it
+   *
+   * Applies to the modes that letterbox (Fit, Integer, IntegerFit, None, FitWidth and
+   * FitHeight, including Auto and FitDownStretchUp when they resolve to Fit). With
+   * None, FitWidth and FitHeight, an image larger than the window is cropped from
+   * the side away from the anchor.
+   */
+  setAnchor(anchor: Anchor): void
+  /**
+   * Caps how far `ScaleMode.Stretch` may distort the aspect ratio
+   *
+   * See `RenderOptions.maxAspectSkew` for the formula. Pass `null` to stretch
+   * without a limit. Fails if the skew is negative or not finite.
+   */
+  setMaxAspectSkew(maxSkew?: number | undefined | null): void
+  /**
+   * Registers a callback invoked after each successful present
+   *
+   * The callback receives the window's render cache ID and the frame's timing.
+   * It runs later on the JavaScript thread, never inside the render call, so
+   * it may render again without deadlocking. Frames skipped as duplicates or
+   * by the window's frame rate limit don't invoke it. Pass `null` to remove it.
+   */
+  onPresent(callback: ((error: Error | null, info: PresentInfo) => void) | null): void
+  /**
+   * Sets whether `render` skips frames identical to the one the window shows
+   *
+   * See `RenderOptions.skipDuplicateFrames`.
+   */
+  setSkipDuplicateFrames(skip: boolean): void
+  /**
+   * Sets whether letterbox bars are balanced from the image's exact position
+   *
+   * See `RenderOptions.balancedLetterbox`.
+   */
+  setBalancedLetterbox(balanced: boolean): void
+  /**
+   * Sets how long the window size must stay unchanged before the surface is
+   * resized to it, in milliseconds
+   *
+   * Coalesces the surface resizes of a drag-resize into one. Until the size
+   * settles, frames are drawn at the previous surface size and scaled to the
+   * window. Pass 0 to resize on every size change.
+   */
+  setResizeDebounceMs(millis: number): void
+  /**
+   * Turns off resizing the surface as part of rendering
+   *
+   * While enabled, renders to a window whose size no longer matches its
+   * surface fail with `SurfaceSizeMismatch` until `resizeSurface` is called.
+   */
+  setManualResize(manual: boolean): void
+  /**
+   * Resizes the window's surface to the window's current size
+   *
+   * Needed after the window resizes when manual resizing is enabled. Does
+   * nothing if the window has no surface yet or its size already matches.
+   */
+  resizeSurface(window: Window): void
+  /**
+   * Sets where the first tile starts for `ScaleMode.Tile` and `ScaleMode.IntegerTile`
+   *
+   * The offset is in window pixels relative to the window (or viewport) and
+   * may be negative or larger than the buffer; animate it to scroll the pattern.
+   */
+  setTileOffset(x: number, y: number): void
   /** Sets the background color */
   setBackgroundColor(r: number, g: number, b: number, a: number): void
+  /** Sets the background color from one 0xRRGGBBAA number */
+  setBackgroundColorPacked(rgba: number): void
+  /**
+   * Fills the letterbox area with a checkerboard instead of the background color
+   *
+   * Cells start at the top-left corner of the window (or viewport). Pass `null`
+   * to go back to the solid background color. Fails if a color isn't 3 or 4
+   * values long or the cell size is 0.
+   */
+  setLetterboxCheckerboard(checkerboard?: Checkerboard | undefined | null): void
+  /** Sets the dithering mode used when reducing to 8 bits per channel */
+  setDitherMode(mode: DitherMode): void
+  /**
+   * Mirrors the buffer left to right, e.g. for front-facing camera frames
+   *
+   * Applies before scaling, so scale modes letterbox and crop the mirrored image
+   * the same way as the original.
+   */
+  setFlipHorizontal(flip: boolean): void
+  /**
+   * Mirrors the buffer top to bottom
+   *
+   * Combines with the origin: flipping a `BottomLeft` buffer shows it as if it
+   * were `TopLeft`.
+   */
+  setFlipVertical(flip: boolean): void
+  /**
+   * Rotates the buffer clockwise before scaling
+   *
+   * Rotation happens in source space: the scale mode lays out the rotated
+   * image, so `Cw90` and `Cw270` fit a landscape buffer as portrait. Flips
+   * and the origin apply to the buffer before it is rotated.
+   */
+  setRotation(rotation: Rotation): void
+  /**
+   * Sets the gamma applied to color channels as they are copied
+   *
+   * Channels become `255 * (value / 255)^(1 / gamma)`, so values above 1
+   * brighten the midtones. Pass `null` to turn gamma correction off.
+   */
+  setGamma(gamma?: number | undefined | null): void
+  /**
+   * Sets the color multiplied into every buffer pixel as it is copied
+   *
+   * See `RenderOptions.tint`. Pass `null` to remove the tint. Fails unless
+   * the tint has 3 (RGB) or 4 (RGBA) values.
+   */
+  setTint(tint?: Array<number> | undefined | null): void
+  /**
+   * Sets the multiplier applied to color channels after gamma
+   *
+   * Pass `null` to turn the brightness adjustment off.
+   */
+  setBrightness(brightness?: number | undefined | null): void
+  /**
+   * Sets the bytes from the start of one source row to the next
+   *
+   * For decoders that pad rows; buffers must then hold `stride * buffer_height`
+   * bytes. Pass `null` for packed rows (`buffer_width` * bytes per pixel).
+   * Fails if the stride is shorter than a row in the current pixel format.
+   */
+  setSourceStrideBytes(stride?: number | undefined | null): void
+  /**
+   * Sets the part of the buffer to display
+   *
+   * `rect` is in buffer pixels following the configured origin. Only it is
+   * sampled, and the scale modes treat its size as the buffer size, so moving
+   * or resizing it between frames pans and zooms. Pass `null` to display the
+   * whole buffer. Fails if the rectangle is empty or extends past the buffer.
+   */
+  setSourceRect(rect?: Rect | undefined | null): void
+  /**
+   * Sets the row order of source buffers
+   *
+   * `BottomLeft` flips the buffer vertically while copying, so OpenGL readbacks
+   * display upright without a separate flip pass.
+   */
+  setOrigin(origin: Origin): void
+  /**
+   * Sets the byte layout of source buffers
+   *
+   * Channels are reordered while copying, so BGRA video frames or 24-bit RGB
+   * images can be rendered without converting them in JavaScript first.
+   */
+  setPixelFormat(format: PixelFormat): void
+  /**
+   * Sets the colors of `PixelFormat.Indexed8` pixels
+   *
+   * `palette` holds 256 RGBA colors (1024 bytes); pixel value N is drawn with
+   * color N. Indexed buffers can't be rendered until a palette is set.
+   */
+  setPalette(palette: Buffer): void
+  /**
+   * Sets the sampling filter for scaled copies
+   *
+   * `Bilinear` blends the four nearest source pixels, which looks smoother
+   * than `Nearest` when upscaling photos or video. `AreaAverage` averages every
+   * source pixel behind a window pixel when downscaling, avoiding the moiré of
+   * skipped pixels in thumbnails of detailed images. `Lanczos` keeps the most
+   * detail when downscaling photos but is many times slower, so use it for
+   * still images rather than video. Copies at 1:1 scale always
+   * use nearest sampling since filtering can't change them. Ignored while
+   * adaptive quality is enabled.
+   */
+  setFilterMode(mode: FilterMode): void
+  /**
+   * Sets how alpha is written to the window
+   *
+   * `Straight` and `Premultiplied` keep the source and background alpha, so a
+   * semi-transparent HUD can be drawn over the desktop where the platform
+   * surface supports transparency. `Opaque` writes every pixel fully opaque.
+   */
+  setAlphaMode(mode: AlphaMode): void
+  /**
+   * Sets whether the buffer replaces or blends over the window's contents
+   *
+   * With `SourceOver` the background isn't cleared; the buffer is blended by
+   * its alpha over whatever the previous present left in the surface, and the
+   * letterbox bars keep their old contents.
+   */
+  setCompositeMode(mode: CompositeMode): void
+  /**
+   * Sets how samples outside the buffer are resolved
+   *
+   * Matters for filters that read neighboring pixels: with `Wrap`, the
+   * bilinear filter blends opposite edges so tiled textures scale seamlessly.
+   */
+  setWrapMode(mode: WrapMode): void
+  /**
+   * Enables or disables motion-adaptive sampling quality
+   *
+   * When enabled, each rendered buffer is compared with the previous one:
+   * frames with large changes use nearest sampling (motion masks detail), while
+   * near-static frames use bilinear filtering. Useful for video playback.
+   */
+  setAdaptiveQuality(enabled: boolean): void
+  /** Returns the filter used for the most recently rendered buffer */
+  lastFilterMode(): FilterMode
+  /**
+   * Enables or disables timing of the clear, copy and present stages
+   *
+   * Off by default to keep the extra clock reads out of the hot path.
+   */
+  setDetailedTiming(enabled: boolean): void
+  /** Returns statistics for the window, or null if it was never rendered to */
+  stats(window: Window): RenderStats | null
+  /**
+   * Returns when the window last presented a frame, or null if it never did
+   *
+   * The time is in milliseconds on the clock of `monotonicTimeMicros()`, so
+   * schedulers can compare it to the current time without extra bookkeeping.
+   */
+  lastPresentTime(window: Window): number | null
+  /**
+   * Reads back the frame most recently presented to the window
+   *
+   * Returns the window-sized RGBA pixels after scaling, letterboxing and the
+   * alpha mode were applied, e.g. for visual regression tests that diff
+   * against a golden image. Fails with `NothingRendered` if nothing has been
+   * rendered to the window yet.
+   *
+   * # Arguments
+   * * `window` - The Tao window to capture
+   * * `premultiplied` - Multiply color channels by alpha in the result (default: false);
+   *   frames already rendered with `AlphaMode.Premultiplied` are returned unchanged
+   *
+   * # Returns
+   * width * height * 4 bytes of RGBA, where width and height are the frame's dimensions
+   */
+  capture(window: Window, premultiplied?: boolean | undefined | null): Buffer
+  /**
+   * Selects the scaling implementation by name
+   *
+   * Overrides the filter normally picked for scaled copies (including adaptive
+   * quality). Pass `null` to go back to the default. See `availableScalers()`
+   * for the registered names.
+   */
+  setScaler(name?: string | undefined | null): void
+  /**
+   * Restricts rendering to a sub-rectangle of the window
+   *
+   * Scaling and letterboxing happen within the viewport instead of the whole
+   * window, and pixels outside it are left untouched. This lets several renderers
+   * share one window (split-screen, picture-in-picture). The viewport is clipped
+   * to the window size on every frame. Pass `null` to render to the whole window.
+   */
+  setViewport(viewport?: Rect | undefined | null): void
+  /**
+   * Sets the background margins inside the window (or viewport), in window pixels
+   *
+   * See `RenderOptions.padding`. Pass zeros to remove the margins.
+   */
+  setPadding(top: number, right: number, bottom: number, left: number): void
+  /**
+   * Tells the window where the caret of a self-rendered text field is
+   *
+   * `rect` is given in buffer coordinates (following the configured origin) and
+   * is mapped through the current scale mode and viewport into window
+   * coordinates before being forwarded to [`Window::set_ime_cursor_area`], so
+   * IME candidate windows and magnifiers track the caret as drawn on screen.
+   * The rectangle's width is ignored, since the window only takes the caret's
+   * position and height.
+   *
+   * [`Window::set_ime_cursor_area`]: crate::tao::structs::Window::set_ime_cursor_area
+   */
+  setTextInputRect(window: Window, rect: Rect): void
+  /**
+   * Returns where the buffer is drawn in the window at its current size
+   *
+   * Uses the same math and rounding as rendering, so overlays can be placed
+   * exactly on the letterboxed image. With `ScaleMode.Fill` the image may be
+   * larger than the window and is cropped around its center.
+   */
+  layout(window: Window): RenderLayout
+  /**
+   * Returns the window's inner size and render cache ID
+   *
+   * Both are read under a single window lock, so the size matches the window
+   * the ID refers to even while it is being resized.
+   */
+  windowMetrics(window: Window): WindowMetrics
+  /**
+   * Returns whether frames can be presented to the window
+   *
+   * Creates the window's render surface like [`PixelRenderer::prepare`], but
+   * returns false instead of throwing when that fails (e.g. on a headless CI
+   * runner without a display or GPU adapter), in which case no render state is
+   * cached for the window.
+   */
+  canRender(window: Window): boolean
+  /**
+   * Creates and caches the window's render surface without presenting
+   *
+   * The surface is sized to the window's current size, so the first `render`
+   * skips surface creation. Does nothing if the window already has one. Call it
+   * ahead of time when the first frame must appear within a tight deadline.
+   */
+  prepare(window: Window): void
   /**
    * Renders a pixel buffer to the given window
    *
    * # Arguments
    * * `window` - The Tao window to render to
-   * * `buffer` - RGBA pixel buffer (must be buffer_width * buffer_height * 4 bytes)
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   * * `frame_id` - Identifies the frame's content for `skipDuplicateFrames`,
+   *   instead of hashing the buffer
+   *
+   * # Returns
+   * Whether the frame was presented (false when skipped as a duplicate, within
+   * the window's minimum present interval, or because the window is hidden or
+   * minimized, see `Window.isOccluded`)
    *
    * # Performance Note
    * This method uses a global cache to avoid resource exhaustion errors
    * that occur when creating new contexts/surfaces on each render call.
    * Resources are cached per-window and reused across all PixelRenderer instances.
    */
-  render(window: Window, buffer: Buffer): void
+  render(window: Window, buffer: Buffer, frameId?: number | undefined | null): boolean
+  /**
+   * Renders pixels read in place from a `Uint8ClampedArray` or `Uint8Array`
+   *
+   * Same as `render`, but the view's bytes are read directly without wrapping
+   * them in a Buffer, so views over a `SharedArrayBuffer` (e.g. `ImageData.data`
+   * filled by a worker) cost no allocation per frame. Only the view's own
+   * range of its underlying buffer is read, and its byte length is validated
+   * like a Buffer's. Don't write to the memory while this call runs.
+   * Returns whether the frame was presented, like `render`.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `pixels` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   */
+  renderFromTypedArray(window: Window, pixels: Uint8ClampedArray | Uint8Array): boolean
+  /**
+   * Returns where this renderer scales buffers
+   *
+   * `Gpu` only when it was requested and the `gpu` build feature is enabled.
+   */
+  backend(): Backend
+  /**
+   * Renders a buffer with different dimensions than the renderer's, for this call only
+   *
+   * The buffer is validated against the given dimensions, and the scale mode,
+   * colors, filters and other settings are kept. Rows are taken as packed, since
+   * a source stride belongs to the configured width. The renderer's own
+   * dimensions are unchanged afterwards, so one renderer can follow a stream
+   * that changes resolution mid-session.
+   */
+  renderWithDimensions(window: Window, buffer: Buffer, bufferWidth: number, bufferHeight: number): boolean
+  /**
+   * Renders a pixel buffer, pacing presents to the window's minimum present interval
+   *
+   * Set the interval with `Window.setTargetFps` or
+   * `Window.setMinPresentIntervalMicros`. If the window presented too recently,
+   * the frame is skipped, or with `wait` the call blocks until the interval has
+   * elapsed and then presents. This lets a tight loop drive the renderer
+   * without presenting more often than needed. Otherwise it renders like
+   * `render`, so frames to an occluded window or duplicates under
+   * `skipDuplicateFrames` are skipped too.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   * * `wait` - Block until the frame can be presented instead of skipping it (default: false)
+   *
+   * # Returns
+   * Whether the frame was presented
+   */
+  renderPaced(window: Window, buffer: Buffer, wait?: boolean | undefined | null): boolean
+  /**
+   * Renders a pixel buffer without blocking the JavaScript thread on scaling
+   *
+   * Threading model: clearing and scaling the buffer into a window-sized frame
+   * runs on a libuv worker thread. Presenting then happens back on the
+   * JavaScript thread when the promise settles, because window surfaces can't
+   * be used from other threads; the present itself is not parallelized. If the
+   * window was resized in between, the frame is composed again at the new size
+   * before presenting.
+   *
+   * The task uses a snapshot of the renderer's settings at call time. Don't
+   * modify `buffer` until the promise settles.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   */
+  renderAsync(window: Window, buffer: Buffer): Promise<void>
+  /**
+   * Renders the same pixel buffer to several windows
+   *
+   * The buffer is validated once and the render cache is locked once for the
+   * whole batch. A failure on one window doesn't stop the others; check the
+   * returned results, which are in the same order as `windows`.
+   *
+   * # Arguments
+   * * `windows` - The Tao windows to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   */
+  renderMany(windows: Array<Window>, buffer: Buffer): Array<WindowRenderResult>
+  /**
+   * Renders a pixel buffer into a new RGBA buffer instead of a window
+   *
+   * Runs the same pipeline as `render` (background, viewport, scale mode,
+   * filtering, dithering and alpha mode) against an `out_width` x `out_height`
+   * frame, which is useful for headless tests and for feeding video encoders.
+   *
+   * # Arguments
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   * * `out_width` - Width of the output frame in pixels
+   * * `out_height` - Height of the output frame in pixels
+   *
+   * # Returns
+   * out_width * out_height * 4 bytes of RGBA
+   */
+  renderToVec(buffer: Buffer, outWidth: number, outHeight: number): Buffer
+  /**
+   * Redraws only the part of the window showing a changed rectangle of the buffer
+   *
+   * The dirty rectangle is mapped through the current scale mode and viewport,
+   * and only the window pixels it covers (plus a pixel of margin for filtering)
+   * are recopied; the background isn't cleared. The whole surface is still
+   * presented since the backend has no damage support, but the copy work is
+   * limited to the dirty area.
+   *
+   * The rest of the window keeps what the previous present drew, so the first
+   * call after creating the renderer or resizing the window must be a full
+   * `render`. With `AlphaMode.Premultiplied` the whole frame is redrawn. With
+   * `CompositeMode.SourceOver` the dirty pixels are blended over what the window
+   * shows, as `render` does.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - The full buffer, buffer_width * buffer_height pixels in the configured pixel format
+   * * `x` - Left edge of the changed rectangle in buffer pixels
+   * * `y` - Top edge of the changed rectangle in buffer pixels
+   * * `width` - Width of the changed rectangle
+   * * `height` - Height of the changed rectangle
+   */
+  renderRegion(window: Window, buffer: Buffer, x: number, y: number, width: number, height: number): void
+  /**
+   * Renders a pixel buffer and presents it at a target time
+   *
+   * The frame is composed immediately, then presentation waits (blocking) until
+   * `target_time_micros` on the clock returned by `monotonicTimeMicros()`.
+   * Nothing is locked while waiting, so other windows and render threads keep
+   * presenting. Frames whose target passed by more than `max_lateness_micros`
+   * (default: one 60 Hz frame) are dropped without presenting, so a player
+   * that falls behind its audio clock catches up instead of drifting. Targets
+   * more than 100ms ahead are rejected rather than waited for.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   * * `target_time_micros` - When the frame should appear on screen
+   * * `max_lateness_micros` - How late a frame may still be presented
+   */
+  presentAt(window: Window, buffer: Buffer, targetTimeMicros: number, maxLatenessMicros?: number | undefined | null): PresentOutcome
+  /**
+   * Clears the window and executes a list of draw commands, presenting once
+   *
+   * Commands run in order in window coordinates, clipped to the window (the
+   * viewport and scale mode don't apply). All commands are validated before
+   * anything is drawn, so an invalid command leaves the window untouched.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `commands` - Primitives to draw, bottom to top
+   */
+  renderCommands(window: Window, commands: Array<DrawCommand>): void
+  /**
+   * Creates a handle that presents frames for the window on a background thread
+   *
+   * The window's surface is created (and cleared to the background color) here,
+   * on the window's thread. Later calls to `submit` on the handle only copy the
+   * frame and hand it to the worker. The handle uses a snapshot of this
+   * renderer's current settings.
+   */
+  createRenderHandle(window: Window): RenderHandle
+  /**
+   * Queues a pixel buffer for the shared render thread and returns immediately
+   *
+   * One process-wide thread scales and presents the frames of every window in
+   * the order they were queued, using a snapshot of this renderer's settings
+   * and a copy of `buffer`, at the window's size when it was queued. The
+   * window's surface is created here first if it has none, on the window's
+   * thread. Frames are never dropped; call
+   * `flushRenderThread` to wait for them, and to get errors from failed
+   * presents, which are otherwise reported by the next call to this method.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   */
+  renderThreaded(window: Window, buffer: Buffer): void
+  /**
+   * Renders the buffer twice, side by side, using a different scale mode for each half
+   *
+   * This is a developer tool for comparing scale modes (e.g. Fit vs Fill) on the
+   * same content. The left and right halves are scaled independently and
+   * separated by a thin divider line. Like `render`, it draws within the
+   * viewport, inside the padding, over the background color or letterbox
+   * checkerboard. Fails with `InvalidConfiguration` in `CompositeMode.SourceOver`,
+   * since the halves always replace the frame.
+   *
+   * # Arguments
+   * * `window` - The Tao window to render to
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   * * `left_mode` - Scaling mode for the left half
+   * * `right_mode` - Scaling mode for the right half
+   */
+  renderSplit(window: Window, buffer: Buffer, leftMode: ScaleMode, rightMode: ScaleMode): void
+}
+
+/**
+ * Presents frames for a single window from a background thread
+ *
+ * Created with [`PixelRenderer::create_render_handle`]. The handle owns a
+ * reference to the native window and only releases it after the worker thread
+ * has stopped, so the cached surface can never be presented after its window
+ * is destroyed. A handle that outlives its `Window` releases the surface when
+ * it's dropped. Surfaces are created on the window's thread; if the worker
+ * needs a new one (e.g. after a failed resize), the submit after that reports
+ * an error and the next `render` on the window's thread recreates it.
+ */
+export declare class RenderHandle {
+  /**
+   * Queues a frame for the worker thread to present
+   *
+   * Returns false if the worker is still busy with the previous frame, in
+   * which case this frame is dropped. Errors from earlier background presents
+   * are returned here.
+   *
+   * # Arguments
+   * * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+   */
+  submit(buffer: Buffer): boolean
+  /**
+   * Stops the worker thread after it finishes any queued frame
+   *
+   * Called automatically when the handle is dropped.
+   */
+  close(): void
 }
 
 /** The web context for a webview. */
@@ -355,6 +965,14 @@ export declare class Window {
   setMaximized(maximized: boolean): void
   /** Gets whether the window is minimized. */
   isMinimized(): boolean
+  /**
+   * Gets whether the window's contents can't be seen: it's hidden or minimized.
+   *
+   * Tao reports no occlusion events, so a visible window fully covered by
+   * other windows still counts as not occluded. `PixelRenderer.render` skips
+   * occluded windows; render loops can check this to skip producing frames.
+   */
+  isOccluded(): boolean
   /** Sets whether the window is minimized. */
   setMinimized(minimized: boolean): void
   /** Gets whether the window is always on top. */
@@ -369,6 +987,31 @@ export declare class Window {
   cursorIcon(): CursorIcon
   /** Sets the cursor icon. */
   setCursorIcon(cursor: CursorIcon): void
+  /**
+   * Cycles the cursor through RGBA frames while it is over the window.
+   *
+   * Frames advance on a native timer at `fps`, replacing any running animation.
+   * The hotspot is clamped to each frame. Call `clearAnimatedCursor` to stop
+   * the animation and restore the system cursor.
+   */
+  setAnimatedCursor(frames: Array<CursorFrame>, fps: number, hotspotX: number, hotspotY: number): void
+  /**
+   * Stops the animated cursor and restores the cursor icon.
+   *
+   * Returns true if an animation was running.
+   */
+  clearAnimatedCursor(): boolean
+  /**
+   * Clips the window to the set pixels of a mask.
+   *
+   * `mask` holds one byte per pixel (width * height bytes) in logical window
+   * coordinates; nonzero bytes are inside the shape. Input and drawing outside
+   * the shape are removed, not just made transparent. Uses the XShape extension
+   * on X11; unsupported elsewhere.
+   */
+  setShape(mask: Buffer, width: number, height: number): void
+  /** Restores the window's rectangular shape. */
+  clearShape(): void
   /** Sets the cursor position. */
   setCursorPosition(x: number, y: number): void
   /** Gets the cursor position. */
@@ -383,14 +1026,83 @@ export declare class Window {
   setWindowIcon(width: number, height: number, rgba: Buffer): void
   /** Sets whether to ignore cursor events. */
   setIgnoreCursorEvents(ignore: boolean): void
+  /**
+   * Sets where the text cursor is, in physical window coordinates.
+   *
+   * `x` and `y` are the caret's top-left corner and `height` its height. The IME
+   * candidate window and screen magnifiers follow the caret; tao positions them
+   * at a single point, so the candidate window is placed at the caret's bottom
+   * edge, at (x, y + height), where it doesn't cover the caret.
+   */
+  setImeCursorArea(x: number, y: number, height: number): void
+  /**
+   * Registers a callback invoked with the new outer position when the window moves.
+   *
+   * Moving a window can put it on a monitor with a different scale factor or
+   * refresh rate without changing its size; the cached monitor properties are
+   * refreshed before callbacks run.
+   */
+  onMoved(callback: (error: Error | null, position: Position) => void): void
+  /** Gets the scale factor of the monitor the window is on. */
+  scaleFactor(): number
+  /**
+   * Gets the refresh rate in hertz of the monitor the window is on, if known.
+   *
+   * On Linux this is the rate GDK reports for the monitor, keeping fractional
+   * rates such as 59.94 Hz, or `null` when the compositor doesn't report one
+   * (as on some Wayland setups). Other platforms don't expose the active mode,
+   * so there it's the whole-hertz rate of the fastest video mode at the
+   * monitor's current resolution, which may be above the rate in use.
+   */
+  refreshRate(): number | null
+  /**
+   * Sets the taskbar/dock progress indicator.
+   *
+   * `value` ranges from 0 to 1 and is ignored for the `None` and `Indeterminate`
+   * states. Uses the Windows taskbar, the macOS dock and the Unity launcher on
+   * Linux; other platforms ignore it.
+   */
+  setProgressBar(state: ProgressState, value?: number | undefined | null): void
+  /**
+   * Sets the minimum time between presents for this window, in microseconds.
+   *
+   * Frames submitted through a `PixelRenderer` before the interval has elapsed since
+   * the last present are dropped, which keeps rarely-changing windows from presenting
+   * more often than the compositor needs. Use 0 to remove the limit.
+   */
+  setMinPresentIntervalMicros(micros: number): void
+  /**
+   * Limits how often a `PixelRenderer` presents to this window, in frames per second.
+   *
+   * Sets the minimum present interval to `1 / fps` seconds. Use 0 to remove the limit.
+   */
+  setTargetFps(fps: number): void
   /** Requests a redrawing of the window. */
   requestRedraw(): void
   /**
    * Closes the window.
-   * This hides the window immediately. The underlying window handle is still held
-   * until the Window struct is dropped.
+   * This hides the window immediately and releases its cached render surface.
+   * The underlying window handle is still held until the Window struct is dropped.
    */
   close(): void
+  /**
+   * Gets the key of the window's cached render state.
+   *
+   * This is the ID `PixelRenderer` caches surfaces under, as listed by
+   * `cachedRenderWindows()` and accepted by `clearRenderCache()`. It is the
+   * same value as `id`.
+   */
+  renderCacheId(): bigint
+  /**
+   * Gets the native window handle, for passing to other native libraries.
+   *
+   * Returns null if the window hasn't been created or its native window isn't
+   * available yet. The handle is borrowed, not owned: it is only valid while
+   * this window is alive, so the other library must stop using it before the
+   * window is closed. Presenting to it from elsewhere competes with any
+   * `PixelRenderer` rendering to the same window.
+   */
+  rawHandle(): RawHandleInfo | null
   /** Returns true if the window is marked as closed (hidden via close()). */
   isClosed(): boolean
 }
@@ -431,6 +1143,41 @@ export declare class WindowBuilder {
   build(eventLoop: EventLoop): Window
 }
 
+/** How the alpha channel of rendered frames is written. */
+export declare const enum AlphaMode {
+  /** Every pixel is written fully opaque. */
+  Opaque = 0,
+  /** Source and background alpha pass through unchanged. */
+  Straight = 1,
+  /**
+   * Alpha passes through and color channels are multiplied by it, for
+   * compositors that expect premultiplied alpha.
+   */
+  Premultiplied = 2
+}
+
+/** Where a letterboxed image sits within the window or viewport. */
+export declare const enum Anchor {
+  /** Top-left corner. */
+  TopLeft = 0,
+  /** Top edge, centered horizontally. */
+  Top = 1,
+  /** Top-right corner. */
+  TopRight = 2,
+  /** Left edge, centered vertically. */
+  Left = 3,
+  /** Centered. */
+  Center = 4,
+  /** Right edge, centered vertically. */
+  Right = 5,
+  /** Bottom-left corner. */
+  BottomLeft = 6,
+  /** Bottom edge, centered horizontally. */
+  Bottom = 7,
+  /** Bottom-right corner. */
+  BottomRight = 8
+}
+
 export interface ApplicationEvent {
   event: WebviewApplicationEvent
 }
@@ -448,6 +1195,20 @@ export interface ApplicationOptions {
  */
 export declare function availableMonitors(): Array<MonitorInfo>
 
+/** Returns the names of the scalers that can be passed to `PixelRenderer.setScaler` */
+export declare function availableScalers(): Array<string>
+
+/** Where `PixelRenderer` scales buffers. */
+export declare const enum Backend {
+  /** Scale on the CPU into a window-sized frame. */
+  Cpu = 0,
+  /**
+   * Upload the buffer as a texture and scale it on the GPU (needs the `gpu`
+   * build feature; falls back to `Cpu` otherwise).
+   */
+  Gpu = 1
+}
+
 /** Background throttling policy for webviews. */
 export declare const enum BackgroundThrottlingPolicy {
   /** Throttling is suspended when the page is in the background. */
@@ -489,6 +1250,68 @@ export interface BrowserWindowOptions {
   fullscreen?: FullscreenType
 }
 
+/**
+ * Returns the IDs of windows that currently have cached render state
+ *
+ * IDs match `Window.id`, so tooling can reconcile the cache against live
+ * windows and release entries left behind by windows closed without cleanup.
+ */
+export declare function cachedRenderWindows(): Array<bigint>
+
+/** Size of the render cache, returned by [`render_cache_stats`] */
+export interface CacheStats {
+  /** Number of windows with cached render state */
+  windowCount: number
+  /**
+   * Sum of the cached surfaces' sizes at 4 bytes per pixel
+   *
+   * An estimate of the frame memory held, not counting GPU-side copies.
+   */
+  approxBytes: number
+}
+
+/** Two-color checkerboard filling the letterbox area */
+export interface Checkerboard {
+  /** Color of the top-left cell, [R, G, B] or [R, G, B, A] */
+  colorA: Array<number>
+  /** Color of the cells between, [R, G, B] or [R, G, B, A] */
+  colorB: Array<number>
+  /** Side of each square cell in window pixels */
+  cellSize: number
+}
+
+/**
+ * Releases the cached render state for a window ID (see `Window.renderCacheId()`)
+ *
+ * Windows release their state automatically when closed or garbage collected,
+ * so this is only needed to free a surface early.
+ *
+ * Returns true if the window had cached state. The next render to the window
+ * creates a fresh surface.
+ */
+export declare function clearRenderCache(windowId: bigint): boolean
+
+/** Releases the cached render state of every window */
+export declare function clearRenderCaches(): void
+
+/** RGBA image read from the clipboard */
+export interface ClipboardImage {
+  /** RGBA pixels (width * height * 4 bytes) */
+  data: Buffer
+  /** Width in pixels */
+  width: number
+  /** Height in pixels */
+  height: number
+}
+
+/** How rendered buffers combine with what the window already shows. */
+export declare const enum CompositeMode {
+  /** Clear to the background color and overwrite with the buffer. */
+  Replace = 0,
+  /** Keep the current contents and blend the buffer over them by its alpha. */
+  SourceOver = 1
+}
+
 export declare const enum ControlFlow {
   Poll = 0,
   WaitUntil = 1,
@@ -514,6 +1337,16 @@ export interface CursorChangeDetails {
   newCursor: CursorIcon
 }
 
+/** One frame of an animated cursor */
+export interface CursorFrame {
+  /** RGBA pixels (width * height * 4 bytes) */
+  rgba: Buffer
+  /** Width in pixels */
+  width: number
+  /** Height in pixels */
+  height: number
+}
+
 /** Cursor icon. */
 export declare const enum CursorIcon {
   Default = 0,
@@ -571,6 +1404,16 @@ export interface Dimensions {
   height: number
 }
 
+/** Dithering applied when quantizing high-precision samples to 8 bits per channel. */
+export declare const enum DitherMode {
+  /** Round to the nearest value (may show banding in gradients). */
+  None = 0,
+  /** Ordered dithering with a 4x4 Bayer matrix (cheap, order-independent). */
+  Ordered = 1,
+  /** Floyd-Steinberg error diffusion (higher quality, processed serially). */
+  FloydSteinberg = 2
+}
+
 /** Drag drop event. */
 export declare const enum DragDropEvent {
   /** The drag has entered the webview area. */
@@ -583,6 +1426,46 @@ export declare const enum DragDropEvent {
   Dropped = 3
 }
 
+/**
+ * A single primitive in a display list
+ *
+ * Coordinates are in window pixels. Pixels are written as-is, without blending.
+ */
+export interface DrawCommand {
+  /** Kind of primitive to draw */
+  kind: DrawCommandKind
+  /** Left edge (FillRect, BlitBuffer) or start X (DrawLine) */
+  x: number
+  /** Top edge (FillRect, BlitBuffer) or start Y (DrawLine) */
+  y: number
+  /** Width of the rectangle or blitted buffer */
+  width?: number
+  /** Height of the rectangle or blitted buffer */
+  height?: number
+  /** End X of a line */
+  x2?: number
+  /** End Y of a line */
+  y2?: number
+  /** Color [R, G, B, A] for FillRect and DrawLine */
+  color?: Array<number>
+  /** RGBA pixels for BlitBuffer (width * height * 4 bytes) */
+  buffer?: Buffer
+  /** Text for DrawText */
+  text?: string
+}
+
+/** Kind of primitive in a `PixelRenderer` display list. */
+export declare const enum DrawCommandKind {
+  /** Fill a rectangle with a solid color. */
+  FillRect = 0,
+  /** Copy an RGBA buffer unscaled. */
+  BlitBuffer = 1,
+  /** Draw a one pixel wide line. */
+  DrawLine = 2,
+  /** Draw text (not supported yet). */
+  DrawText = 3
+}
+
 /** Element state for input devices. */
 export declare const enum ElementState {
   Pressed = 0,
@@ -614,11 +1497,50 @@ export type ExternalError =
   | { type: 'NotSupported' }
   | { type: 'Os', field0: string }
 
+/** Sampling filter used when scaling a buffer. */
+export declare const enum FilterMode {
+  /** Nearest neighbor (sharp, blocky when upscaling). */
+  Nearest = 0,
+  /** Bilinear interpolation of the four nearest source pixels (smooth). */
+  Bilinear = 1,
+  /**
+   * Average of all source pixels behind each window pixel when downscaling,
+   * avoiding the aliasing of skipped pixels (thumbnails); nearest neighbor
+   * when upscaling.
+   */
+  AreaAverage = 2,
+  /**
+   * Lanczos-3 windowed sinc when downscaling, keeping more detail than
+   * `Bilinear` in photo thumbnails; bilinear when upscaling. Many times slower
+   * than the other filters, so meant for still images rather than video.
+   */
+  Lanczos = 3
+}
+
+/**
+ * Blocks until every frame queued with `renderThreaded` has been presented
+ *
+ * Throws the error of the most recent failed threaded present, if any.
+ */
+export declare function flushRenderThread(): void
+
 /** Force touch/pen pressure. */
 export type Force =
   | { type: 'Calibrated', force: number, stage: number }
   | { type: 'Normalized', field0: number }
 
+/**
+ * Forces GTK to use the X11 backend, e.g. to run under XWayland in a Wayland session
+ *
+ * Sets `GDK_BACKEND=x11` unless a backend is already pinned. GTK picks its
+ * backend when it initializes, so call this once at startup, before creating
+ * the first event loop; later calls fail. This changes the process
+ * environment, so avoid calling it while other threads read it.
+ *
+ * Returns whether the backend was changed (always false outside Linux and BSDs).
+ */
+export declare function forceX11Backend(): boolean
+
 /** Fullscreen mode. */
 export type Fullscreen =
   | { type: 'Exclusive', field0: MonitorInfo }
@@ -639,6 +1561,15 @@ export interface GestureEvent {
   amount: number
 }
 
+/**
+ * Reads the image currently on the clipboard, if any
+ *
+ * The returned data can be passed to `PixelRenderer.render` with a renderer
+ * created for the same dimensions. Must be called on the event loop thread
+ * after the event loop has been created.
+ */
+export declare function getClipboardImage(): ClipboardImage | null
+
 export declare function getWebviewVersion(): string
 
 export interface HeaderData {
@@ -690,6 +1621,14 @@ export interface IpcMessage {
   uri: string
 }
 
+/**
+ * Whether a render call that threw an error with this `code` may succeed when retried
+ *
+ * True for surface failures such as `PresentFailed`, false for errors like
+ * `BufferSizeMismatch` that will fail the same way every time.
+ */
+export declare function isTransientRenderError(code: RenderError): boolean
+
 /** Keyboard key. */
 export declare const enum Key {
   /** The '1' key. */
@@ -1085,8 +2024,38 @@ export interface MonitorInfo {
   position: Position
   /** The scale factor of monitor. */
   scaleFactor: number
+  /** Whether this is the primary monitor (null when unknown). */
+  isPrimary?: boolean
+}
+
+/** Monitor layout returned by `monitors()`. */
+export interface MonitorLayout {
+  /** The display server the layout was read from: "x11", "wayland", "windows", "cocoa" or "unknown". */
+  displayServer: string
+  /** Whether windows can be placed at absolute positions (false on Wayland). */
+  supportsPositioning: boolean
+  /** The monitors, in the order the platform reports them. */
+  monitors: Array<MonitorInfo>
 }
 
+/**
+ * Returns the monitor layout along with the display server it was read from.
+ * Positions and sizes are in physical pixels on the virtual desktop, so a
+ * window can be placed on a given monitor before it is created. Wayland doesn't
+ * let clients position windows, which `supportsPositioning` reports; monitor
+ * positions there may not reflect the real arrangement.
+ * The monitor list is empty when monitors cannot be queried (see `availableMonitors`).
+ */
+export declare function monitors(): MonitorLayout
+
+/**
+ * Returns the current time on the presentation clock, in microseconds
+ *
+ * Target times passed to `PixelRenderer.presentAt` are on this clock. It is
+ * monotonic and starts near zero when the module is first used.
+ */
+export declare function monotonicTimeMicros(): number
+
 /** Mouse button event. */
 export type MouseButton =
   | { type: 'Left' }
@@ -1171,6 +2140,14 @@ export interface NotSupportedError {
   message: string
 }
 
+/** Where row 0 of a source pixel buffer sits. */
+export declare const enum Origin {
+  /** Row 0 is the top of the image (most image decoders and canvases). */
+  TopLeft = 0,
+  /** Row 0 is the bottom of the image (OpenGL `glReadPixels`, some capture APIs). */
+  BottomLeft = 1
+}
+
 /** OS error. */
 export interface OsError {
   /** The OS error code. */
@@ -1187,6 +2164,27 @@ export declare const enum PageLoadEvent {
   Completed = 1
 }
 
+/** Byte layout of a source pixel buffer. */
+export declare const enum PixelFormat {
+  /** 4 bytes per pixel: red, green, blue, alpha. */
+  Rgba = 0,
+  /** 4 bytes per pixel: blue, green, red, alpha (common for video frames and Windows APIs). */
+  Bgra = 1,
+  /** 3 bytes per pixel: red, green, blue (opaque). */
+  Rgb = 2,
+  /** 3 bytes per pixel: blue, green, red (opaque). */
+  Bgr = 3,
+  /** 1 byte per pixel: gray level, shown as red = green = blue (opaque). */
+  Gray8 = 4,
+  /** 1 byte per pixel: index into the renderer's 256-color palette. */
+  Indexed8 = 5,
+  /**
+   * 4 bytes per pixel: one native-endian 32-bit word per pixel holding
+   * 0xAARRGGBB, as produced by many software renderers.
+   */
+  NativeArgb = 6
+}
+
 /** 2D position. */
 export interface Position {
   /** The X coordinate. */
@@ -1195,6 +2193,35 @@ export interface Position {
   y: number
 }
 
+/** Returns a copy of an RGBA buffer with color channels multiplied by alpha */
+export declare function premultiplyAlpha(buffer: Buffer): Buffer
+
+/** A successful present, passed to the callback registered with [`PixelRenderer::on_present`] */
+export interface PresentInfo {
+  /** Render cache ID of the window that was presented to */
+  windowId: bigint
+  /** Microseconds from the start of drawing to the end of presenting the frame */
+  frameUs?: number
+  /** Number of frames presented to the window, including this one */
+  framesPresented: number
+  /** When the frame was presented, in monotonic milliseconds (see `lastPresentTime`) */
+  presentTime: number
+}
+
+/** Outcome of presenting a frame scheduled for a target time. */
+export declare const enum PresentOutcome {
+  /** Presented at (or just after) the target time. */
+  Presented = 0,
+  /** Presented immediately because the target time had already passed. */
+  Late = 1,
+  /**
+   * Not presented, because the target time passed by more than the allowed
+   * lateness or the window couldn't take a frame (minimized, or within its
+   * minimum present interval).
+   */
+  Dropped = 2
+}
+
 /**
  * Returns the primary monitor information.
  * Returns Some(MonitorInfo) if a primary monitor exists, None on error or
@@ -1241,6 +2268,40 @@ export interface ProxyEndpoint {
   port: number
 }
 
+/**
+ * Native handle of a window returned by `Window.rawHandle()`.
+ *
+ * Only valid while the window is alive: once it is closed or garbage
+ * collected, the handle may dangle or be reused by another window.
+ */
+export interface RawHandleInfo {
+  /** The windowing API the handle belongs to. */
+  kind: RawHandleKind
+  /** The XID, `HWND`, `wl_surface*` or `NSView*`, as an integer. */
+  handle: bigint
+  /**
+   * The connection the handle belongs to: Xlib `Display*`, `xcb_connection_t*`
+   * or `wl_display*` (null on Win32 and AppKit, or when unknown).
+   */
+  display?: bigint
+}
+
+/** Windowing API a raw window handle belongs to. */
+export declare const enum RawHandleKind {
+  /** X11 through Xlib: the handle is the window's XID. */
+  Xlib = 0,
+  /** X11 through XCB: the handle is the window's XID. */
+  Xcb = 1,
+  /** Wayland: the handle is a `wl_surface*`. */
+  Wayland = 2,
+  /** Win32: the handle is the `HWND`. */
+  Win32 = 3,
+  /** macOS AppKit: the handle is the window's `NSView*`. */
+  AppKit = 4,
+  /** Any other windowing API; the handle is 0. */
+  Other = 5
+}
+
 /** Raw keyboard event data. */
 export interface RawKeyEvent {
   /** The key code. */
@@ -1271,6 +2332,99 @@ export interface Rectangle {
   size: Size
 }
 
+/** Returns the number of windows with cached render state */
+export declare function renderCacheLen(): number
+
+/**
+ * Reports how many windows have cached render state and roughly how much
+ * memory their surfaces hold
+ *
+ * A count that keeps growing as windows open and close points at windows
+ * closed without releasing their state.
+ */
+export declare function renderCacheStats(): CacheStats
+
+/**
+ * Describes the window's render surface, for attaching to bug reports
+ *
+ * Fields other than `cached` are null until something has been rendered to the window.
+ */
+export declare function rendererInfo(window: Window): RendererInfo
+
+/** Diagnostics about a window's render surface, returned by [`renderer_info`] */
+export interface RendererInfo {
+  /** Whether the window has a cached render surface (false until the first render) */
+  cached: boolean
+  /** Graphics API presenting the frames, e.g. "vulkan", "metal", "dx12" or "gl" */
+  backend?: string
+  /** Name of the adapter (GPU or software rasterizer) behind the surface */
+  adapterName?: string
+  /** Whether the adapter renders in software on the CPU */
+  software?: boolean
+  /** Window width the surface was last sized for */
+  lastWindowWidth?: number
+  /** Window height the surface was last sized for */
+  lastWindowHeight?: number
+}
+
+/** Why a render call failed, exposed to JavaScript as the error's `code` */
+export declare enum RenderError {
+  /** The window was never created or has been destroyed. */
+  WindowNotInitialized = 'WindowNotInitialized',
+  /** The buffer length doesn't match the renderer's dimensions, stride and format. */
+  BufferSizeMismatch = 'BufferSizeMismatch',
+  /**
+   * The renderer can't draw buffers in its current configuration (e.g.
+   * `Indexed8` without a palette, or a stride shorter than a row).
+   */
+  InvalidConfiguration = 'InvalidConfiguration',
+  /** An argument other than the buffer is invalid (e.g. a draw command). */
+  InvalidArgument = 'InvalidArgument',
+  /** A window or render cache lock was poisoned by a panic on another thread. */
+  LockPoisoned = 'LockPoisoned',
+  /** The window has no render surface and none can be created from this call. */
+  SurfaceUnavailable = 'SurfaceUnavailable',
+  /** Creating the window's render surface failed. */
+  SurfaceCreationFailed = 'SurfaceCreationFailed',
+  /**
+   * Presenting the frame failed, usually transiently (e.g. the surface was
+   * lost or outdated); retrying may succeed.
+   */
+  PresentFailed = 'PresentFailed',
+  /** The window was resized but its surface wasn't, with manual resizing enabled. */
+  SurfaceSizeMismatch = 'SurfaceSizeMismatch',
+  /** The image file to render doesn't exist. */
+  FileNotFound = 'FileNotFound',
+  /** The image file couldn't be read or decoded. */
+  DecodeFailed = 'DecodeFailed',
+  /** Nothing has been rendered to the window yet, so there is no frame to read back. */
+  NothingRendered = 'NothingRendered'
+}
+
+/** Where the scaled buffer lands in a window, returned by [`PixelRenderer::layout`] */
+export interface RenderLayout {
+  /** Window X of the image's left edge (including the viewport offset) */
+  offsetX: number
+  /** Window Y of the image's top edge (including the viewport offset) */
+  offsetY: number
+  /** Width of the scaled image in window pixels */
+  scaledWidth: number
+  /** Height of the scaled image in window pixels */
+  scaledHeight: number
+  /**
+   * Total width of the bars left and right of the image, 0 when the image
+   * spans the window (or viewport) horizontally
+   *
+   * Centered images have half of it on each side.
+   */
+  letterboxX: number
+  /**
+   * Total height of the bars above and below the image, 0 when the image
+   * spans the window (or viewport) vertically
+   */
+  letterboxY: number
+}
+
 /** Render options for pixel buffer display */
 export interface RenderOptions {
   /** Width of the source buffer in pixels */
@@ -1279,8 +2433,189 @@ export interface RenderOptions {
   bufferHeight: number
   /** Scaling mode (default: Fit) */
   scaleMode?: ScaleMode
-  /** Background color for letterboxing [R, G, B, A] (default: [0, 0, 0, 255]) */
+  /** Background color for letterboxing, [R, G, B] or [R, G, B, A] (default: [0, 0, 0, 255]) */
   backgroundColor?: Array<number>
+  /**
+   * Background color as one 0xRRGGBBAA number, e.g. 0x202020ff (default: none)
+   *
+   * Takes precedence over `background_color` when both are set.
+   */
+  backgroundColorRgba?: number
+  /** Dithering used when quantizing high-precision samples to 8 bits (default: None) */
+  dither?: DitherMode
+  /**
+   * Row order of the source buffer (default: TopLeft)
+   *
+   * Use `BottomLeft` for buffers read back from OpenGL, which start at the bottom row.
+   */
+  origin?: Origin
+  /**
+   * Pick sampling per frame based on motion (default: false)
+   *
+   * Fast-changing frames use cheap nearest sampling, while near-static frames
+   * use bilinear filtering where detail is visible.
+   */
+  adaptiveQuality?: boolean
+  /** Record per-stage timings into `RenderStats` (default: false) */
+  detailedTiming?: boolean
+  /**
+   * Relative aspect ratio difference within which `ScaleMode.Auto` stretches
+   * instead of fitting (default: 0.05)
+   */
+  autoAspectTolerance?: number
+  /** How filters resolve samples that fall outside the buffer (default: Clamp) */
+  wrapMode?: WrapMode
+  /** Byte layout of source buffers (default: Rgba) */
+  pixelFormat?: PixelFormat
+  /**
+   * Sampling filter for scaled copies (default: Nearest)
+   *
+   * Ignored while adaptive quality is enabled, which picks the filter per frame.
+   */
+  filterMode?: FilterMode
+  /**
+   * How alpha is written to the window (default: Straight)
+   *
+   * Non-opaque modes only show through where the platform surface supports
+   * transparency (layered windows, compositing X11 window managers).
+   */
+  alphaMode?: AlphaMode
+  /**
+   * How the buffer combines with what the window already shows (default: Replace)
+   *
+   * `SourceOver` skips the background clear and blends the buffer over the
+   * previous frame by its alpha, for overlays.
+   */
+  compositeMode?: CompositeMode
+  /**
+   * Window pixels per buffer pixel before the scale mode applies (default: 1.0)
+   *
+   * Set to the window's `scaleFactor()` for buffers authored at logical
+   * resolution, so they keep their size on HiDPI displays (e.g. with `ScaleMode.None`).
+   */
+  scaleFactor?: number
+  /**
+   * Window position of the first tile's top-left corner for `ScaleMode.Tile`
+   * and `ScaleMode.IntegerTile`,
+   * as [x, y] (default: [0, 0])
+   *
+   * Animate it to scroll the pattern.
+   */
+  tileOffset?: Array<number>
+  /** Mirror the buffer left to right (default: false) */
+  flipHorizontal?: boolean
+  /** Mirror the buffer top to bottom, on top of the origin's row order (default: false) */
+  flipVertical?: boolean
+  /**
+   * Clockwise rotation of the buffer, applied after flips and before scaling
+   * (default: None)
+   */
+  rotation?: Rotation
+  /**
+   * Gamma applied to color channels as they are copied; above 1 lifts the
+   * midtones (default: none)
+   */
+  gamma?: number
+  /** Multiplier applied to color channels after gamma (default: none) */
+  brightness?: number
+  /**
+   * Color multiplied into every buffer pixel as it is copied, [R, G, B] or
+   * [R, G, B, A]; each channel is scaled by `tint / 255` (default: none)
+   *
+   * E.g. [255, 128, 128] tints toward red and [128, 128, 128] darkens by half.
+   */
+  tint?: Array<number>
+  /**
+   * Bytes from the start of one source row to the next, for buffers with
+   * padded rows (default: buffer_width * bytes per pixel)
+   */
+  sourceStrideBytes?: number
+  /**
+   * Where the image sits in the window when it doesn't fill it, for the Fit,
+   * Integer, IntegerFit, None, FitWidth and FitHeight scale modes (default: Center)
+   */
+  anchor?: Anchor
+  /**
+   * Largest aspect distortion `ScaleMode.Stretch` may apply before
+   * letterboxing the rest, e.g. 0.1 for 10% (default: unlimited)
+   *
+   * The skew is `max(sx, sy) / min(sx, sy) - 1` for the horizontal and vertical
+   * scales `sx` and `sy`. Beyond the limit, the larger scale is reduced to
+   * `min(sx, sy) * (1 + max_aspect_skew)` and the image is centered.
+   */
+  maxAspectSkew?: number
+  /**
+   * Round the image edges from their exact positions so opposite bars differ
+   * by at most one pixel and stay put while resizing (default: false)
+   *
+   * Applies to centered `Fit`, which otherwise truncates the image size and
+   * puts the leftover pixels on the bottom/right bar, and to the crop of
+   * `Fill`. Off by default to keep existing pixel-exact layouts.
+   */
+  balancedLetterbox?: boolean
+  /**
+   * Window sub-rectangle to render into, leaving the rest of the window
+   * untouched (default: whole window)
+   *
+   * The background clear and scaling use the rectangle as the target area, the
+   * same as `setViewport`.
+   */
+  clipRect?: Rect
+  /**
+   * Margin of background left inside the window (or clip rectangle) on each
+   * side, as [top, right, bottom, left] in window pixels (default: [0, 0, 0, 0])
+   *
+   * Every scale mode lays the image out within the remaining rectangle, while
+   * the background fill still covers the margin.
+   */
+  padding?: Array<number>
+  /**
+   * Part of the buffer to display, in buffer pixels following the origin
+   * (default: whole buffer)
+   *
+   * Only this rectangle is sampled, and the scale modes treat its size as the
+   * buffer size. Animate it to pan and zoom over a large buffer without copying.
+   * Must lie within the buffer.
+   */
+  sourceRect?: Rect
+  /**
+   * Checkerboard drawn instead of the solid background color, so the edge of
+   * the image stays visible (default: solid background color)
+   */
+  letterboxCheckerboard?: Checkerboard
+  /**
+   * Leave resizing the surface to `resizeSurface()` (default: false)
+   *
+   * Renders then fail with `SurfaceSizeMismatch` instead of resizing the
+   * surface when the window size changed, for embedders that control when
+   * resizes happen.
+   */
+  manualResize?: boolean
+  /**
+   * Where buffers are scaled (default: Cpu)
+   *
+   * `Gpu` needs the `gpu` build feature and falls back to the CPU when the
+   * adapter can't create the pipeline or the configuration needs the CPU path.
+   * Only `render` and `renderWithDimensions` use the GPU.
+   */
+  backend?: Backend
+  /**
+   * Milliseconds the window size must stay unchanged before the surface is
+   * resized to it (default: 0, resize immediately)
+   *
+   * While a drag-resize is in progress, frames are composed at the previous
+   * surface size and scaled to the window, then snap to the exact size once
+   * the window has been stable for this long.
+   */
+  resizeDebounceMs?: number
+  /**
+   * Skip presenting a frame identical to the one the window shows (default: false)
+   *
+   * Frames are compared by a hash of the buffer, or by the `frameId` passed to
+   * `render`. A resized window is always presented again. Changing the
+   * renderer's settings doesn't count as a change; pass a new frame ID.
+   */
+  skipDuplicateFrames?: boolean
 }
 
 /**
@@ -1295,6 +2630,36 @@ export interface RenderOptions {
  */
 export declare function renderPixels(window: Window, buffer: Buffer, bufferWidth: number, bufferHeight: number): void
 
+/** Statistics about presents to a window, returned by [`PixelRenderer::stats`] */
+export interface RenderStats {
+  /**
+   * Microseconds spent clearing to the background color in the last frame
+   * (only recorded with detailed timing)
+   */
+  clearUs?: number
+  /**
+   * Microseconds spent copying and scaling the buffer in the last frame
+   * (only recorded with detailed timing)
+   */
+  copyUs?: number
+  /**
+   * Microseconds spent in the platform present call in the last frame
+   * (only recorded with detailed timing)
+   */
+  presentUs?: number
+  /**
+   * Microseconds from the start of drawing to the end of presenting the last
+   * frame, including any wait for a scheduled present
+   */
+  lastFrameUs?: number
+  /** Average of `last_frame_us` over recent frames */
+  avgFrameUs?: number
+  /** Presents per second over recent frames (needs at least two presents) */
+  fps?: number
+  /** Number of frames presented to the window */
+  framesPresented: number
+}
+
 /** A responder for a request. */
 export interface RequestAsyncResponder {
   /** The URI of the request. */
@@ -1325,6 +2690,18 @@ export declare const enum ResizeDirection {
   West = 7
 }
 
+/** Clockwise rotation applied to a source buffer before scaling. */
+export declare const enum Rotation {
+  /** Upright. */
+  None = 0,
+  /** Rotated a quarter turn clockwise (width and height swap). */
+  Cw90 = 1,
+  /** Upside down. */
+  Cw180 = 2,
+  /** Rotated a quarter turn counterclockwise (width and height swap). */
+  Cw270 = 3
+}
+
 /** Window scale factor change details. */
 export interface ScaleFactorChangeDetails {
   /** The new scale factor. */
@@ -1344,9 +2721,62 @@ export declare const enum ScaleMode {
   /** Integer scaling for pixel-perfect rendering. */
   Integer = 3,
   /** No scaling - keep original size (centered). */
-  None = 4
+  None = 4,
+  /**
+   * Stretch when the buffer and window aspect ratios are within the auto
+   * tolerance, otherwise Fit.
+   */
+  Auto = 5,
+  /**
+   * Fit when the buffer is larger than the window in either dimension,
+   * otherwise Stretch (downscale with letterbox, upscale to fill).
+   */
+  FitDownStretchUp = 6,
+  /** Repeat the buffer unscaled across the window, starting at the tile offset. */
+  Tile = 7,
+  /**
+   * Largest integer scale that fits the window, letterboxed like Fit. Buffers
+   * larger than the window shrink by an integer divisor instead of being cropped.
+   */
+  IntegerFit = 8,
+  /**
+   * Maintain aspect ratio and fill the window's width; the height is
+   * letterboxed or cropped.
+   */
+  FitWidth = 9,
+  /**
+   * Maintain aspect ratio and fill the window's height; the width is
+   * pillarboxed or cropped.
+   */
+  FitHeight = 10,
+  /**
+   * Repeat the buffer across the window at the largest integer scale that
+   * fits it once, starting at the tile offset, so every window pixel shows
+   * exactly one buffer pixel.
+   */
+  IntegerTile = 11
 }
 
+/**
+ * Parses a scale mode name from a config, e.g. "fit" or "FitDownStretchUp"
+ *
+ * Case and word separators ("-", "_", spaces) are ignored. Fails for unknown names.
+ */
+export declare function scaleModeFromString(name: string): ScaleMode
+
+/** Returns the canonical config name of a scale mode, e.g. "fit-down-stretch-up" */
+export declare function scaleModeToString(mode: ScaleMode): string
+
+/**
+ * Puts an RGBA image on the clipboard
+ *
+ * # Arguments
+ * * `buffer` - RGBA pixels (must be width * height * 4 bytes)
+ * * `width` - Width of the image in pixels
+ * * `height` - Height of the image in pixels
+ */
+export declare function setClipboardImage(buffer: Buffer, width: number, height: number): void
+
 /** 2D size. */
 export interface Size {
   /** The width. */
@@ -1364,6 +2794,18 @@ export declare const enum StartCause {
   Init = 4
 }
 
+/**
+ * Returns the names of the supported source pixel formats, as members of
+ * `PixelFormat` (e.g. `PixelFormat[name]`)
+ */
+export declare function supportedPixelFormats(): Array<string>
+
+/**
+ * Returns the canonical config names of every supported scale mode, e.g. for
+ * building a settings menu; each parses with `scaleModeFromString`
+ */
+export declare function supportedScaleModes(): Array<string>
+
 /** Control flow of the application event loop. */
 export declare const enum TaoControlFlow {
   /** The application will continue running normally. */
@@ -1439,6 +2881,9 @@ export declare const enum TouchPhase {
   Cancelled = 3
 }
 
+/** Returns a copy of a premultiplied RGBA buffer converted back to straight alpha */
+export declare function unpremultiplyAlpha(buffer: Buffer): Buffer
+
 /** User attention type. */
 export declare const enum UserAttentionType {
   Critical = 0,
@@ -1681,6 +3126,16 @@ export declare const enum WindowLevel {
   AlwaysOnBottom = 2
 }
 
+/** Window size and cache key read together, returned by [`PixelRenderer::window_metrics`] */
+export interface WindowMetrics {
+  /** Inner width of the window in physical pixels */
+  width: number
+  /** Inner height of the window in physical pixels */
+  height: number
+  /** Key of the window's render state, for `clearRenderCache` */
+  cacheId: bigint
+}
+
 /** Window options for creating a window. */
 export interface WindowOptions {
   /** The title of window. */
@@ -1715,6 +3170,18 @@ export interface WindowOptions {
   theme?: TaoTheme
 }
 
+/** Outcome of presenting to one window, returned by [`PixelRenderer::render_many`] */
+export interface WindowRenderResult {
+  /** ID of the window (0 if the window wasn't initialized) */
+  windowId: bigint
+  /** Whether the frame was presented (or skipped by the window's frame rate limit) */
+  success: boolean
+  /** Why presenting failed */
+  error?: string
+  /** Code of the failure, to tell transient surface errors from programmer errors */
+  errorCode?: RenderError
+}
+
 /** Window size limits. */
 export interface WindowSizeConstraints {
   /** The minimum width. */
@@ -1727,6 +3194,16 @@ export interface WindowSizeConstraints {
   maxHeight?: number
 }
 
+/** How source coordinates that fall outside the buffer are resolved when sampling. */
+export declare const enum WrapMode {
+  /** Repeat the edge pixels. */
+  Clamp = 0,
+  /** Continue from the opposite edge (seamless tiling). */
+  Wrap = 1,
+  /** Reflect back into the buffer at each edge. */
+  Mirror = 2
+}
+
 /** Theme for the webview. */
 export declare const enum WryTheme {
   /** Light theme. */
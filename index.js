@@ -590,7 +590,7 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { Application, BrowserWindow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget, PixelRenderer, WebContext, Webview, WebView, WebViewBuilder, Window, WindowBuilder, availableMonitors, BackgroundThrottlingPolicy, BadIcon, ControlFlow, CursorIcon, DeviceEventFilter, DragDropEvent, ElementState, Error, FullscreenType, getWebviewVersion, ImeState, Key, KeyCode, KeyLocation, ModifiersState, MouseButtonState, NewWindowResponse, PageLoadEvent, primaryMonitor, ProgressBarStatus, ProgressState, renderPixels, ResizeDirection, ScaleMode, StartCause, TaoControlFlow, TaoFullscreenType, TaoTheme, taoVersion, Theme, TouchPhase, UserAttentionType, WebviewApplicationEvent, webviewVersion, WindowEvent, WindowLevel, WryTheme } = nativeBinding
+const { Application, BrowserWindow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget, PixelRenderer, RenderHandle, WebContext, Webview, WebView, WebViewBuilder, Window, WindowBuilder, AlphaMode, Anchor, availableMonitors, availableScalers, Backend, BackgroundThrottlingPolicy, BadIcon, cachedRenderWindows, clearRenderCache, clearRenderCaches, CompositeMode, ControlFlow, CursorIcon, DeviceEventFilter, DitherMode, DragDropEvent, DrawCommandKind, ElementState, Error, FilterMode, flushRenderThread, forceX11Backend, FullscreenType, getClipboardImage, getWebviewVersion, ImeState, isTransientRenderError, Key, KeyCode, KeyLocation, ModifiersState, monitors, monotonicTimeMicros, MouseButtonState, NewWindowResponse, Origin, PageLoadEvent, PixelFormat, premultiplyAlpha, PresentOutcome, primaryMonitor, ProgressBarStatus, ProgressState, RawHandleKind, renderCacheLen, renderCacheStats, rendererInfo, RenderError, renderPixels, ResizeDirection, Rotation, ScaleMode, scaleModeFromString, scaleModeToString, setClipboardImage, StartCause, supportedPixelFormats, supportedScaleModes, TaoControlFlow, TaoFullscreenType, TaoTheme, taoVersion, Theme, TouchPhase, unpremultiplyAlpha, UserAttentionType, WebviewApplicationEvent, webviewVersion, WindowEvent, WindowLevel, WrapMode, WryTheme } = nativeBinding
 export { Application }
 export { BrowserWindow }
 export { EventLoop }
@@ -598,47 +598,82 @@ export { EventLoopBuilder }
 export { EventLoopProxy }
 export { EventLoopWindowTarget }
 export { PixelRenderer }
+export { RenderHandle }
 export { WebContext }
 export { Webview }
 export { WebView }
 export { WebViewBuilder }
 export { Window }
 export { WindowBuilder }
+export { AlphaMode }
+export { Anchor }
 export { availableMonitors }
+export { availableScalers }
+export { Backend }
 export { BackgroundThrottlingPolicy }
 export { BadIcon }
+export { cachedRenderWindows }
+export { clearRenderCache }
+export { clearRenderCaches }
+export { CompositeMode }
 export { ControlFlow }
 export { CursorIcon }
 export { DeviceEventFilter }
+export { DitherMode }
 export { DragDropEvent }
+export { DrawCommandKind }
 export { ElementState }
 export { Error }
+export { FilterMode }
+export { flushRenderThread }
+export { forceX11Backend }
 export { FullscreenType }
+export { getClipboardImage }
 export { getWebviewVersion }
 export { ImeState }
+export { isTransientRenderError }
 export { Key }
 export { KeyCode }
 export { KeyLocation }
 export { ModifiersState }
+export { monitors }
+export { monotonicTimeMicros }
 export { MouseButtonState }
 export { NewWindowResponse }
+export { Origin }
 export { PageLoadEvent }
+export { PixelFormat }
+export { premultiplyAlpha }
+export { PresentOutcome }
 export { primaryMonitor }
 export { ProgressBarStatus }
 export { ProgressState }
+export { RawHandleKind }
+export { renderCacheLen }
+export { renderCacheStats }
+export { rendererInfo }
+export { RenderError }
 export { renderPixels }
 export { ResizeDirection }
+export { Rotation }
 export { ScaleMode }
+export { scaleModeFromString }
+export { scaleModeToString }
+export { setClipboardImage }
 export { StartCause }
+export { supportedPixelFormats }
+export { supportedScaleModes }
 export { TaoControlFlow }
 export { TaoFullscreenType }
 export { TaoTheme }
 export { taoVersion }
 export { Theme }
 export { TouchPhase }
+export { unpremultiplyAlpha }
 export { UserAttentionType }
 export { WebviewApplicationEvent }
 export { webviewVersion }
 export { WindowEvent }
 export { WindowLevel }
+export { WrapMode }
 export { WryTheme }
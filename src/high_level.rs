@@ -48,14 +48,21 @@ use tao::platform::windows::WindowBuilderExtWindows;
 /// GTK3 / webkit2gtk-4.1 stack used here. We therefore no longer force the X11
 /// backend and let GTK auto-detect Wayland, which gives native Wayland support.
 ///
-/// Users who still need the X11 backend (e.g. to run under XWayland) can opt in by
-/// setting `WEBVIEW_NAPI_PREFER_X11=1`, or by setting `GDK_BACKEND` directly. An
-/// explicit `GDK_BACKEND` is always respected and never overridden.
+/// Users who still need the X11 backend (e.g. to run under XWayland) can opt in on
+/// any runtime by setting `WEBVIEW_NAPI_PREFER_X11=1`, or by setting `GDK_BACKEND`
+/// directly. An explicit `GDK_BACKEND` is always respected and never overridden.
 #[cfg(target_os = "linux")]
 pub(crate) fn apply_runtime_gl_workaround() {
   use std::sync::Once;
   static INIT: Once = Once::new();
   INIT.call_once(|| {
+    // The X11 opt-in applies to every runtime, not just the GL workaround below
+    if std::env::var_os("WEBVIEW_NAPI_PREFER_X11").is_some()
+      && std::env::var_os("WAYLAND_DISPLAY").is_some()
+    {
+      crate::tao::platform::pin_x11_backend();
+    }
+
     let running_under_other_runtime = std::fs::read_link("/proc/self/exe")
       .ok()
       .and_then(|p| {
@@ -79,22 +86,14 @@ pub(crate) fn apply_runtime_gl_workaround() {
         changed = true;
       }
 
-      // Backend selection. When no backend is pinned by the user:
-      // - If `WEBVIEW_NAPI_PREFER_X11` is set, force X11 (runs under XWayland).
-      // - Otherwise, explicitly select the native Wayland backend when a Wayland
-      //   session is detected, so GTK doesn't fall back to X11/XWayland when both
-      //   `WAYLAND_DISPLAY` and `DISPLAY` are present. This is only done when the
-      //   user has not set `GDK_BACKEND` themselves.
-      if std::env::var_os("GDK_BACKEND").is_none() {
-        if std::env::var_os("WEBVIEW_NAPI_PREFER_X11").is_some() {
-          if std::env::var_os("WAYLAND_DISPLAY").is_some() {
-            std::env::set_var("GDK_BACKEND", "x11");
-            changed = true;
-          }
-        } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
-          std::env::set_var("GDK_BACKEND", "wayland");
-          changed = true;
-        }
+      // Backend selection. When no backend is pinned (by the user or the X11
+      // opt-in above), explicitly select the native Wayland backend when a
+      // Wayland session is detected, so GTK doesn't fall back to X11/XWayland
+      // when both `WAYLAND_DISPLAY` and `DISPLAY` are present.
+      if std::env::var_os("GDK_BACKEND").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_some()
+      {
+        std::env::set_var("GDK_BACKEND", "wayland");
+        changed = true;
       }
 
       // Disable the WebKit DMABUF renderer. This is a no-op on the current GTK3
@@ -296,13 +295,6 @@ impl Application {
     // before any WebKit/GTK initialization happens.
     apply_runtime_gl_workaround();
 
-    // Force X11 backend on Linux before creating the event loop
-    // This must happen BEFORE the event loop is created to prevent Wayland protocol errors
-    #[cfg(target_os = "linux")]
-    {
-      let _ = crate::tao::platform::platform_info();
-    }
-
     let event_loop = tao::event_loop::EventLoop::new();
     let event_loop_proxy = event_loop.create_proxy();
     Self {
@@ -423,10 +415,7 @@ impl Application {
 
       if let Ok(window) = builder.build(event_loop_target) {
         let mut handle = win_handle.lock().unwrap();
-        *handle = Some(crate::tao::structs::Window {
-          #[allow(clippy::arc_with_non_send_sync)]
-          inner: Some(Arc::new(Mutex::new(window))),
-        });
+        *handle = Some(crate::tao::structs::Window::from_tao(window));
         drop(handle);
 
         // Retain a strong reference so the native window survives even if the
@@ -545,6 +534,13 @@ impl Application {
         app_ref.process_pending_items(event_loop_target);
 
         match event {
+          tao::event::Event::WindowEvent {
+            window_id,
+            event: tao::event::WindowEvent::Moved(position),
+            ..
+          } => {
+            crate::tao::structs::dispatch_window_moved(event_loop_target, window_id, position);
+          }
           // Handle redraw requests to ensure window content is painted
           tao::event::Event::RedrawRequested(_) => {
             // Window content will be drawn by the window's renderer
@@ -611,6 +607,13 @@ impl Application {
         app_ref.process_pending_items(event_loop_target);
 
         match event {
+          tao::event::Event::WindowEvent {
+            window_id,
+            event: tao::event::WindowEvent::Moved(position),
+            ..
+          } => {
+            crate::tao::structs::dispatch_window_moved(event_loop_target, window_id, position);
+          }
           tao::event::Event::WindowEvent {
             event: tao::event::WindowEvent::CloseRequested,
             ..
@@ -828,7 +831,18 @@ impl BrowserWindow {
   }
 
   #[napi]
-  pub fn set_progress_bar(&self, _state: ProgressBarState) {}
+  pub fn set_progress_bar(&self, state: ProgressBarState) {
+    if let Some(win) = self.inner.lock().unwrap().as_ref() {
+      let status = match state.status {
+        ProgressBarStatus::None => crate::tao::enums::ProgressState::None,
+        ProgressBarStatus::Normal => crate::tao::enums::ProgressState::Normal,
+        ProgressBarStatus::Indeterminate => crate::tao::enums::ProgressState::Indeterminate,
+        ProgressBarStatus::Paused => crate::tao::enums::ProgressState::Paused,
+        ProgressBarStatus::Error => crate::tao::enums::ProgressState::Error,
+      };
+      let _ = win.set_progress_bar(status, Some(state.progress / 100.0));
+    }
+  }
 
   #[napi]
   pub fn set_maximized(&self, value: bool) {
@@ -26,23 +26,42 @@ pub use wry::structs::{
 pub use wry::types::{Result, WebViewId, RGBA};
 
 // Re-export tao types
+pub use tao::cursor::CursorFrame;
 pub use tao::enums::{
-  CursorIcon, DeviceEvent, ElementState, Force, Key, KeyCode, KeyLocation, ModifiersState,
-  MouseButton, MouseButtonState, ProgressState, ResizeDirection, ScaleMode, StartCause,
-  TaoControlFlow, TaoFullscreenType, TaoTheme, TouchPhase, UserAttentionType, WindowEvent,
+  AlphaMode, Anchor, Backend, CompositeMode, CursorIcon, DeviceEvent, DitherMode, DrawCommandKind,
+  ElementState, FilterMode, Force, Key, KeyCode, KeyLocation, ModifiersState, MouseButton,
+  MouseButtonState, Origin, PixelFormat, PresentOutcome, ProgressState, RawHandleKind,
+  ResizeDirection, Rotation, ScaleMode, StartCause, TaoControlFlow, TaoFullscreenType, TaoTheme,
+  TouchPhase, UserAttentionType, WindowEvent, WrapMode,
 };
-pub use tao::functions::{available_monitors, primary_monitor, tao_version};
+pub use tao::functions::{available_monitors, monitors, primary_monitor, tao_version};
+pub use tao::platform::force_x11_backend;
 pub use tao::structs::{
   CursorPosition, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget, GestureEvent,
-  HiDpiScaling, Icon, KeyboardEvent, MonitorInfo, MouseEvent, NotSupportedError, OsError, Position,
-  RawKeyEvent, Rectangle, ResizeDetails, ScaleFactorChangeDetails, Size, TaoProgressBar,
-  ThemeChangeDetails, Touch, VideoMode, Window, WindowAttributes, WindowBuilder, WindowDragOptions,
-  WindowJumpOptions, WindowOptions, WindowSizeConstraints,
+  HiDpiScaling, Icon, KeyboardEvent, MonitorInfo, MonitorLayout, MouseEvent, NotSupportedError,
+  OsError, Position, RawHandleInfo, RawKeyEvent, Rectangle, ResizeDetails,
+  ScaleFactorChangeDetails, Size, TaoProgressBar, ThemeChangeDetails, Touch, VideoMode, Window,
+  WindowAttributes, WindowBuilder, WindowDragOptions, WindowJumpOptions, WindowOptions,
+  WindowSizeConstraints,
 };
 pub use tao::types::{AxisId, ButtonId, DeviceId, Result as TaoResult, WindowId, RGBA as TaoRGBA};
 
 // Re-export render types
-pub use tao::render::{render_pixels, PixelRenderer, RenderOptions};
+pub use tao::render::{
+  alpha::{premultiply_alpha, unpremultiply_alpha},
+  cached_render_windows, clear_render_cache, clear_render_caches,
+  clipboard::{get_clipboard_image, set_clipboard_image, ClipboardImage},
+  display_list::DrawCommand,
+  error::{is_transient_render_error, RenderError},
+  flush_render_thread,
+  pixel_format::supported_pixel_formats,
+  render_cache_len, render_cache_stats, render_pixels, renderer_info,
+  scaler::available_scalers,
+  scaling::{scale_mode_from_string, scale_mode_to_string, supported_scale_modes},
+  schedule::monotonic_time_micros,
+  CacheStats, Checkerboard, PixelRenderer, PresentInfo, RenderHandle, RenderLayout, RenderOptions,
+  RenderStats, RendererInfo, WindowMetrics, WindowRenderResult,
+};
 
 // High-level API adapter
 pub mod high_level;
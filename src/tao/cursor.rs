@@ -0,0 +1,225 @@
+//! Animated cursors
+//!
+//! Cycles a window's cursor through RGBA frames on a native timer, e.g. for
+//! spinners in apps that render their own loading states. Only Linux (GTK) is
+//! supported for now.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use platform::CursorAnimation;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// One frame of an animated cursor
+#[napi(object)]
+pub struct CursorFrame {
+  /// RGBA pixels (width * height * 4 bytes)
+  pub rgba: Buffer,
+  /// Width in pixels
+  pub width: u32,
+  /// Height in pixels
+  pub height: u32,
+}
+
+/// Validates cursor frames and the frame rate, returning the frame interval
+pub(crate) fn frame_interval(
+  frames: &[CursorFrame],
+  fps: f64,
+) -> napi::Result<std::time::Duration> {
+  if frames.is_empty() {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      "Animated cursor needs at least one frame".to_string(),
+    ));
+  }
+  // Rates so low that the interval overflows a Duration are rejected too
+  let interval = (fps.is_finite() && fps > 0.0)
+    .then(|| std::time::Duration::try_from_secs_f64(1.0 / fps).ok())
+    .flatten()
+    .ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Invalid cursor frame rate: {}", fps),
+      )
+    })?;
+  for (i, frame) in frames.iter().enumerate() {
+    let expected_len = frame.width as usize * frame.height as usize * 4;
+    if frame.width == 0 || frame.height == 0 || frame.rgba.len() != expected_len {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Cursor frame {} size mismatch: got {} bytes, expected {} bytes for {}x{}",
+          i,
+          frame.rgba.len(),
+          expected_len,
+          frame.width,
+          frame.height
+        ),
+      ));
+    }
+  }
+  Ok(interval)
+}
+
+thread_local! {
+  /// Running animations by window ID. GTK objects never leave the event loop thread.
+  static ANIMATIONS: RefCell<HashMap<u64, CursorAnimation>> = RefCell::new(HashMap::new());
+}
+
+/// Starts cycling the window's cursor through `frames`, replacing any running animation
+pub(crate) fn start_animation(
+  window: &tao::window::Window,
+  frames: &[CursorFrame],
+  fps: f64,
+  hotspot: (u32, u32),
+) -> napi::Result<()> {
+  let interval = frame_interval(frames, fps)?;
+  let window_id = crate::tao::structs::window_id_to_u64(window.id());
+  // Stop the old animation first so its cleanup doesn't reset the new cursor
+  stop_animation(window_id);
+  let animation = CursorAnimation::start(window, frames, interval, hotspot)?;
+  ANIMATIONS.with(|animations| animations.borrow_mut().insert(window_id, animation));
+  Ok(())
+}
+
+/// Stops the window's cursor animation, returning whether one was running
+pub(crate) fn stop_animation(window_id: u64) -> bool {
+  // Drop outside the borrow since restoring the cursor may re-enter GTK
+  let animation = ANIMATIONS.with(|animations| animations.borrow_mut().remove(&window_id));
+  animation.is_some()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::CursorFrame;
+  use gtk::prelude::WidgetExt;
+  use gtk::{gdk, gdk_pixbuf, glib};
+  use std::time::Duration;
+  use tao::platform::unix::WindowExtUnix;
+
+  /// A running cursor animation; stops and restores the cursor when dropped
+  pub struct CursorAnimation {
+    source: Option<glib::SourceId>,
+    window: Option<gdk::Window>,
+  }
+
+  impl CursorAnimation {
+    pub fn start(
+      window: &tao::window::Window,
+      frames: &[CursorFrame],
+      interval: Duration,
+      hotspot: (u32, u32),
+    ) -> napi::Result<Self> {
+      if !gtk::is_initialized_main_thread() {
+        return Err(napi::Error::new(
+          napi::Status::GenericFailure,
+          "Animated cursors must be set on the event loop thread".to_string(),
+        ));
+      }
+      let gdk_window = window.gtk_window().window().ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          "Window is not realized yet".to_string(),
+        )
+      })?;
+      let display = gdk_window.display();
+      let cursors: Vec<gdk::Cursor> = frames
+        .iter()
+        .map(|frame| {
+          let pixbuf = gdk_pixbuf::Pixbuf::from_bytes(
+            &glib::Bytes::from(&frame.rgba[..]),
+            gdk_pixbuf::Colorspace::Rgb,
+            true,
+            8,
+            frame.width as i32,
+            frame.height as i32,
+            frame.width as i32 * 4,
+          );
+          let x = hotspot.0.min(frame.width - 1) as i32;
+          let y = hotspot.1.min(frame.height - 1) as i32;
+          gdk::Cursor::from_pixbuf(&display, &pixbuf, x, y)
+        })
+        .collect();
+
+      gdk_window.set_cursor(Some(&cursors[0]));
+      let timer_window = gdk_window.clone();
+      let mut index = 0;
+      let source = glib::timeout_add_local(interval, move || {
+        index = (index + 1) % cursors.len();
+        timer_window.set_cursor(Some(&cursors[index]));
+        glib::ControlFlow::Continue
+      });
+
+      Ok(Self {
+        source: Some(source),
+        window: Some(gdk_window),
+      })
+    }
+  }
+
+  impl Drop for CursorAnimation {
+    fn drop(&mut self) {
+      if let Some(source) = self.source.take() {
+        source.remove();
+      }
+      if let Some(window) = self.window.take() {
+        window.set_cursor(None);
+      }
+    }
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+  use super::CursorFrame;
+  use std::time::Duration;
+
+  /// A running cursor animation (unsupported on this platform)
+  pub struct CursorAnimation;
+
+  impl CursorAnimation {
+    pub fn start(
+      _window: &tao::window::Window,
+      _frames: &[CursorFrame],
+      _interval: Duration,
+      _hotspot: (u32, u32),
+    ) -> napi::Result<Self> {
+      Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Animated cursors are not supported on this platform yet".to_string(),
+      ))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn frame(width: u32, height: u32, len: usize) -> CursorFrame {
+    CursorFrame {
+      rgba: vec![0u8; len].into(),
+      width,
+      height,
+    }
+  }
+
+  #[test]
+  fn test_frame_interval_from_fps() {
+    let frames = [frame(2, 2, 16)];
+    assert_eq!(
+      frame_interval(&frames, 4.0).unwrap(),
+      std::time::Duration::from_millis(250)
+    );
+  }
+
+  #[test]
+  fn test_frame_interval_rejects_bad_input() {
+    assert!(frame_interval(&[], 10.0).is_err());
+    assert!(frame_interval(&[frame(2, 2, 16)], 0.0).is_err());
+    assert!(frame_interval(&[frame(2, 2, 16)], f64::NAN).is_err());
+    assert!(frame_interval(&[frame(2, 2, 16)], 1e-20).is_err());
+    assert!(frame_interval(&[frame(2, 2, 16), frame(2, 2, 15)], 10.0).is_err());
+    assert!(frame_interval(&[frame(0, 0, 0)], 10.0).is_err());
+  }
+}
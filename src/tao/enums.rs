@@ -54,7 +54,7 @@ pub enum WindowEvent {
 
 /// Scale mode for rendering when window is resized.
 #[napi]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScaleMode {
   /// Stretch the buffer to fit the window (may distort aspect ratio).
   Stretch,
@@ -66,6 +66,200 @@ pub enum ScaleMode {
   Integer,
   /// No scaling - keep original size (centered).
   None,
+  /// Stretch when the buffer and window aspect ratios are within the auto
+  /// tolerance, otherwise Fit.
+  Auto,
+  /// Fit when the buffer is larger than the window in either dimension,
+  /// otherwise Stretch (downscale with letterbox, upscale to fill).
+  FitDownStretchUp,
+  /// Repeat the buffer unscaled across the window, starting at the tile offset.
+  Tile,
+  /// Largest integer scale that fits the window, letterboxed like Fit. Buffers
+  /// larger than the window shrink by an integer divisor instead of being cropped.
+  IntegerFit,
+  /// Maintain aspect ratio and fill the window's width; the height is
+  /// letterboxed or cropped.
+  FitWidth,
+  /// Maintain aspect ratio and fill the window's height; the width is
+  /// pillarboxed or cropped.
+  FitHeight,
+  /// Repeat the buffer across the window at the largest integer scale that
+  /// fits it once, starting at the tile offset, so every window pixel shows
+  /// exactly one buffer pixel.
+  IntegerTile,
+}
+
+/// Where a letterboxed image sits within the window or viewport.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+  /// Top-left corner.
+  TopLeft,
+  /// Top edge, centered horizontally.
+  Top,
+  /// Top-right corner.
+  TopRight,
+  /// Left edge, centered vertically.
+  Left,
+  /// Centered.
+  Center,
+  /// Right edge, centered vertically.
+  Right,
+  /// Bottom-left corner.
+  BottomLeft,
+  /// Bottom edge, centered horizontally.
+  Bottom,
+  /// Bottom-right corner.
+  BottomRight,
+}
+
+/// Dithering applied when quantizing high-precision samples to 8 bits per channel.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DitherMode {
+  /// Round to the nearest value (may show banding in gradients).
+  None,
+  /// Ordered dithering with a 4x4 Bayer matrix (cheap, order-independent).
+  Ordered,
+  /// Floyd-Steinberg error diffusion (higher quality, processed serially).
+  FloydSteinberg,
+}
+
+/// Sampling filter used when scaling a buffer.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+  /// Nearest neighbor (sharp, blocky when upscaling).
+  Nearest,
+  /// Bilinear interpolation of the four nearest source pixels (smooth).
+  Bilinear,
+  /// Average of all source pixels behind each window pixel when downscaling,
+  /// avoiding the aliasing of skipped pixels (thumbnails); nearest neighbor
+  /// when upscaling.
+  AreaAverage,
+  /// Lanczos-3 windowed sinc when downscaling, keeping more detail than
+  /// `Bilinear` in photo thumbnails; bilinear when upscaling. Many times slower
+  /// than the other filters, so meant for still images rather than video.
+  Lanczos,
+}
+
+/// Kind of primitive in a `PixelRenderer` display list.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawCommandKind {
+  /// Fill a rectangle with a solid color.
+  FillRect,
+  /// Copy an RGBA buffer unscaled.
+  BlitBuffer,
+  /// Draw a one pixel wide line.
+  DrawLine,
+  /// Draw text (not supported yet).
+  DrawText,
+}
+
+/// Where row 0 of a source pixel buffer sits.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+  /// Row 0 is the top of the image (most image decoders and canvases).
+  TopLeft,
+  /// Row 0 is the bottom of the image (OpenGL `glReadPixels`, some capture APIs).
+  BottomLeft,
+}
+
+/// How the alpha channel of rendered frames is written.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+  /// Every pixel is written fully opaque.
+  Opaque,
+  /// Source and background alpha pass through unchanged.
+  Straight,
+  /// Alpha passes through and color channels are multiplied by it, for
+  /// compositors that expect premultiplied alpha.
+  Premultiplied,
+}
+
+/// Where `PixelRenderer` scales buffers.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+  /// Scale on the CPU into a window-sized frame.
+  Cpu,
+  /// Upload the buffer as a texture and scale it on the GPU (needs the `gpu`
+  /// build feature; falls back to `Cpu` otherwise).
+  Gpu,
+}
+
+/// How rendered buffers combine with what the window already shows.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositeMode {
+  /// Clear to the background color and overwrite with the buffer.
+  Replace,
+  /// Keep the current contents and blend the buffer over them by its alpha.
+  SourceOver,
+}
+
+/// Byte layout of a source pixel buffer.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+  /// 4 bytes per pixel: red, green, blue, alpha.
+  Rgba,
+  /// 4 bytes per pixel: blue, green, red, alpha (common for video frames and Windows APIs).
+  Bgra,
+  /// 3 bytes per pixel: red, green, blue (opaque).
+  Rgb,
+  /// 3 bytes per pixel: blue, green, red (opaque).
+  Bgr,
+  /// 1 byte per pixel: gray level, shown as red = green = blue (opaque).
+  Gray8,
+  /// 1 byte per pixel: index into the renderer's 256-color palette.
+  Indexed8,
+  /// 4 bytes per pixel: one native-endian 32-bit word per pixel holding
+  /// 0xAARRGGBB, as produced by many software renderers.
+  NativeArgb,
+}
+
+/// How source coordinates that fall outside the buffer are resolved when sampling.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+  /// Repeat the edge pixels.
+  Clamp,
+  /// Continue from the opposite edge (seamless tiling).
+  Wrap,
+  /// Reflect back into the buffer at each edge.
+  Mirror,
+}
+
+/// Outcome of presenting a frame scheduled for a target time.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentOutcome {
+  /// Presented at (or just after) the target time.
+  Presented,
+  /// Presented immediately because the target time had already passed.
+  Late,
+  /// Not presented, because the target time passed by more than the allowed
+  /// lateness or the window couldn't take a frame (minimized, or within its
+  /// minimum present interval).
+  Dropped,
+}
+
+/// Clockwise rotation applied to a source buffer before scaling.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+  /// Upright.
+  None,
+  /// Rotated a quarter turn clockwise (width and height swap).
+  Cw90,
+  /// Upside down.
+  Cw180,
+  /// Rotated a quarter turn counterclockwise (width and height swap).
+  Cw270,
 }
 
 /// Mouse button event.
@@ -644,6 +838,24 @@ pub enum ResizeDirection {
   West,
 }
 
+/// Windowing API a raw window handle belongs to.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawHandleKind {
+  /// X11 through Xlib: the handle is the window's XID.
+  Xlib,
+  /// X11 through XCB: the handle is the window's XID.
+  Xcb,
+  /// Wayland: the handle is a `wl_surface*`.
+  Wayland,
+  /// Win32: the handle is the `HWND`.
+  Win32,
+  /// macOS AppKit: the handle is the window's `NSView*`.
+  AppKit,
+  /// Any other windowing API; the handle is 0.
+  Other,
+}
+
 /// User attention type.
 #[napi]
 pub enum UserAttentionType {
@@ -4,7 +4,8 @@
 
 use napi_derive::napi;
 
-use crate::tao::structs::MonitorInfo;
+use crate::tao::platform::platform_info;
+use crate::tao::structs::{MonitorInfo, MonitorLayout};
 
 /// Returns the current version of the tao crate.
 /// This value is extracted from Cargo.lock at compile time.
@@ -26,7 +27,6 @@ fn try_create_event_loop() -> Option<tao::event_loop::EventLoop<()>> {
   }
 
   let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-    crate::tao::platform::platform_info();
     tao::event_loop::EventLoop::<()>::new()
   }));
 
@@ -45,18 +45,7 @@ fn try_create_event_loop() -> Option<tao::event_loop::EventLoop<()>> {
 pub fn primary_monitor() -> Option<MonitorInfo> {
   let event_loop = try_create_event_loop()?;
   let monitor = event_loop.primary_monitor()?;
-  Some(MonitorInfo {
-    name: monitor.name(),
-    size: crate::tao::structs::Size {
-      width: monitor.size().width as f64,
-      height: monitor.size().height as f64,
-    },
-    position: crate::tao::structs::Position {
-      x: monitor.position().x as f64,
-      y: monitor.position().y as f64,
-    },
-    scale_factor: monitor.scale_factor(),
-  })
+  Some(monitor_info(&monitor, Some(true)))
 }
 
 /// Returns a list of all available monitors.
@@ -67,23 +56,49 @@ pub fn available_monitors() -> Vec<MonitorInfo> {
   let Some(event_loop) = try_create_event_loop() else {
     return Vec::new();
   };
+  // Wayland has no notion of a primary monitor
+  let primary = event_loop.primary_monitor();
   event_loop
     .available_monitors()
-    .map(|m| MonitorInfo {
-      name: m.name(),
-      size: crate::tao::structs::Size {
-        width: m.size().width as f64,
-        height: m.size().height as f64,
-      },
-      position: crate::tao::structs::Position {
-        x: m.position().x as f64,
-        y: m.position().y as f64,
-      },
-      scale_factor: m.scale_factor(),
+    .map(|m| {
+      let is_primary = primary.as_ref().map(|primary| *primary == m);
+      monitor_info(&m, is_primary)
     })
     .collect()
 }
 
+/// Returns the monitor layout along with the display server it was read from.
+/// Positions and sizes are in physical pixels on the virtual desktop, so a
+/// window can be placed on a given monitor before it is created. Wayland doesn't
+/// let clients position windows, which `supportsPositioning` reports; monitor
+/// positions there may not reflect the real arrangement.
+/// The monitor list is empty when monitors cannot be queried (see `availableMonitors`).
+#[napi]
+pub fn monitors() -> MonitorLayout {
+  let platform = platform_info();
+  MonitorLayout {
+    display_server: platform.display_server.name().to_string(),
+    supports_positioning: platform.supports_positioning,
+    monitors: available_monitors(),
+  }
+}
+
+fn monitor_info(monitor: &tao::monitor::MonitorHandle, is_primary: Option<bool>) -> MonitorInfo {
+  MonitorInfo {
+    name: monitor.name(),
+    size: crate::tao::structs::Size {
+      width: monitor.size().width as f64,
+      height: monitor.size().height as f64,
+    },
+    position: crate::tao::structs::Position {
+      x: monitor.position().x as f64,
+      y: monitor.position().y as f64,
+    },
+    scale_factor: monitor.scale_factor(),
+    is_primary,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -2,12 +2,28 @@
 //!
 //! This module contains all N-API bindings for tao types, structs, enums, and functions.
 
+pub mod cursor;
 pub mod enums;
 pub mod functions;
 pub mod platform;
 pub mod render;
+pub mod shape;
 pub mod structs;
 pub mod types;
 
 // Re-export render module items for backward compatibility
-pub use render::{render_pixels, PixelRenderer, RenderOptions};
+pub use render::{
+  alpha::{premultiply_alpha, unpremultiply_alpha},
+  cached_render_windows, clear_render_cache, clear_render_caches,
+  clipboard::{get_clipboard_image, set_clipboard_image, ClipboardImage},
+  display_list::DrawCommand,
+  error::{is_transient_render_error, RenderError},
+  flush_render_thread,
+  pixel_format::supported_pixel_formats,
+  render_cache_len, render_cache_stats, render_pixels, renderer_info,
+  scaler::available_scalers,
+  scaling::{scale_mode_from_string, scale_mode_to_string, supported_scale_modes},
+  schedule::monotonic_time_micros,
+  CacheStats, Checkerboard, PixelRenderer, PresentInfo, RenderHandle, RenderLayout, RenderOptions,
+  RenderStats, RendererInfo, WindowMetrics, WindowRenderResult,
+};
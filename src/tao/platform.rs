@@ -1,8 +1,11 @@
 //! Platform detection and utilities
 //!
 //! This module provides utilities for detecting the current display server
-//! and platform-specific configurations.
+//! and platform-specific configurations. Detection only reads the environment
+//! (and, once GTK is running, whether an X11 compositor is); changing it (e.g.
+//! [`force_x11_backend`]) is always an explicit opt-in.
 
+use napi_derive::napi;
 use std::env;
 
 /// Display server type
@@ -14,23 +17,38 @@ pub enum DisplayServer {
   Wayland,
   /// Windows Desktop Window Manager / Win32
   Windows,
-  /// Apple Quartz Compositor (macOS)
-  Quartz,
-  /// Unknown or Headless (e.g. Server CLI)
+  /// Cocoa / Quartz Compositor (macOS)
+  Cocoa,
+  /// Unrecognized platform or no display server (e.g. headless, BSDs without X11)
   Unknown,
 }
 
+/// How much of a window's transparency the platform can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransparencyLevel {
+  /// Windows are always opaque (e.g. X11 without a compositing manager)
+  None,
+  /// Whole windows can be faded, but pixel alpha is ignored
+  PerWindowAlpha,
+  /// Each pixel's alpha blends with what is behind the window, as overlays need
+  PerPixelAlpha,
+}
+
 /// Platform information
 #[derive(Debug, Clone)]
 pub struct PlatformInfo {
   /// The display server type
   pub display_server: DisplayServer,
-  /// Whether the platform supports transparency
-  pub supports_transparency: bool,
+  /// How much transparency windows can show
+  pub transparency_level: TransparencyLevel,
   /// Whether the platform supports window positioning
   pub supports_positioning: bool,
   /// Whether the platform supports direct pixel buffer rendering
   pub supports_direct_rendering: bool,
+  /// Whether scale factors can be fractional (e.g. 1.25), rather than whole numbers
+  ///
+  /// Source art can be pre-scaled by whole factors where this is false.
+  pub supports_fractional_scaling: bool,
 }
 
 impl Default for PlatformInfo {
@@ -39,80 +57,125 @@ impl Default for PlatformInfo {
   }
 }
 
+impl DisplayServer {
+  /// Lowercase name of the display server, as reported to JavaScript
+  pub fn name(self) -> &'static str {
+    match self {
+      DisplayServer::X11 => "x11",
+      DisplayServer::Wayland => "wayland",
+      DisplayServer::Windows => "windows",
+      DisplayServer::Cocoa => "cocoa",
+      DisplayServer::Unknown => "unknown",
+    }
+  }
+}
+
 impl PlatformInfo {
   /// Detects the current platform information
+  ///
+  /// Only reads the environment (and GDK's compositor state), so it is safe to
+  /// call repeatedly.
   pub fn detect() -> Self {
-    // --- LINUX CONFIGURATION ---
-    #[cfg(target_os = "linux")]
+    // --- LINUX / BSD CONFIGURATION (GTK) ---
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
     {
-      // Priority 1: Check for Wayland
-      // If WAYLAND_DISPLAY is set, we are likely running natively on Wayland.
-      if env::var("WAYLAND_DISPLAY").is_ok() {
-        return PlatformInfo {
-          display_server: DisplayServer::Wayland,
-          supports_transparency: true,
-          // Wayland protocols explicitly discourage/block absolute window positioning
-          // by the client for security reasons.
-          supports_positioning: false,
-          supports_direct_rendering: true,
-        };
+      let gdk_backend = env::var("GDK_BACKEND").ok();
+      let mut info = Self::for_display_server(gtk_display_server(
+        env::var_os("WAYLAND_DISPLAY").is_some(),
+        env::var_os("DISPLAY").is_some(),
+        gdk_backend.as_deref(),
+      ));
+      // Without a compositing manager, X11 windows can't be transparent at all
+      if info.is_x11() && x11_compositor_running() == Some(false) {
+        info.transparency_level = TransparencyLevel::None;
       }
-
-      // Priority 2: Check for X11
-      // If DISPLAY is set, we are on X11 (or XWayland without the Wayland var exposed).
-      if env::var("DISPLAY").is_ok() {
-        return PlatformInfo {
-          display_server: DisplayServer::X11,
-          supports_transparency: true,
-          supports_positioning: true,
-          supports_direct_rendering: true,
-        };
-      }
-
-      // Priority 3: Headless / Console
-      #[deny(clippy::needless_return)]
-      return PlatformInfo {
-        display_server: DisplayServer::Unknown,
-        supports_transparency: false,
-        supports_positioning: false,
-        supports_direct_rendering: false,
-      };
+      info
     }
 
     // --- WINDOWS CONFIGURATION ---
     #[cfg(target_os = "windows")]
     {
-      PlatformInfo {
-        display_server: DisplayServer::Windows,
-        supports_transparency: true,
-        supports_positioning: true,
-        supports_direct_rendering: true,
-      }
+      Self::for_display_server(DisplayServer::Windows)
     }
 
     // --- MACOS CONFIGURATION ---
     #[cfg(target_os = "macos")]
     {
-      PlatformInfo {
-        display_server: DisplayServer::Quartz,
-        supports_transparency: true,
-        supports_positioning: true,
-        supports_direct_rendering: true,
-      }
+      Self::for_display_server(DisplayServer::Cocoa)
     }
 
-    // --- OTHERS (BSD, Android, iOS, etc.) ---
-    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    // --- OTHERS (Android, iOS, etc.) ---
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "windows",
+      target_os = "macos"
+    )))]
     {
-      PlatformInfo {
-        display_server: DisplayServer::Unknown,
-        supports_transparency: false,
+      Self::for_display_server(DisplayServer::Unknown)
+    }
+  }
+
+  /// Capabilities of a display server
+  ///
+  /// X11 is assumed to have a compositing manager; [`PlatformInfo::detect`]
+  /// checks for one once GTK is running.
+  pub fn for_display_server(display_server: DisplayServer) -> Self {
+    match display_server {
+      DisplayServer::Wayland => PlatformInfo {
+        display_server,
+        // Wayland compositors always blend window surfaces
+        transparency_level: TransparencyLevel::PerPixelAlpha,
+        // Wayland protocols explicitly discourage/block absolute window positioning
+        // by the client for security reasons.
+        supports_positioning: false,
+        supports_direct_rendering: true,
+        // GTK 3 only scales by whole factors (GDK_SCALE / the output scale)
+        supports_fractional_scaling: false,
+      },
+      DisplayServer::X11 | DisplayServer::Cocoa => PlatformInfo {
+        display_server,
+        transparency_level: TransparencyLevel::PerPixelAlpha,
+        supports_positioning: true,
+        supports_direct_rendering: true,
+        // GTK 3 and macOS backing stores only scale by whole factors
+        supports_fractional_scaling: false,
+      },
+      DisplayServer::Windows => PlatformInfo {
+        display_server,
+        // Layered windows composed by DWM
+        transparency_level: TransparencyLevel::PerPixelAlpha,
+        supports_positioning: true,
+        supports_direct_rendering: true,
+        // Per-monitor DPI settings such as 125% and 150%
+        supports_fractional_scaling: true,
+      },
+      // Headless / Console
+      DisplayServer::Unknown => PlatformInfo {
+        display_server,
+        transparency_level: TransparencyLevel::None,
         supports_positioning: false,
         supports_direct_rendering: false,
-      }
+        supports_fractional_scaling: false,
+      },
     }
   }
 
+  /// Returns true if windows can show any transparency
+  #[deprecated(note = "use `transparency_level`, which tells per-pixel from per-window alpha")]
+  pub fn supports_transparency(&self) -> bool {
+    self.transparency_level != TransparencyLevel::None
+  }
+
   /// Returns true if running on X11
   pub fn is_x11(&self) -> bool {
     self.display_server == DisplayServer::X11
@@ -130,7 +193,7 @@ impl PlatformInfo {
 
   /// Returns true if running on macOS
   pub fn is_macos(&self) -> bool {
-    self.display_server == DisplayServer::Quartz
+    self.display_server == DisplayServer::Cocoa
   }
 }
 
@@ -138,3 +201,168 @@ impl PlatformInfo {
 pub fn platform_info() -> PlatformInfo {
   PlatformInfo::detect()
 }
+
+/// Forces GTK to use the X11 backend, e.g. to run under XWayland in a Wayland session
+///
+/// Sets `GDK_BACKEND=x11` unless a backend is already pinned. GTK picks its
+/// backend when it initializes, so call this once at startup, before creating
+/// the first event loop; later calls fail. This changes the process
+/// environment, so avoid calling it while other threads read it.
+///
+/// Returns whether the backend was changed (always false outside Linux and BSDs).
+#[napi]
+pub fn force_x11_backend() -> napi::Result<bool> {
+  if crate::tao::structs::EVENT_LOOP_CREATED.load(std::sync::atomic::Ordering::SeqCst) {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      "The X11 backend must be forced before the event loop is created".to_string(),
+    ));
+  }
+  Ok(pin_x11_backend())
+}
+
+/// Sets `GDK_BACKEND=x11` unless a backend is already pinned, returning whether it did
+pub(crate) fn pin_x11_backend() -> bool {
+  if !cfg!(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )) || env::var_os("GDK_BACKEND").is_some()
+  {
+    return false;
+  }
+  env::set_var("GDK_BACKEND", "x11");
+  true
+}
+
+/// Whether a compositing manager owns the `_NET_WM_CM_S<screen>` selection
+///
+/// Asks GDK, which checks the selection owner. Returns `None` off the GTK main
+/// thread or before GTK has opened the display (i.e. before the event loop is
+/// created).
+#[cfg(target_os = "linux")]
+fn x11_compositor_running() -> Option<bool> {
+  if !gtk::is_initialized_main_thread() {
+    return None;
+  }
+  gtk::gdk::Screen::default().map(|screen| screen.is_composited())
+}
+
+/// GTK is only a dependency on Linux, so the BSDs can't ask GDK
+#[cfg(any(
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn x11_compositor_running() -> Option<bool> {
+  None
+}
+
+/// Picks the display server GTK will connect to from the session's environment
+///
+/// `GDK_BACKEND` is a comma-separated list of backends tried in order (`*`
+/// means GTK's default order); a backend is only usable if its session
+/// variable is set. Without it, GTK prefers Wayland over X11.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+  test
+))]
+fn gtk_display_server(
+  wayland_display: bool,
+  display: bool,
+  gdk_backend: Option<&str>,
+) -> DisplayServer {
+  let default_order = || {
+    if wayland_display {
+      Some(DisplayServer::Wayland)
+    } else if display {
+      Some(DisplayServer::X11)
+    } else {
+      None
+    }
+  };
+  let Some(gdk_backend) = gdk_backend.filter(|backend| !backend.trim().is_empty()) else {
+    return default_order().unwrap_or(DisplayServer::Unknown);
+  };
+  gdk_backend
+    .split(',')
+    .find_map(|backend| match backend.trim() {
+      "wayland" if wayland_display => Some(DisplayServer::Wayland),
+      "x11" if display => Some(DisplayServer::X11),
+      "*" => default_order(),
+      _ => None,
+    })
+    .unwrap_or(DisplayServer::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_gtk_display_server_prefers_wayland() {
+    assert_eq!(gtk_display_server(true, true, None), DisplayServer::Wayland);
+    assert_eq!(gtk_display_server(false, true, None), DisplayServer::X11);
+    assert_eq!(
+      gtk_display_server(false, false, None),
+      DisplayServer::Unknown
+    );
+  }
+
+  #[test]
+  fn test_gtk_display_server_honors_gdk_backend() {
+    assert_eq!(
+      gtk_display_server(true, true, Some("x11")),
+      DisplayServer::X11
+    );
+    assert_eq!(
+      gtk_display_server(true, false, Some("x11,wayland")),
+      DisplayServer::Wayland
+    );
+    assert_eq!(
+      gtk_display_server(true, true, Some("*")),
+      DisplayServer::Wayland
+    );
+    assert_eq!(
+      gtk_display_server(true, false, Some("x11")),
+      DisplayServer::Unknown
+    );
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_headless_has_no_transparency() {
+    let info = PlatformInfo::for_display_server(DisplayServer::Unknown);
+    assert_eq!(info.transparency_level, TransparencyLevel::None);
+    assert!(!info.supports_transparency());
+  }
+
+  #[test]
+  fn test_display_server_names() {
+    assert_eq!(DisplayServer::Wayland.name(), "wayland");
+    assert_eq!(DisplayServer::Cocoa.name(), "cocoa");
+  }
+
+  #[test]
+  fn test_cocoa_capabilities() {
+    let info = PlatformInfo::for_display_server(DisplayServer::Cocoa);
+    assert!(info.is_macos());
+    assert_eq!(info.transparency_level, TransparencyLevel::PerPixelAlpha);
+    assert!(info.supports_positioning);
+  }
+
+  #[test]
+  fn test_wayland_capabilities() {
+    let info = PlatformInfo::for_display_server(DisplayServer::Wayland);
+    assert!(info.is_wayland());
+    assert_eq!(info.transparency_level, TransparencyLevel::PerPixelAlpha);
+    assert!(!info.supports_positioning);
+  }
+}
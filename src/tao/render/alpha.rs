@@ -0,0 +1,132 @@
+//! Alpha channel conversions
+//!
+//! This module converts RGBA buffers between straight and premultiplied alpha,
+//! e.g. for handing captured frames to compositors that expect premultiplied input.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Multiplies the color channels of each RGBA pixel by its alpha, in place
+pub fn premultiply_in_place(pixels: &mut [u8]) {
+  for pixel in pixels.chunks_exact_mut(4) {
+    let alpha = pixel[3] as u32;
+    for channel in &mut pixel[..3] {
+      *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+    }
+  }
+}
+
+/// Divides the color channels of each premultiplied RGBA pixel by its alpha, in place
+///
+/// Fully transparent pixels become transparent black.
+pub fn unpremultiply_in_place(pixels: &mut [u8]) {
+  for pixel in pixels.chunks_exact_mut(4) {
+    let alpha = pixel[3] as u32;
+    for channel in &mut pixel[..3] {
+      *channel = (*channel as u32 * 255 + alpha / 2)
+        .checked_div(alpha)
+        .map_or(0, |v| v.min(255) as u8);
+    }
+  }
+}
+
+/// Blends straight-alpha RGBA `src` over `backdrop`, writing the result into `src`
+///
+/// Colors are weighted by the source alpha (`src * a + backdrop * (1 - a)`) and
+/// alphas combine as `a + backdrop_a * (1 - a)`, so an opaque backdrop stays opaque.
+pub fn blend_source_over(src: &mut [u8], backdrop: &[u8]) {
+  for (pixel, under) in src.chunks_exact_mut(4).zip(backdrop.chunks_exact(4)) {
+    let alpha = pixel[3] as u32;
+    let inverse = 255 - alpha;
+    for (channel, &under) in pixel[..3].iter_mut().zip(&under[..3]) {
+      *channel = ((*channel as u32 * alpha + under as u32 * inverse + 127) / 255) as u8;
+    }
+    pixel[3] = (alpha + (under[3] as u32 * inverse + 127) / 255) as u8;
+  }
+}
+
+/// Validates that a buffer holds whole RGBA pixels
+fn validate_rgba_len(len: usize) -> napi::Result<()> {
+  if !len.is_multiple_of(4) {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Buffer length {} is not a multiple of 4 (RGBA)", len),
+    ));
+  }
+  Ok(())
+}
+
+/// Returns a copy of an RGBA buffer with color channels multiplied by alpha
+#[napi]
+pub fn premultiply_alpha(buffer: Buffer) -> napi::Result<Buffer> {
+  validate_rgba_len(buffer.len())?;
+  let mut pixels = buffer.to_vec();
+  premultiply_in_place(&mut pixels);
+  Ok(pixels.into())
+}
+
+/// Returns a copy of a premultiplied RGBA buffer converted back to straight alpha
+#[napi]
+pub fn unpremultiply_alpha(buffer: Buffer) -> napi::Result<Buffer> {
+  validate_rgba_len(buffer.len())?;
+  let mut pixels = buffer.to_vec();
+  unpremultiply_in_place(&mut pixels);
+  Ok(pixels.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_blend_source_over() {
+    let mut src = [255, 0, 0, 128, 10, 20, 30, 255, 9, 9, 9, 0];
+    blend_source_over(&mut src, &[0, 255, 0, 255, 0, 0, 0, 255, 1, 2, 3, 255]);
+    assert_eq!(src, [128, 127, 0, 255, 10, 20, 30, 255, 1, 2, 3, 255]);
+  }
+
+  #[test]
+  fn test_premultiply() {
+    let mut pixels = [255, 128, 0, 128, 10, 20, 30, 255, 200, 200, 200, 0];
+    premultiply_in_place(&mut pixels);
+    assert_eq!(pixels, [128, 64, 0, 128, 10, 20, 30, 255, 0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_unpremultiply() {
+    let mut pixels = [128, 64, 0, 128, 10, 20, 30, 255, 7, 7, 7, 0];
+    unpremultiply_in_place(&mut pixels);
+    assert_eq!(pixels, [255, 128, 0, 128, 10, 20, 30, 255, 0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_unpremultiply_clamps_invalid_input() {
+    // Color larger than alpha is invalid premultiplied data
+    let mut pixels = [200, 0, 0, 100];
+    unpremultiply_in_place(&mut pixels);
+    assert_eq!(pixels, [255, 0, 0, 100]);
+  }
+
+  #[test]
+  fn test_round_trip_opaque_is_lossless() {
+    let original: Vec<u8> = (0..=255u8).flat_map(|v| [v, 255 - v, v / 2, 255]).collect();
+    let mut pixels = original.clone();
+    premultiply_in_place(&mut pixels);
+    unpremultiply_in_place(&mut pixels);
+    assert_eq!(pixels, original);
+  }
+
+  #[test]
+  fn test_trailing_bytes_are_ignored() {
+    let mut pixels = [100, 100, 100, 0, 9, 9];
+    premultiply_in_place(&mut pixels);
+    assert_eq!(pixels, [0, 0, 0, 0, 9, 9]);
+  }
+
+  #[test]
+  fn test_validate_rgba_len() {
+    assert!(validate_rgba_len(8).is_ok());
+    assert!(validate_rgba_len(0).is_ok());
+    assert!(validate_rgba_len(7).is_err());
+  }
+}
@@ -3,6 +3,8 @@
 //! This module provides functions for copying and scaling pixel buffers
 //! using various algorithms (nearest-neighbor scaling, centered copy, fill mode).
 
+use super::validation::buffer_len;
+
 // Debug logging macro
 macro_rules! debug_log {
     ($($arg:tt)*) => {
@@ -50,13 +52,13 @@ pub fn copy_buffer_scaled(frame: &mut [u8], buffer: &[u8], params: CopyBufferPar
   // The pixels crate creates a frame that is buffer_width x buffer_height.
   // We simply copy the source buffer directly into the frame.
   // The pixels crate handles all the scaling when rendering to the window.
-  let expected_len = (buffer_width * buffer_height * 4) as usize;
-  if buffer.len() == expected_len && frame.len() == expected_len {
+  let expected_len = buffer_len(buffer_width, buffer_height, 4);
+  if expected_len == Some(buffer.len()) && frame.len() == buffer.len() {
     frame.copy_from_slice(buffer);
     debug_log!("  copied {} bytes directly", buffer.len());
   } else {
     debug_log!(
-      "  size mismatch: buffer={}, frame={}, expected={}",
+      "  size mismatch: buffer={}, frame={}, expected={:?}",
       buffer.len(),
       frame.len(),
       expected_len
@@ -79,8 +81,8 @@ pub fn copy_buffer_centered(
   // The frame is sized to buffer_width x buffer_height
   // We simply copy the buffer to the frame starting at (0, 0)
   // since the source and destination are the same size
-  let expected_len = (buffer_width * buffer_height * 4) as usize;
-  if buffer.len() == expected_len && frame.len() == expected_len {
+  let expected_len = buffer_len(buffer_width, buffer_height, 4);
+  if expected_len == Some(buffer.len()) && frame.len() == buffer.len() {
     frame.copy_from_slice(buffer);
   }
 }
@@ -137,8 +139,8 @@ pub fn copy_buffer_fill(
   // The scaling is handled during render. To properly implement Fill,
   // we would need to scale the cropped region to fill the buffer.
   // For now, copy the full buffer which will be stretched.
-  let expected_len = (buffer_width * buffer_height * 4) as usize;
-  if buffer.len() == expected_len && frame.len() == expected_len {
+  let expected_len = buffer_len(buffer_width, buffer_height, 4);
+  if expected_len == Some(buffer.len()) && frame.len() == buffer.len() {
     frame.copy_from_slice(buffer);
   }
 }
@@ -0,0 +1,171 @@
+//! Clipboard image interop
+//!
+//! Lets viewer apps paste an image from the clipboard straight into a
+//! [`PixelRenderer`](super::PixelRenderer) and copy frames back out. Images are
+//! exchanged as tightly packed RGBA. Only Linux (GTK) is supported for now.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// RGBA image read from the clipboard
+#[napi(object)]
+pub struct ClipboardImage {
+  /// RGBA pixels (width * height * 4 bytes)
+  pub data: Buffer,
+  /// Width in pixels
+  pub width: u32,
+  /// Height in pixels
+  pub height: u32,
+}
+
+/// Reads the image currently on the clipboard, if any
+///
+/// The returned data can be passed to `PixelRenderer.render` with a renderer
+/// created for the same dimensions. Must be called on the event loop thread
+/// after the event loop has been created.
+#[napi]
+pub fn get_clipboard_image() -> napi::Result<Option<ClipboardImage>> {
+  platform::get_image()
+}
+
+/// Puts an RGBA image on the clipboard
+///
+/// # Arguments
+/// * `buffer` - RGBA pixels (must be width * height * 4 bytes)
+/// * `width` - Width of the image in pixels
+/// * `height` - Height of the image in pixels
+#[napi]
+pub fn set_clipboard_image(buffer: Buffer, width: u32, height: u32) -> napi::Result<()> {
+  let expected_len = width as usize * height as usize * 4;
+  if buffer.len() != expected_len {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
+        buffer.len(),
+        expected_len,
+        width,
+        height
+      ),
+    ));
+  }
+  platform::set_image(buffer.to_vec(), width, height)
+}
+
+/// Repacks pixel rows with padding and 3 or 4 channels into tight RGBA
+#[cfg(any(target_os = "linux", test))]
+fn pack_rgba(pixels: &[u8], width: u32, height: u32, rowstride: usize, channels: usize) -> Vec<u8> {
+  let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+  for y in 0..height as usize {
+    for x in 0..width as usize {
+      let idx = y * rowstride + x * channels;
+      match pixels.get(idx..idx + channels) {
+        Some(p) if channels >= 4 => rgba.extend_from_slice(&p[..4]),
+        Some(p) if channels == 3 => rgba.extend_from_slice(&[p[0], p[1], p[2], 255]),
+        _ => rgba.extend_from_slice(&[0, 0, 0, 0]),
+      }
+    }
+  }
+  rgba
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{pack_rgba, ClipboardImage};
+  use gtk::{gdk, gdk_pixbuf, glib};
+
+  fn clipboard() -> napi::Result<gtk::Clipboard> {
+    if !gtk::is_initialized_main_thread() {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Clipboard is only available on the event loop thread after the event loop is created"
+          .to_string(),
+      ));
+    }
+    Ok(gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD))
+  }
+
+  pub fn get_image() -> napi::Result<Option<ClipboardImage>> {
+    let Some(pixbuf) = clipboard()?.wait_for_image() else {
+      return Ok(None);
+    };
+    let width = pixbuf.width().max(0) as u32;
+    let height = pixbuf.height().max(0) as u32;
+    let bytes = pixbuf.read_pixel_bytes();
+    let data = pack_rgba(
+      &bytes,
+      width,
+      height,
+      pixbuf.rowstride().max(0) as usize,
+      pixbuf.n_channels().max(0) as usize,
+    );
+    Ok(Some(ClipboardImage {
+      data: data.into(),
+      width,
+      height,
+    }))
+  }
+
+  pub fn set_image(rgba: Vec<u8>, width: u32, height: u32) -> napi::Result<()> {
+    let clipboard = clipboard()?;
+    let pixbuf = gdk_pixbuf::Pixbuf::from_bytes(
+      &glib::Bytes::from_owned(rgba),
+      gdk_pixbuf::Colorspace::Rgb,
+      true,
+      8,
+      width as i32,
+      height as i32,
+      width as i32 * 4,
+    );
+    clipboard.set_image(&pixbuf);
+    // Keep the image available after this process exits
+    clipboard.store();
+    Ok(())
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+  use super::ClipboardImage;
+
+  fn unsupported() -> napi::Error {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      "Clipboard images are not supported on this platform yet".to_string(),
+    )
+  }
+
+  pub fn get_image() -> napi::Result<Option<ClipboardImage>> {
+    Err(unsupported())
+  }
+
+  pub fn set_image(_rgba: Vec<u8>, _width: u32, _height: u32) -> napi::Result<()> {
+    Err(unsupported())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_pack_rgba_adds_alpha_and_drops_padding() {
+    // 2x2 RGB image with 2 bytes of row padding
+    let pixels = [1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0];
+    assert_eq!(
+      pack_rgba(&pixels, 2, 2, 8, 3),
+      vec![1, 2, 3, 255, 4, 5, 6, 255, 7, 8, 9, 255, 10, 11, 12, 255]
+    );
+  }
+
+  #[test]
+  fn test_pack_rgba_keeps_alpha() {
+    let pixels = [1, 2, 3, 4, 5, 6, 7, 8];
+    assert_eq!(pack_rgba(&pixels, 2, 1, 8, 4), pixels.to_vec());
+  }
+
+  #[test]
+  fn test_pack_rgba_short_buffer_is_transparent() {
+    assert_eq!(pack_rgba(&[1, 2, 3], 1, 1, 4, 4), vec![0, 0, 0, 0]);
+  }
+}
@@ -0,0 +1,346 @@
+//! Batched draw commands
+//!
+//! A display list lets callers compose many primitives per frame with a single
+//! call across the JS/native boundary. Commands are validated up front, then
+//! executed in order against the window-sized frame with clipping.
+
+use super::FrameSize;
+use crate::tao::enums::DrawCommandKind;
+use crate::wry::structs::Rect;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// A single primitive in a display list
+///
+/// Coordinates are in window pixels. Pixels are written as-is, without blending.
+#[napi(object)]
+pub struct DrawCommand {
+  /// Kind of primitive to draw
+  pub kind: DrawCommandKind,
+  /// Left edge (FillRect, BlitBuffer) or start X (DrawLine)
+  pub x: i32,
+  /// Top edge (FillRect, BlitBuffer) or start Y (DrawLine)
+  pub y: i32,
+  /// Width of the rectangle or blitted buffer
+  pub width: Option<u32>,
+  /// Height of the rectangle or blitted buffer
+  pub height: Option<u32>,
+  /// End X of a line
+  pub x2: Option<i32>,
+  /// End Y of a line
+  pub y2: Option<i32>,
+  /// Color [R, G, B, A] for FillRect and DrawLine
+  pub color: Option<Vec<u8>>,
+  /// RGBA pixels for BlitBuffer (width * height * 4 bytes)
+  pub buffer: Option<Buffer>,
+  /// Text for DrawText
+  pub text: Option<String>,
+}
+
+/// A validated draw command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DrawOp<'a> {
+  FillRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    color: [u8; 4],
+  },
+  Blit {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    pixels: &'a [u8],
+  },
+  Line {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    color: [u8; 4],
+  },
+}
+
+/// Validates draw commands and converts them to ops
+///
+/// Fails on the first invalid command, before anything is drawn.
+pub(crate) fn compile(commands: &[DrawCommand]) -> napi::Result<Vec<DrawOp<'_>>> {
+  commands
+    .iter()
+    .enumerate()
+    .map(|(index, command)| compile_command(index, command))
+    .collect()
+}
+
+fn compile_command(index: usize, command: &DrawCommand) -> napi::Result<DrawOp<'_>> {
+  let invalid = |message: &str| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid draw command {}: {}", index, message),
+    )
+  };
+  let color = || {
+    command
+      .color
+      .as_ref()
+      .filter(|c| c.len() >= 4)
+      .map(|c| [c[0], c[1], c[2], c[3]])
+      .ok_or_else(|| invalid("color must have 4 components"))
+  };
+  let size = || match (command.width, command.height) {
+    (Some(width), Some(height)) => Ok((width, height)),
+    _ => Err(invalid("width and height are required")),
+  };
+
+  match command.kind {
+    DrawCommandKind::FillRect => {
+      let (width, height) = size()?;
+      Ok(DrawOp::FillRect {
+        x: command.x,
+        y: command.y,
+        width,
+        height,
+        color: color()?,
+      })
+    }
+    DrawCommandKind::BlitBuffer => {
+      let (width, height) = size()?;
+      let pixels: &[u8] = command
+        .buffer
+        .as_ref()
+        .ok_or_else(|| invalid("buffer is required"))?;
+      let expected_len = width as usize * height as usize * 4;
+      if pixels.len() != expected_len {
+        return Err(invalid(&format!(
+          "buffer has {} bytes, expected {} for {}x{}",
+          pixels.len(),
+          expected_len,
+          width,
+          height
+        )));
+      }
+      Ok(DrawOp::Blit {
+        x: command.x,
+        y: command.y,
+        width,
+        height,
+        pixels,
+      })
+    }
+    DrawCommandKind::DrawLine => match (command.x2, command.y2) {
+      (Some(x1), Some(y1)) => Ok(DrawOp::Line {
+        x0: command.x,
+        y0: command.y,
+        x1,
+        y1,
+        color: color()?,
+      }),
+      _ => Err(invalid("x2 and y2 are required")),
+    },
+    DrawCommandKind::DrawText => Err(invalid(
+      "DrawText is not supported yet (no font rasterizer is available)",
+    )),
+  }
+}
+
+/// Executes ops in order against a frame of the given size
+pub(crate) fn execute(frame: &mut [u8], size: FrameSize, ops: &[DrawOp]) {
+  for op in ops {
+    match *op {
+      DrawOp::FillRect {
+        x,
+        y,
+        width,
+        height,
+        color,
+      } => {
+        let Some((left, top, right, bottom)) = clip(x, y, width, height, size.width, size.height)
+        else {
+          continue;
+        };
+        for py in top..bottom {
+          for px in left..right {
+            put_pixel(frame, size.stride, px, py, &color);
+          }
+        }
+      }
+      DrawOp::Blit {
+        x,
+        y,
+        width,
+        height,
+        pixels,
+      } => {
+        let Some((left, top, right, bottom)) = clip(x, y, width, height, size.width, size.height)
+        else {
+          continue;
+        };
+        for py in top..bottom {
+          let src_y = (py as i64 - y as i64) as usize;
+          for px in left..right {
+            let src_x = (px as i64 - x as i64) as usize;
+            let src_idx = (src_y * width as usize + src_x) * 4;
+            put_pixel(frame, size.stride, px, py, &pixels[src_idx..src_idx + 4]);
+          }
+        }
+      }
+      DrawOp::Line {
+        x0,
+        y0,
+        x1,
+        y1,
+        color,
+      } => draw_line(frame, size, (x0, y0), (x1, y1), &color),
+    }
+  }
+}
+
+/// Intersects a rectangle with the frame, returning (left, top, right, bottom)
+fn clip(
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+  frame_width: u32,
+  frame_height: u32,
+) -> Option<(u32, u32, u32, u32)> {
+  let rect = Rect {
+    x,
+    y,
+    width,
+    height,
+  }
+  .clip_to(frame_width, frame_height)?;
+  let (left, top) = (rect.x as u32, rect.y as u32);
+  Some((left, top, left + rect.width, top + rect.height))
+}
+
+/// Draws a line with Bresenham's algorithm, skipping pixels outside the frame
+fn draw_line(
+  frame: &mut [u8],
+  size: FrameSize,
+  start: (i32, i32),
+  end: (i32, i32),
+  color: &[u8; 4],
+) {
+  let (mut x, mut y) = (start.0 as i64, start.1 as i64);
+  let (x1, y1) = (end.0 as i64, end.1 as i64);
+  let dx = (x1 - x).abs();
+  let dy = -(y1 - y).abs();
+  let step_x = if x < x1 { 1 } else { -1 };
+  let step_y = if y < y1 { 1 } else { -1 };
+  let mut error = dx + dy;
+
+  loop {
+    if x >= 0 && y >= 0 && x < size.width as i64 && y < size.height as i64 {
+      put_pixel(frame, size.stride, x as u32, y as u32, color);
+    }
+    if x == x1 && y == y1 {
+      break;
+    }
+    let doubled = 2 * error;
+    if doubled >= dy {
+      error += dy;
+      x += step_x;
+    }
+    if doubled <= dx {
+      error += dx;
+      y += step_y;
+    }
+  }
+}
+
+fn put_pixel(frame: &mut [u8], stride: u32, x: u32, y: u32, color: &[u8]) {
+  let idx = (y as usize * stride as usize + x as usize) * 4;
+  if idx + 4 <= frame.len() {
+    frame[idx..idx + 4].copy_from_slice(color);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const RED: [u8; 4] = [255, 0, 0, 255];
+
+  fn pixel(frame: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+    let idx = ((y * width + x) * 4) as usize;
+    [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+  }
+
+  #[test]
+  fn test_fill_rect_is_clipped_to_frame() {
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    let ops = [DrawOp::FillRect {
+      x: -2,
+      y: 2,
+      width: 4,
+      height: 10,
+      color: RED,
+    }];
+    execute(&mut frame, FrameSize::packed(4, 4), &ops);
+    assert_eq!(pixel(&frame, 4, 0, 2), RED);
+    assert_eq!(pixel(&frame, 4, 1, 3), RED);
+    assert_eq!(pixel(&frame, 4, 2, 2), [0; 4]);
+    assert_eq!(pixel(&frame, 4, 0, 1), [0; 4]);
+  }
+
+  #[test]
+  fn test_blit_offsets_source_when_clipped() {
+    let mut frame = vec![0u8; 2 * 2 * 4];
+    let source: Vec<u8> = (0..4u8).flat_map(|i| [i, i, i, 255]).collect();
+    let ops = [DrawOp::Blit {
+      x: -1,
+      y: -1,
+      width: 2,
+      height: 2,
+      pixels: &source,
+    }];
+    execute(&mut frame, FrameSize::packed(2, 2), &ops);
+    // Only the bottom-right source pixel lands in the frame, at (0, 0)
+    assert_eq!(pixel(&frame, 2, 0, 0), [3, 3, 3, 255]);
+    assert_eq!(pixel(&frame, 2, 1, 1), [0; 4]);
+  }
+
+  #[test]
+  fn test_line_is_continuous_and_clipped() {
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    let ops = [DrawOp::Line {
+      x0: -2,
+      y0: -2,
+      x1: 5,
+      y1: 5,
+      color: RED,
+    }];
+    execute(&mut frame, FrameSize::packed(4, 4), &ops);
+    for i in 0..4 {
+      assert_eq!(pixel(&frame, 4, i, i), RED);
+    }
+    assert_eq!(pixel(&frame, 4, 1, 0), [0; 4]);
+  }
+
+  #[test]
+  fn test_ops_run_in_order() {
+    let mut frame = vec![0u8; 4];
+    let ops = [
+      DrawOp::FillRect {
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+        color: RED,
+      },
+      DrawOp::Line {
+        x0: 0,
+        y0: 0,
+        x1: 0,
+        y1: 0,
+        color: [0, 0, 255, 255],
+      },
+    ];
+    execute(&mut frame, FrameSize::packed(1, 1), &ops);
+    assert_eq!(pixel(&frame, 1, 0, 0), [0, 0, 255, 255]);
+  }
+}
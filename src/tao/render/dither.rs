@@ -0,0 +1,226 @@
+//! Dithering for 8-bit quantization
+//!
+//! This module converts high-precision channel values (0.0..=255.0) to 8-bit
+//! output, optionally dithering to hide banding in smooth gradients.
+
+use crate::tao::enums::DitherMode;
+
+/// 4x4 Bayer matrix used for ordered dithering
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Rounds a channel value to the nearest 8-bit level, clamping out-of-range values
+///
+/// Adding 0.5 and truncating avoids a libm `round` call in the per-pixel path.
+#[inline]
+pub fn quantize_nearest(value: f32) -> u8 {
+  (value.clamp(0.0, 255.0) + 0.5) as u8
+}
+
+/// Quantizes a single channel value using ordered (Bayer) dithering
+///
+/// # Arguments
+/// * `value` - Channel value in the 0.0..=255.0 range
+/// * `x` - Destination X coordinate, used to index the Bayer matrix
+/// * `y` - Destination Y coordinate, used to index the Bayer matrix
+pub fn quantize_ordered(value: f32, x: u32, y: u32) -> u8 {
+  let threshold = (BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as f32 + 0.5) / 16.0 - 0.5;
+  quantize_nearest(value + threshold)
+}
+
+/// Floyd-Steinberg error rows, handed back by [`Quantizer::into_rows`] for reuse
+#[derive(Debug, Default)]
+pub struct ErrorRows {
+  current: Vec<[f32; 3]>,
+  next: Vec<[f32; 3]>,
+}
+
+impl ErrorRows {
+  /// Makes room for rows `width` pixels wide
+  pub fn reserve(&mut self, width: usize) {
+    for row in [&mut self.current, &mut self.next] {
+      row.reserve(width.saturating_sub(row.len()));
+    }
+  }
+}
+
+/// Quantizes high-precision RGBA pixels to 8 bits per channel
+///
+/// Pixels must be fed row by row, left to right, calling [`Quantizer::end_row`]
+/// after each row. Alpha is always rounded without dithering.
+pub struct Quantizer {
+  mode: DitherMode,
+  /// Error carried into the current row (RGB, indexed by x)
+  errors: Vec<[f32; 3]>,
+  /// Error carried into the next row (RGB, indexed by x)
+  next_errors: Vec<[f32; 3]>,
+}
+
+impl Quantizer {
+  /// Creates a quantizer for rows of the given width
+  pub fn new(mode: DitherMode, width: u32) -> Self {
+    Self::with_rows(mode, width, ErrorRows::default())
+  }
+
+  /// Creates a quantizer that reuses the allocations of earlier error rows
+  pub fn with_rows(mode: DitherMode, width: u32, rows: ErrorRows) -> Self {
+    let error_len = if mode == DitherMode::FloydSteinberg {
+      width as usize
+    } else {
+      0
+    };
+    let ErrorRows {
+      current: mut errors,
+      next: mut next_errors,
+    } = rows;
+    errors.clear();
+    errors.resize(error_len, [0.0; 3]);
+    next_errors.clear();
+    next_errors.resize(error_len, [0.0; 3]);
+    Self {
+      mode,
+      errors,
+      next_errors,
+    }
+  }
+
+  /// Returns the error rows so a later quantizer can reuse their allocations
+  pub fn into_rows(self) -> ErrorRows {
+    ErrorRows {
+      current: self.errors,
+      next: self.next_errors,
+    }
+  }
+
+  /// Quantizes one pixel at the given destination coordinates
+  pub fn quantize(&mut self, x: u32, y: u32, pixel: [f32; 4]) -> [u8; 4] {
+    let alpha = quantize_nearest(pixel[3]);
+    match self.mode {
+      DitherMode::None => [
+        quantize_nearest(pixel[0]),
+        quantize_nearest(pixel[1]),
+        quantize_nearest(pixel[2]),
+        alpha,
+      ],
+      DitherMode::Ordered => [
+        quantize_ordered(pixel[0], x, y),
+        quantize_ordered(pixel[1], x, y),
+        quantize_ordered(pixel[2], x, y),
+        alpha,
+      ],
+      DitherMode::FloydSteinberg => {
+        let x = x as usize;
+        let width = self.errors.len();
+        let mut out = [0u8, 0, 0, alpha];
+        for c in 0..3 {
+          let wanted = pixel[c] + self.errors.get(x).map_or(0.0, |e| e[c]);
+          let quantized = quantize_nearest(wanted) as f32;
+          let error = wanted - quantized;
+          out[c] = quantized as u8;
+
+          // Distribute the error to unprocessed neighbors (7/16, 3/16, 5/16, 1/16)
+          if x + 1 < width {
+            self.errors[x + 1][c] += error * 7.0 / 16.0;
+            self.next_errors[x + 1][c] += error / 16.0;
+          }
+          if x > 0 && x - 1 < width {
+            self.next_errors[x - 1][c] += error * 3.0 / 16.0;
+          }
+          if x < width {
+            self.next_errors[x][c] += error * 5.0 / 16.0;
+          }
+        }
+        out
+      }
+    }
+  }
+
+  /// Finishes the current row, carrying accumulated error into the next one
+  pub fn end_row(&mut self) {
+    if self.mode == DitherMode::FloydSteinberg {
+      std::mem::swap(&mut self.errors, &mut self.next_errors);
+      self.next_errors.fill([0.0; 3]);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn quantize_row(mode: DitherMode, width: u32, value: f32) -> Vec<u8> {
+    let mut quantizer = Quantizer::new(mode, width);
+    let row = (0..width)
+      .map(|x| quantizer.quantize(x, 0, [value, value, value, 255.0])[0])
+      .collect();
+    quantizer.end_row();
+    row
+  }
+
+  #[test]
+  fn test_reused_rows_start_without_error() {
+    let mut quantizer = Quantizer::new(DitherMode::FloydSteinberg, 4);
+    quantizer.quantize(0, 0, [100.4, 100.4, 100.4, 255.0]);
+    let mut reused = Quantizer::with_rows(DitherMode::FloydSteinberg, 4, quantizer.into_rows());
+    assert_eq!(reused.quantize(1, 0, [100.4; 4])[0], 100);
+  }
+
+  #[test]
+  fn test_none_rounds_to_nearest() {
+    let row = quantize_row(DitherMode::None, 8, 100.4);
+    assert!(row.iter().all(|&v| v == 100));
+  }
+
+  #[test]
+  fn test_exact_values_are_preserved() {
+    for mode in [
+      DitherMode::None,
+      DitherMode::Ordered,
+      DitherMode::FloydSteinberg,
+    ] {
+      let row = quantize_row(mode, 16, 200.0);
+      assert!(
+        row.iter().all(|&v| v == 200),
+        "{:?} altered exact value",
+        mode
+      );
+    }
+  }
+
+  #[test]
+  fn test_ordered_preserves_average() {
+    // A value halfway between two levels should produce a mix of both
+    let mut sum = 0u32;
+    for y in 0..4 {
+      for x in 0..4 {
+        let v = quantize_ordered(100.5, x, y);
+        assert!(v == 100 || v == 101);
+        sum += v as u32;
+      }
+    }
+    assert_eq!(sum, 100 * 16 + 8);
+  }
+
+  #[test]
+  fn test_floyd_steinberg_preserves_average() {
+    let mut quantizer = Quantizer::new(DitherMode::FloydSteinberg, 64);
+    let mut values = Vec::new();
+    for y in 0..64 {
+      for x in 0..64 {
+        values.push(quantizer.quantize(x, y, [50.25, 50.25, 50.25, 255.0])[0]);
+      }
+      quantizer.end_row();
+    }
+    let avg = values.iter().map(|&v| v as f32).sum::<f32>() / values.len() as f32;
+    assert!((avg - 50.25).abs() < 0.05, "average {} drifted", avg);
+    assert!(values.iter().all(|&v| v == 50 || v == 51));
+  }
+
+  #[test]
+  fn test_values_are_clamped() {
+    let mut quantizer = Quantizer::new(DitherMode::Ordered, 1);
+    assert_eq!(
+      quantizer.quantize(0, 0, [-10.0, 300.0, 255.0, 400.0]),
+      [0, 255, 255, 255]
+    );
+  }
+}
@@ -0,0 +1,118 @@
+//! Render error codes
+//!
+//! Failures on the render path carry a [`RenderError`] as the thrown error's
+//! `code` property, so JavaScript can retry presents that failed for transient
+//! surface reasons and fail fast on programmer errors like a mismatched buffer.
+
+use napi_derive::napi;
+
+/// Why a render call failed, exposed to JavaScript as the error's `code`
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+  /// The window was never created or has been destroyed.
+  WindowNotInitialized,
+  /// The buffer length doesn't match the renderer's dimensions, stride and format.
+  BufferSizeMismatch,
+  /// The renderer can't draw buffers in its current configuration (e.g.
+  /// `Indexed8` without a palette, or a stride shorter than a row).
+  InvalidConfiguration,
+  /// An argument other than the buffer is invalid (e.g. a draw command).
+  InvalidArgument,
+  /// A window or render cache lock was poisoned by a panic on another thread.
+  LockPoisoned,
+  /// The window has no render surface and none can be created from this call.
+  SurfaceUnavailable,
+  /// Creating the window's render surface failed.
+  SurfaceCreationFailed,
+  /// Presenting the frame failed, usually transiently (e.g. the surface was
+  /// lost or outdated); retrying may succeed.
+  PresentFailed,
+  /// The window was resized but its surface wasn't, with manual resizing enabled.
+  SurfaceSizeMismatch,
+  /// The image file to render doesn't exist.
+  FileNotFound,
+  /// The image file couldn't be read or decoded.
+  DecodeFailed,
+  /// Nothing has been rendered to the window yet, so there is no frame to read back.
+  NothingRendered,
+}
+
+impl AsRef<str> for RenderError {
+  fn as_ref(&self) -> &str {
+    match self {
+      RenderError::WindowNotInitialized => "WindowNotInitialized",
+      RenderError::BufferSizeMismatch => "BufferSizeMismatch",
+      RenderError::InvalidConfiguration => "InvalidConfiguration",
+      RenderError::InvalidArgument => "InvalidArgument",
+      RenderError::LockPoisoned => "LockPoisoned",
+      RenderError::SurfaceUnavailable => "SurfaceUnavailable",
+      RenderError::SurfaceCreationFailed => "SurfaceCreationFailed",
+      RenderError::PresentFailed => "PresentFailed",
+      RenderError::SurfaceSizeMismatch => "SurfaceSizeMismatch",
+      RenderError::FileNotFound => "FileNotFound",
+      RenderError::DecodeFailed => "DecodeFailed",
+      RenderError::NothingRendered => "NothingRendered",
+    }
+  }
+}
+
+impl RenderError {
+  /// Builds an error with this code
+  pub(crate) fn error(self, reason: impl Into<String>) -> napi::Error<RenderError> {
+    napi::Error::new(self, reason.into())
+  }
+
+  /// Gives a plain error this code, keeping its message
+  pub(crate) fn wrap(self, err: napi::Error) -> napi::Error<RenderError> {
+    self.error(err.reason.clone())
+  }
+
+  /// Whether the same call may succeed when retried
+  pub fn is_transient(self) -> bool {
+    matches!(
+      self,
+      RenderError::SurfaceUnavailable | RenderError::PresentFailed
+    )
+  }
+}
+
+/// Whether a render call that threw an error with this `code` may succeed when retried
+///
+/// True for surface failures such as `PresentFailed`, false for errors like
+/// `BufferSizeMismatch` that will fail the same way every time.
+#[napi]
+pub fn is_transient_render_error(code: RenderError) -> bool {
+  code.is_transient()
+}
+
+/// Converts a render error for APIs limited to plain errors, keeping the code in the message
+pub(crate) fn into_napi_error(err: napi::Error<RenderError>) -> napi::Error {
+  napi::Error::new(
+    napi::Status::GenericFailure,
+    format!("{}: {}", err.status.as_ref(), err.reason),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_code_is_the_variant_name() {
+    let err = RenderError::BufferSizeMismatch.error("got 4 bytes");
+    assert_eq!(err.status.as_ref(), "BufferSizeMismatch");
+    assert_eq!(
+      into_napi_error(err).reason,
+      "BufferSizeMismatch: got 4 bytes"
+    );
+  }
+
+  #[test]
+  fn test_only_surface_errors_are_transient() {
+    assert!(RenderError::PresentFailed.is_transient());
+    assert!(!RenderError::BufferSizeMismatch.is_transient());
+    assert!(!RenderError::WindowNotInitialized.is_transient());
+    assert!(is_transient_render_error(RenderError::SurfaceUnavailable));
+  }
+}
@@ -0,0 +1,428 @@
+//! GPU scaling backend
+//!
+//! With the `gpu` cargo feature, renderers created with `Backend.Gpu` upload
+//! the buffer at its own size and let the GPU scale it into the window, with a
+//! nearest or linear sampler matching the filter mode. The CPU then only
+//! converts the buffer to RGBA instead of scaling it into a window-sized frame.
+//!
+//! Configurations the GPU path can't express (viewports, tiling, blending,
+//! checkerboards, custom scalers, crops other than Fill's) use the CPU path, as
+//! do windows whose adapter fails to create the pipeline.
+
+use super::error::RenderError;
+use super::{
+  apply_alpha_mode, window_lock_error, window_not_initialized_error, FrameRegion, FrameSize,
+  PixelRenderer, PresentTarget, RenderState, StageTimer, DEBUG_ENABLED, RENDER_STATE,
+};
+use crate::tao::enums::{Backend, CompositeMode, FilterMode, ScaleMode};
+use pixels::wgpu;
+use std::time::Instant;
+
+/// Draws the buffer texture into a rectangle given in clip space
+const SHADER: &str = r#"
+struct Placement {
+  // left, top, right, bottom
+  rect: vec4<f32>,
+}
+
+@group(0) @binding(0) var buffer_texture: texture_2d<f32>;
+@group(0) @binding(1) var buffer_sampler: sampler;
+@group(0) @binding(2) var<uniform> placement: Placement;
+
+struct VertexOutput {
+  @builtin(position) position: vec4<f32>,
+  @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+  // Triangle strip over the corners: top-left, top-right, bottom-left, bottom-right
+  let corner = vec2<f32>(f32(index & 1u), f32(index >> 1u));
+  var out: VertexOutput;
+  out.position = vec4<f32>(mix(placement.rect.xy, placement.rect.zw, corner), 0.0, 1.0);
+  out.uv = corner;
+  return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  return textureSample(buffer_texture, buffer_sampler, in.uv);
+}
+"#;
+
+/// Outcome of creating a window's GPU pipeline, kept so failures aren't retried every frame
+#[derive(Debug)]
+pub(crate) enum GpuState {
+  Ready(Box<GpuScaler>),
+  Unavailable,
+}
+
+/// Pipeline scaling the buffer texture onto one window's surface
+#[derive(Debug)]
+pub(crate) struct GpuScaler {
+  pipeline: wgpu::RenderPipeline,
+  bind_group_layout: wgpu::BindGroupLayout,
+  nearest: wgpu::Sampler,
+  linear: wgpu::Sampler,
+  /// Uniform holding the destination rectangle
+  placement: wgpu::Buffer,
+}
+
+impl GpuScaler {
+  /// Creates the pipeline for the surface of `pixels`, failing on validation errors
+  fn new(pixels: &pixels::Pixels) -> Result<Self, String> {
+    let device = &pixels.context().device;
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: Some("pixel_renderer_gpu_shader"),
+      source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+    });
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("pixel_renderer_gpu_bind_group_layout"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+          },
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 2,
+          visibility: wgpu::ShaderStages::VERTEX,
+          ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+          },
+          count: None,
+        },
+      ],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("pixel_renderer_gpu_pipeline_layout"),
+      bind_group_layouts: &[&bind_group_layout],
+      push_constant_ranges: &[],
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("pixel_renderer_gpu_pipeline"),
+      layout: Some(&pipeline_layout),
+      vertex: wgpu::VertexState {
+        module: &module,
+        entry_point: "vs_main",
+        buffers: &[],
+      },
+      primitive: wgpu::PrimitiveState {
+        topology: wgpu::PrimitiveTopology::TriangleStrip,
+        ..Default::default()
+      },
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      fragment: Some(wgpu::FragmentState {
+        module: &module,
+        entry_point: "fs_main",
+        targets: &[Some(wgpu::ColorTargetState {
+          format: pixels.surface_texture_format(),
+          blend: Some(wgpu::BlendState::REPLACE),
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      multiview: None,
+    });
+    let sampler = |filter: wgpu::FilterMode| {
+      device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("pixel_renderer_gpu_sampler"),
+        mag_filter: filter,
+        min_filter: filter,
+        ..Default::default()
+      })
+    };
+    let nearest = sampler(wgpu::FilterMode::Nearest);
+    let linear = sampler(wgpu::FilterMode::Linear);
+    let placement = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("pixel_renderer_gpu_placement"),
+      size: 16,
+      usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+
+    if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+      return Err(error.to_string());
+    }
+    Ok(Self {
+      pipeline,
+      bind_group_layout,
+      nearest,
+      linear,
+      placement,
+    })
+  }
+
+  /// Clears the target and draws the buffer texture into `rect`
+  fn draw(
+    &self,
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::TextureView,
+    context: &pixels::PixelsContext,
+    rect: [f32; 4],
+    linear: bool,
+    clear: wgpu::Color,
+  ) {
+    let bytes: Vec<u8> = rect.iter().flat_map(|value| value.to_ne_bytes()).collect();
+    context.queue.write_buffer(&self.placement, 0, &bytes);
+
+    // The texture is recreated when the buffer size changes, so bind it per frame
+    let view = context
+      .texture
+      .create_view(&wgpu::TextureViewDescriptor::default());
+    let bind_group = context
+      .device
+      .create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("pixel_renderer_gpu_bind_group"),
+        layout: &self.bind_group_layout,
+        entries: &[
+          wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(&view),
+          },
+          wgpu::BindGroupEntry {
+            binding: 1,
+            resource: wgpu::BindingResource::Sampler(if linear {
+              &self.linear
+            } else {
+              &self.nearest
+            }),
+          },
+          wgpu::BindGroupEntry {
+            binding: 2,
+            resource: self.placement.as_entire_binding(),
+          },
+        ],
+      });
+
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("pixel_renderer_gpu_pass"),
+      color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+        view: target,
+        resolve_target: None,
+        ops: wgpu::Operations {
+          load: wgpu::LoadOp::Clear(clear),
+          store: wgpu::StoreOp::Store,
+        },
+      })],
+      depth_stencil_attachment: None,
+      timestamp_writes: None,
+      occlusion_query_set: None,
+    });
+    pass.set_pipeline(&self.pipeline);
+    pass.set_bind_group(0, &bind_group, &[]);
+    pass.draw(0..4, 0..1);
+  }
+}
+
+impl PixelRenderer {
+  /// Whether frames of this renderer can be scaled on the GPU
+  pub(super) fn gpu_eligible(&self) -> bool {
+    self.backend == Backend::Gpu
+      && self.viewport.is_none()
+      && self.padding == [0; 4]
+      && self.composite_mode == CompositeMode::Replace
+      && self.checker.is_none()
+      && self.scaler.is_none()
+      // The GPU samples bilinearly, which can't reproduce these downscaling filters
+      && !matches!(
+        self.current_filter(),
+        FilterMode::AreaAverage | FilterMode::Lanczos
+      )
+  }
+
+  /// Window rectangle the buffer is scaled into, as (x, y, width, height)
+  ///
+  /// Returns `None` for layouts the GPU path doesn't draw: tiling, and crops
+  /// other than Fill's centered one.
+  fn gpu_rect(&self, window_width: u32, window_height: u32) -> Option<(f64, f64, f64, f64)> {
+    let region = FrameRegion::full(FrameSize::packed(window_width, window_height));
+    let mode = self.resolve_scale_mode(self.scale_mode, region.width, region.height);
+    let (logical_width, logical_height) = self.logical_buffer_size();
+    let (x, y, width, height) = self.scaled_dimensions(logical_width, logical_height, region, mode);
+    let centered = |region: u32, size: u32| (region as f64 - size as f64) / 2.0;
+    match mode {
+      ScaleMode::Tile | ScaleMode::IntegerTile => None,
+      ScaleMode::Fill => Some((
+        centered(region.width, width),
+        centered(region.height, height),
+        width as f64,
+        height as f64,
+      )),
+      _ if width > region.width || height > region.height => None,
+      _ => Some((x as f64, y as f64, width as f64, height as f64)),
+    }
+  }
+
+  /// Presents the buffer scaled on the GPU
+  ///
+  /// Returns whether the frame was presented, or `None` without presenting
+  /// when the GPU path can't draw this frame, so the caller falls back to the
+  /// CPU path.
+  pub(super) fn present_gpu(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: &[u8],
+  ) -> napi::Result<Option<bool>, RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+    let window_size = window_guard.inner_size();
+    let Some((left, top, width, height)) = self.gpu_rect(window_size.width, window_size.height)
+    else {
+      return Ok(None);
+    };
+
+    let mut cache = RENDER_STATE
+      .lock()
+      .map_err(|_| super::render_state_poisoned_error())?;
+    let target = PresentTarget {
+      window_id: crate::tao::structs::window_id_to_u64(window_guard.id()),
+      window: Some(&window_guard),
+      width: window_size.width,
+      height: window_size.height,
+      min_present_interval: window.min_present_interval(),
+    };
+    let Some(state) = self.ready_state(&mut cache, &target)? else {
+      return Ok(Some(false));
+    };
+    let RenderState {
+      pixels,
+      gpu,
+      scratch,
+      ..
+    } = &mut *state;
+    let gpu = gpu.get_or_insert_with(|| match GpuScaler::new(pixels) {
+      Ok(scaler) => GpuState::Ready(Box::new(scaler)),
+      Err(e) => {
+        debug_log!("  GPU pipeline unavailable, using the CPU path: {}", e);
+        GpuState::Unavailable
+      }
+    });
+    let GpuState::Ready(scaler) = gpu else {
+      return Ok(None);
+    };
+
+    // The texture holds the buffer upright at its own size
+    let (buffer_width, buffer_height) = self.oriented_buffer_size();
+    let extent = pixels.context().texture_extent;
+    if (extent.width, extent.height) != (buffer_width, buffer_height) {
+      pixels
+        .resize_buffer(buffer_width, buffer_height)
+        .map_err(|e| {
+          RenderError::SurfaceCreationFailed.error(format!("Failed to resize texture: {:?}", e))
+        })?;
+    }
+
+    let started = Instant::now();
+    let mut timer = StageTimer::new(self.detailed_timing);
+    let frame = pixels.frame_mut();
+    let size = FrameSize::packed(buffer_width, buffer_height);
+    self.draw_scaled(
+      frame,
+      buffer,
+      FrameRegion::full(size),
+      ScaleMode::Stretch,
+      scratch,
+    );
+    apply_alpha_mode(frame, self.alpha_mode);
+    timer.end_copy();
+
+    // Clip space spans the window even while a debounced surface lags behind it
+    let (surface_width, surface_height) = (target.width as f64, target.height as f64);
+    let rect = [
+      (2.0 * left / surface_width - 1.0) as f32,
+      (1.0 - 2.0 * top / surface_height) as f32,
+      (2.0 * (left + width) / surface_width - 1.0) as f32,
+      (1.0 - 2.0 * (top + height) / surface_height) as f32,
+    ];
+    let linear = self.current_filter() != FilterMode::Nearest;
+    let clear = clear_color(self.bg_color, pixels.surface_texture_format().is_srgb());
+    pixels
+      .render_with(|encoder, render_target, context| {
+        scaler.draw(encoder, render_target, context, rect, linear, clear);
+        Ok(())
+      })
+      .map_err(|e| RenderError::PresentFailed.error(format!("Failed to render: {:?}", e)))?;
+    state.record_present(started, timer);
+    self.notify_present(target.window_id, state);
+    Ok(Some(true))
+  }
+}
+
+/// Gives a frame left at the buffer's size by the GPU path the surface's size again
+pub(super) fn restore_frame_size(state: &mut RenderState) {
+  let extent = state.pixels.context().texture_extent;
+  let surface = (state.last_window_width, state.last_window_height);
+  if state.gpu.is_some() && (extent.width, extent.height) != surface {
+    if let Err(e) = state.pixels.resize_buffer(surface.0, surface.1) {
+      debug_log!("  resize_buffer failed: {:?}", e);
+    }
+  }
+}
+
+/// Converts the background color to the clear color of a surface
+///
+/// sRGB surfaces take linear values and encode them when storing, so the
+/// cleared pixels end up with the same bytes as the CPU path's background.
+fn clear_color(color: [u8; 4], srgb: bool) -> wgpu::Color {
+  let channel = |value: u8| {
+    let value = value as f64 / 255.0;
+    if !srgb {
+      value
+    } else if value <= 0.04045 {
+      value / 12.92
+    } else {
+      ((value + 0.055) / 1.055).powf(2.4)
+    }
+  };
+  wgpu::Color {
+    r: channel(color[0]),
+    g: channel(color[1]),
+    b: channel(color[2]),
+    a: color[3] as f64 / 255.0,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_clear_color_decodes_srgb() {
+    let color = clear_color([255, 0, 188, 128], true);
+    assert_eq!((color.r, color.g), (1.0, 0.0));
+    assert!((color.b - 0.5).abs() < 0.01);
+    assert!((color.a - 0.502).abs() < 0.001);
+    assert_eq!(clear_color([51, 0, 0, 255], false).r, 0.2);
+  }
+
+  #[test]
+  fn test_gpu_rect_centers_fill_crop() {
+    let mut renderer = PixelRenderer::new(4, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::Fill);
+    // 4x2 filling 4x4 scales to 8x4, cropped equally on both sides
+    assert_eq!(renderer.gpu_rect(4, 4), Some((-2.0, 0.0, 8.0, 4.0)));
+    renderer.set_scale_mode(ScaleMode::Fit);
+    assert_eq!(renderer.gpu_rect(4, 4), Some((0.0, 1.0, 4.0, 2.0)));
+    renderer.set_scale_mode(ScaleMode::Tile);
+    assert_eq!(renderer.gpu_rect(4, 4), None);
+  }
+}
@@ -0,0 +1,155 @@
+//! Background presentation for pixel renderers
+//!
+//! A [`RenderHandle`] presents frames for one window on a dedicated worker
+//! thread, so the JavaScript thread only has to produce them.
+
+use super::error::RenderError;
+use super::{surface_unavailable_error, window_lock_error, PixelRenderer};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A frame waiting to be presented by the worker
+struct QueuedFrame {
+  buffer: Vec<u8>,
+  window_width: u32,
+  window_height: u32,
+}
+
+/// Presents frames for a single window from a background thread
+///
+/// Created with [`PixelRenderer::create_render_handle`]. The handle owns a
+/// reference to the native window and only releases it after the worker thread
+/// has stopped, so the cached surface can never be presented after its window
+/// is destroyed. A handle that outlives its `Window` releases the surface when
+/// it's dropped. Surfaces are created on the window's thread; if the worker
+/// needs a new one (e.g. after a failed resize), the submit after that reports
+/// an error and the next `render` on the window's thread recreates it.
+#[napi]
+pub struct RenderHandle {
+  /// Keeps the native window alive for as long as the worker may present to it
+  window: Arc<Mutex<crate::tao::structs::NativeWindow>>,
+  /// Renderer settings shared with the worker
+  renderer: Arc<PixelRenderer>,
+  /// Single-slot queue feeding the worker (None once closed)
+  sender: Option<SyncSender<QueuedFrame>>,
+  worker: Option<JoinHandle<()>>,
+  /// Error from the most recent failed background present
+  last_error: Arc<Mutex<Option<napi::Error<RenderError>>>>,
+}
+
+impl RenderHandle {
+  /// Spawns the worker thread for an already initialized window surface
+  pub(crate) fn spawn(
+    window: Arc<Mutex<crate::tao::structs::NativeWindow>>,
+    window_id: u64,
+    renderer: PixelRenderer,
+    min_present_interval: Duration,
+  ) -> napi::Result<Self, RenderError> {
+    let renderer = Arc::new(renderer);
+    let last_error = Arc::new(Mutex::new(None));
+    let (sender, receiver) = sync_channel::<QueuedFrame>(1);
+
+    let worker_renderer = renderer.clone();
+    let worker_error = last_error.clone();
+    let worker = std::thread::Builder::new()
+      .name("pixel-renderer".to_string())
+      .spawn(move || {
+        for frame in receiver {
+          worker_renderer.update_motion(&frame.buffer);
+          let result = worker_renderer.render_cached(
+            window_id,
+            None,
+            frame.window_width,
+            frame.window_height,
+            min_present_interval,
+            |out, size, timer, scratch| {
+              worker_renderer.draw_frame(out, &frame.buffer, size, timer, scratch);
+            },
+          );
+          if let Err(e) = result {
+            if let Ok(mut error) = worker_error.lock() {
+              *error = Some(e);
+            }
+          }
+        }
+      })
+      .map_err(|e| {
+        RenderError::SurfaceUnavailable.error(format!("Failed to spawn render thread: {}", e))
+      })?;
+
+    Ok(Self {
+      window,
+      renderer,
+      sender: Some(sender),
+      worker: Some(worker),
+      last_error,
+    })
+  }
+}
+
+#[napi]
+impl RenderHandle {
+  /// Queues a frame for the worker thread to present
+  ///
+  /// Returns false if the worker is still busy with the previous frame, in
+  /// which case this frame is dropped. Errors from earlier background presents
+  /// are returned here.
+  ///
+  /// # Arguments
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  #[napi]
+  pub fn submit(&self, buffer: Buffer) -> napi::Result<bool, RenderError> {
+    let last_error = self
+      .last_error
+      .lock()
+      .map_err(|_| RenderError::LockPoisoned.error("Failed to lock render handle error"))?
+      .take();
+    if let Some(error) = last_error {
+      return Err(error);
+    }
+    self.renderer.validate_buffer(&buffer)?;
+
+    let sender = self
+      .sender
+      .as_ref()
+      .ok_or_else(|| RenderError::SurfaceUnavailable.error("Render handle is closed"))?;
+
+    let window_size = self
+      .window
+      .lock()
+      .map_err(|_| window_lock_error())?
+      .inner_size();
+    let frame = QueuedFrame {
+      buffer: buffer.to_vec(),
+      window_width: window_size.width,
+      window_height: window_size.height,
+    };
+    match sender.try_send(frame) {
+      Ok(()) => Ok(true),
+      Err(TrySendError::Full(_)) => Ok(false),
+      Err(TrySendError::Disconnected(_)) => Err(surface_unavailable_error()),
+    }
+  }
+
+  /// Stops the worker thread after it finishes any queued frame
+  ///
+  /// Called automatically when the handle is dropped.
+  #[napi]
+  pub fn close(&mut self) {
+    self.sender = None;
+    if let Some(worker) = self.worker.take() {
+      let _ = worker.join();
+    }
+  }
+}
+
+impl Drop for RenderHandle {
+  fn drop(&mut self) {
+    // Join before the window reference is released
+    self.close();
+  }
+}
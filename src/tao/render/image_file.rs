@@ -0,0 +1,97 @@
+//! Image file rendering
+//!
+//! With the `image` cargo feature, `PixelRenderer.renderImageFile` decodes a
+//! PNG or JPEG file to RGBA and renders it in one call, for simple tools that
+//! would rather not decode in JavaScript. Raw buffers remain the primary API.
+
+use super::error::RenderError;
+use super::validation::validate_buffer_dimensions;
+use super::PixelRenderer;
+use crate::tao::enums::PixelFormat;
+use napi_derive::napi;
+use std::path::Path;
+
+#[napi]
+impl PixelRenderer {
+  /// Decodes a PNG or JPEG file and renders it to the given window
+  ///
+  /// The renderer takes the image's size as its buffer dimensions and `Rgba` as
+  /// its pixel format, clearing any source stride and source rectangle, then
+  /// draws the image like `render` with the current scale mode.
+  ///
+  /// Fails with `FileNotFound` when nothing exists at `path`, `DecodeFailed`
+  /// when the file can't be read or decoded, and the usual render error codes
+  /// when presenting fails.
+  ///
+  /// # Returns
+  /// Whether the frame was presented (see `render`)
+  #[napi]
+  pub fn render_image_file(
+    &mut self,
+    window: &crate::tao::structs::Window,
+    path: String,
+  ) -> napi::Result<bool, RenderError> {
+    let image = decode_rgba(Path::new(&path))?;
+    validate_buffer_dimensions(image.width(), image.height())
+      .map_err(|e| RenderError::DecodeFailed.wrap(e))?;
+    self.buffer_width = image.width();
+    self.buffer_height = image.height();
+    self.pixel_format = PixelFormat::Rgba;
+    self.source_stride = None;
+    self.source_rect = None;
+    self.render_bytes(window, image.as_raw(), None)
+  }
+}
+
+/// Reads an image file into RGBA pixels, detecting the format from its contents
+fn decode_rgba(path: &Path) -> napi::Result<image::RgbaImage, RenderError> {
+  let decode_failed = |reason: String| {
+    RenderError::DecodeFailed.error(format!(
+      "Failed to decode image {}: {}",
+      path.display(),
+      reason
+    ))
+  };
+  let reader = image::ImageReader::open(path).map_err(|e| match e.kind() {
+    std::io::ErrorKind::NotFound => {
+      RenderError::FileNotFound.error(format!("Image file not found: {}", path.display()))
+    }
+    _ => decode_failed(e.to_string()),
+  })?;
+  let image = reader
+    .with_guessed_format()
+    .map_err(|e| decode_failed(e.to_string()))?
+    .decode()
+    .map_err(|e| decode_failed(e.to_string()))?;
+  Ok(image.into_rgba8())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_decode_errors_are_distinguished() {
+    let missing = std::env::temp_dir().join("webview-napi-missing-image.png");
+    let error = decode_rgba(&missing).unwrap_err();
+    assert_eq!(error.status, RenderError::FileNotFound);
+
+    let garbage = std::env::temp_dir().join("webview-napi-garbage-image.png");
+    std::fs::write(&garbage, b"not an image").unwrap();
+    let error = decode_rgba(&garbage).unwrap_err();
+    let _ = std::fs::remove_file(&garbage);
+    assert_eq!(error.status, RenderError::DecodeFailed);
+  }
+
+  #[test]
+  fn test_decodes_png_to_rgba() {
+    let path = std::env::temp_dir().join("webview-napi-decode-test.png");
+    image::RgbImage::from_pixel(3, 2, image::Rgb([10, 20, 30]))
+      .save(&path)
+      .unwrap();
+    let decoded = decode_rgba(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(decoded.dimensions(), (3, 2));
+    assert_eq!(decoded.as_raw()[..4], [10, 20, 30, 255]);
+  }
+}
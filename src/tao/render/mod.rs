@@ -1,14 +1,34 @@
 //! Pixel renderer module
 //!
-//! Provides a minimal API for rendering RGBA pixel buffers to Tao windows.
+//! Provides a minimal API for rendering RGBA (or BGRA/RGB/BGR) pixel buffers to Tao windows.
 //! Uses the pixels crate which supports multiple backends (X11, DXGI, Cocoa).
 
-use crate::tao::enums::ScaleMode;
-use crate::tao::render::scaling::calculate_scaled_dimensions;
+use crate::tao::enums::{
+  AlphaMode, Anchor, Backend, CompositeMode, DitherMode, FilterMode, Origin, PixelFormat,
+  PresentOutcome, Rotation, ScaleMode, WrapMode,
+};
+use crate::tao::render::display_list::DrawCommand;
+use crate::tao::render::error::{into_napi_error, RenderError};
+pub use crate::tao::render::handle::RenderHandle;
+use crate::tao::render::motion::FrameSignature;
+use crate::tao::render::pixel_format::Palette;
+use crate::tao::render::scaler::{find_scaler, scaler_for_filter, Scaler};
+use crate::tao::render::scaling::{
+  anchor_position, balanced_span, calculate_balanced_fit, calculate_buffer_transform,
+  calculate_capped_stretch, calculate_scaled_dimensions, integer_scale, is_anchored_mode,
+  resolve_auto_mode, resolve_fit_down_stretch_up, DEFAULT_AUTO_ASPECT_TOLERANCE,
+};
+use crate::tao::render::scratch::FrameScratch;
+use crate::tao::render::tone::{apply_tint, ToneCurve};
+use crate::tao::render::validation::{
+  buffer_len, buffer_size_mismatch_message, buffer_too_large_message, validate_buffer_dimensions,
+};
+use crate::wry::structs::Rect;
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::cell::RefCell;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 // Debug logging macro - set to false to disable debug output
 const DEBUG_ENABLED: bool = false;
@@ -25,15 +45,101 @@ macro_rules! debug_log {
 /// Per-window rendering state to avoid resource exhaustion
 struct RenderState {
   pixels: pixels::Pixels<'static>,
+  /// Window width of the last successful surface resize
   last_window_width: u32,
+  /// Window height of the last successful surface resize
   last_window_height: u32,
+  /// Window size waiting to settle before the surface is resized to it
+  pending_resize: Option<PendingResize>,
+  /// When this window was last presented
+  last_present: Option<Instant>,
+  /// Frame presented last by a renderer skipping duplicate frames
+  last_frame: Option<FrameKey>,
+  /// Statistics about recent presents to this window
+  stats: RenderStats,
+  /// Recent frame times and present intervals behind the averages in `stats`
+  history: PresentHistory,
+  /// Working memory reused by every frame drawn into this window
+  scratch: FrameScratch,
+  /// GPU scaling pipeline (None until a GPU backend renderer presents)
+  #[cfg(feature = "gpu")]
+  gpu: Option<gpu::GpuState>,
+}
+
+impl RenderState {
+  /// Updates the statistics after a frame started at `started` was presented
+  fn record_present(&mut self, started: Instant, mut timer: StageTimer) {
+    timer.end_present();
+    let now = Instant::now();
+    if let Some(last_present) = self.last_present {
+      self.history.intervals.push(now - last_present);
+    }
+    self.history.frame_times.push(now - started);
+    self.last_present = Some(now);
+    timer.write_to(&mut self.stats);
+    self.history.write_to(&mut self.stats);
+  }
+}
+
+/// Identifies the frame a window shows, for skipping duplicate presents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameKey {
+  content: FrameContent,
+  /// Window size the frame was presented at; a resize must present again
+  window_width: u32,
+  window_height: u32,
+}
+
+/// How a frame's content is identified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameContent {
+  /// Hash of the buffer's bytes
+  Hash(u64),
+  /// ID supplied by the caller
+  Id(i64),
+}
+
+impl FrameContent {
+  /// Identifies a buffer by the caller's ID, or else by hashing it
+  fn new(buffer: &[u8], frame_id: Option<i64>) -> Self {
+    match frame_id {
+      Some(id) => FrameContent::Id(id),
+      None => {
+        let mut hasher = std::hash::DefaultHasher::new();
+        std::hash::Hasher::write(&mut hasher, buffer);
+        FrameContent::Hash(std::hash::Hasher::finish(&hasher))
+      }
+    }
+  }
+}
+
+impl FrameKey {
+  /// Identifies a buffer about to be presented to a window at its current size
+  fn new(
+    window: &crate::tao::structs::Window,
+    buffer: &[u8],
+    frame_id: Option<i64>,
+  ) -> napi::Result<Self, RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let size = window_arc
+      .lock()
+      .map_err(|_| window_lock_error())?
+      .inner_size();
+    Ok(Self {
+      content: FrameContent::new(buffer, frame_id),
+      window_width: size.width,
+      window_height: size.height,
+    })
+  }
 }
 
 /// Global cache for rendering state to avoid resource exhaustion errors.
 /// The key is the window ID. Works on all platforms (X11, DXGI, Cocoa).
-static RENDER_STATE: std::sync::LazyLock<
-  Mutex<RefCell<std::collections::HashMap<u64, RenderState>>>,
-> = std::sync::LazyLock::new(|| Mutex::new(RefCell::new(std::collections::HashMap::new())));
+static RENDER_STATE: std::sync::LazyLock<Mutex<std::collections::HashMap<u64, RenderState>>> =
+  std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
 
 /// Render options for pixel buffer display
 #[napi(object)]
@@ -45,8 +151,298 @@ pub struct RenderOptions {
   pub buffer_height: u32,
   /// Scaling mode (default: Fit)
   pub scale_mode: Option<ScaleMode>,
-  /// Background color for letterboxing [R, G, B, A] (default: [0, 0, 0, 255])
+  /// Background color for letterboxing, [R, G, B] or [R, G, B, A] (default: [0, 0, 0, 255])
   pub background_color: Option<Vec<u8>>,
+  /// Background color as one 0xRRGGBBAA number, e.g. 0x202020ff (default: none)
+  ///
+  /// Takes precedence over `background_color` when both are set.
+  pub background_color_rgba: Option<u32>,
+  /// Dithering used when quantizing high-precision samples to 8 bits (default: None)
+  pub dither: Option<DitherMode>,
+  /// Row order of the source buffer (default: TopLeft)
+  ///
+  /// Use `BottomLeft` for buffers read back from OpenGL, which start at the bottom row.
+  pub origin: Option<Origin>,
+  /// Pick sampling per frame based on motion (default: false)
+  ///
+  /// Fast-changing frames use cheap nearest sampling, while near-static frames
+  /// use bilinear filtering where detail is visible.
+  pub adaptive_quality: Option<bool>,
+  /// Record per-stage timings into `RenderStats` (default: false)
+  pub detailed_timing: Option<bool>,
+  /// Relative aspect ratio difference within which `ScaleMode.Auto` stretches
+  /// instead of fitting (default: 0.05)
+  pub auto_aspect_tolerance: Option<f64>,
+  /// How filters resolve samples that fall outside the buffer (default: Clamp)
+  pub wrap_mode: Option<WrapMode>,
+  /// Byte layout of source buffers (default: Rgba)
+  pub pixel_format: Option<PixelFormat>,
+  /// Sampling filter for scaled copies (default: Nearest)
+  ///
+  /// Ignored while adaptive quality is enabled, which picks the filter per frame.
+  pub filter_mode: Option<FilterMode>,
+  /// How alpha is written to the window (default: Straight)
+  ///
+  /// Non-opaque modes only show through where the platform surface supports
+  /// transparency (layered windows, compositing X11 window managers).
+  pub alpha_mode: Option<AlphaMode>,
+  /// How the buffer combines with what the window already shows (default: Replace)
+  ///
+  /// `SourceOver` skips the background clear and blends the buffer over the
+  /// previous frame by its alpha, for overlays.
+  pub composite_mode: Option<CompositeMode>,
+  /// Window pixels per buffer pixel before the scale mode applies (default: 1.0)
+  ///
+  /// Set to the window's `scaleFactor()` for buffers authored at logical
+  /// resolution, so they keep their size on HiDPI displays (e.g. with `ScaleMode.None`).
+  pub scale_factor: Option<f64>,
+  /// Window position of the first tile's top-left corner for `ScaleMode.Tile`
+  /// and `ScaleMode.IntegerTile`,
+  /// as [x, y] (default: [0, 0])
+  ///
+  /// Animate it to scroll the pattern.
+  pub tile_offset: Option<Vec<i32>>,
+  /// Mirror the buffer left to right (default: false)
+  pub flip_horizontal: Option<bool>,
+  /// Mirror the buffer top to bottom, on top of the origin's row order (default: false)
+  pub flip_vertical: Option<bool>,
+  /// Clockwise rotation of the buffer, applied after flips and before scaling
+  /// (default: None)
+  pub rotation: Option<Rotation>,
+  /// Gamma applied to color channels as they are copied; above 1 lifts the
+  /// midtones (default: none)
+  pub gamma: Option<f64>,
+  /// Multiplier applied to color channels after gamma (default: none)
+  pub brightness: Option<f64>,
+  /// Color multiplied into every buffer pixel as it is copied, [R, G, B] or
+  /// [R, G, B, A]; each channel is scaled by `tint / 255` (default: none)
+  ///
+  /// E.g. [255, 128, 128] tints toward red and [128, 128, 128] darkens by half.
+  pub tint: Option<Vec<u8>>,
+  /// Bytes from the start of one source row to the next, for buffers with
+  /// padded rows (default: buffer_width * bytes per pixel)
+  pub source_stride_bytes: Option<u32>,
+  /// Where the image sits in the window when it doesn't fill it, for the Fit,
+  /// Integer, IntegerFit, None, FitWidth and FitHeight scale modes (default: Center)
+  pub anchor: Option<Anchor>,
+  /// Largest aspect distortion `ScaleMode.Stretch` may apply before
+  /// letterboxing the rest, e.g. 0.1 for 10% (default: unlimited)
+  ///
+  /// The skew is `max(sx, sy) / min(sx, sy) - 1` for the horizontal and vertical
+  /// scales `sx` and `sy`. Beyond the limit, the larger scale is reduced to
+  /// `min(sx, sy) * (1 + max_aspect_skew)` and the image is centered.
+  pub max_aspect_skew: Option<f64>,
+  /// Round the image edges from their exact positions so opposite bars differ
+  /// by at most one pixel and stay put while resizing (default: false)
+  ///
+  /// Applies to centered `Fit`, which otherwise truncates the image size and
+  /// puts the leftover pixels on the bottom/right bar, and to the crop of
+  /// `Fill`. Off by default to keep existing pixel-exact layouts.
+  pub balanced_letterbox: Option<bool>,
+  /// Window sub-rectangle to render into, leaving the rest of the window
+  /// untouched (default: whole window)
+  ///
+  /// The background clear and scaling use the rectangle as the target area, the
+  /// same as `setViewport`.
+  pub clip_rect: Option<Rect>,
+  /// Margin of background left inside the window (or clip rectangle) on each
+  /// side, as [top, right, bottom, left] in window pixels (default: [0, 0, 0, 0])
+  ///
+  /// Every scale mode lays the image out within the remaining rectangle, while
+  /// the background fill still covers the margin.
+  pub padding: Option<Vec<u32>>,
+  /// Part of the buffer to display, in buffer pixels following the origin
+  /// (default: whole buffer)
+  ///
+  /// Only this rectangle is sampled, and the scale modes treat its size as the
+  /// buffer size. Animate it to pan and zoom over a large buffer without copying.
+  /// Must lie within the buffer.
+  pub source_rect: Option<Rect>,
+  /// Checkerboard drawn instead of the solid background color, so the edge of
+  /// the image stays visible (default: solid background color)
+  pub letterbox_checkerboard: Option<Checkerboard>,
+  /// Leave resizing the surface to `resizeSurface()` (default: false)
+  ///
+  /// Renders then fail with `SurfaceSizeMismatch` instead of resizing the
+  /// surface when the window size changed, for embedders that control when
+  /// resizes happen.
+  pub manual_resize: Option<bool>,
+  /// Where buffers are scaled (default: Cpu)
+  ///
+  /// `Gpu` needs the `gpu` build feature and falls back to the CPU when the
+  /// adapter can't create the pipeline or the configuration needs the CPU path.
+  /// Only `render` and `renderWithDimensions` use the GPU.
+  pub backend: Option<Backend>,
+  /// Milliseconds the window size must stay unchanged before the surface is
+  /// resized to it (default: 0, resize immediately)
+  ///
+  /// While a drag-resize is in progress, frames are composed at the previous
+  /// surface size and scaled to the window, then snap to the exact size once
+  /// the window has been stable for this long.
+  pub resize_debounce_ms: Option<u32>,
+  /// Skip presenting a frame identical to the one the window shows (default: false)
+  ///
+  /// Frames are compared by a hash of the buffer, or by the `frameId` passed to
+  /// `render`. A resized window is always presented again. Changing the
+  /// renderer's settings doesn't count as a change; pass a new frame ID.
+  pub skip_duplicate_frames: Option<bool>,
+}
+
+/// Statistics about presents to a window, returned by [`PixelRenderer::stats`]
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+  /// Microseconds spent clearing to the background color in the last frame
+  /// (only recorded with detailed timing)
+  pub clear_us: Option<u32>,
+  /// Microseconds spent copying and scaling the buffer in the last frame
+  /// (only recorded with detailed timing)
+  pub copy_us: Option<u32>,
+  /// Microseconds spent in the platform present call in the last frame
+  /// (only recorded with detailed timing)
+  pub present_us: Option<u32>,
+  /// Microseconds from the start of drawing to the end of presenting the last
+  /// frame, including any wait for a scheduled present
+  pub last_frame_us: Option<u32>,
+  /// Average of `last_frame_us` over recent frames
+  pub avg_frame_us: Option<u32>,
+  /// Presents per second over recent frames (needs at least two presents)
+  pub fps: Option<f64>,
+  /// Number of frames presented to the window
+  pub frames_presented: u32,
+}
+
+/// A successful present, passed to the callback registered with [`PixelRenderer::on_present`]
+#[napi(object, object_from_js = false)]
+#[derive(Debug, Clone)]
+pub struct PresentInfo {
+  /// Render cache ID of the window that was presented to
+  pub window_id: u64,
+  /// Microseconds from the start of drawing to the end of presenting the frame
+  pub frame_us: Option<u32>,
+  /// Number of frames presented to the window, including this one
+  pub frames_presented: u32,
+  /// When the frame was presented, in monotonic milliseconds (see `lastPresentTime`)
+  pub present_time: f64,
+}
+
+impl PresentInfo {
+  /// Describes the frame just presented from a window's render state
+  fn for_state(window_id: u64, state: &RenderState) -> Self {
+    Self {
+      window_id,
+      frame_us: state.stats.last_frame_us,
+      frames_presented: state.stats.frames_presented,
+      present_time: state.last_present.map_or(0.0, |instant| {
+        schedule::instant_micros(instant) as f64 / 1000.0
+      }),
+    }
+  }
+}
+
+/// Outcome of presenting to one window, returned by [`PixelRenderer::render_many`]
+#[napi(object, object_from_js = false)]
+#[derive(Debug, Clone)]
+pub struct WindowRenderResult {
+  /// ID of the window (0 if the window wasn't initialized)
+  pub window_id: u64,
+  /// Whether the frame was presented (or skipped by the window's frame rate limit)
+  pub success: bool,
+  /// Why presenting failed
+  pub error: Option<String>,
+  /// Code of the failure, to tell transient surface errors from programmer errors
+  pub error_code: Option<RenderError>,
+}
+
+/// Diagnostics about a window's render surface, returned by [`renderer_info`]
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct RendererInfo {
+  /// Whether the window has a cached render surface (false until the first render)
+  pub cached: bool,
+  /// Graphics API presenting the frames, e.g. "vulkan", "metal", "dx12" or "gl"
+  pub backend: Option<String>,
+  /// Name of the adapter (GPU or software rasterizer) behind the surface
+  pub adapter_name: Option<String>,
+  /// Whether the adapter renders in software on the CPU
+  pub software: Option<bool>,
+  /// Window width the surface was last sized for
+  pub last_window_width: Option<u32>,
+  /// Window height the surface was last sized for
+  pub last_window_height: Option<u32>,
+}
+
+/// Size of the render cache, returned by [`render_cache_stats`]
+#[napi(object, object_from_js = false)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheStats {
+  /// Number of windows with cached render state
+  pub window_count: u32,
+  /// Sum of the cached surfaces' sizes at 4 bytes per pixel
+  ///
+  /// An estimate of the frame memory held, not counting GPU-side copies.
+  pub approx_bytes: f64,
+}
+
+impl CacheStats {
+  /// Totals the cache for surfaces of the given window sizes
+  fn for_surfaces(sizes: impl Iterator<Item = (u32, u32)>) -> Self {
+    let mut stats = CacheStats {
+      window_count: 0,
+      approx_bytes: 0.0,
+    };
+    for (width, height) in sizes {
+      stats.window_count += 1;
+      stats.approx_bytes += width as f64 * height as f64 * 4.0;
+    }
+    stats
+  }
+}
+
+/// Where the scaled buffer lands in a window, returned by [`PixelRenderer::layout`]
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderLayout {
+  /// Window X of the image's left edge (including the viewport offset)
+  pub offset_x: u32,
+  /// Window Y of the image's top edge (including the viewport offset)
+  pub offset_y: u32,
+  /// Width of the scaled image in window pixels
+  pub scaled_width: u32,
+  /// Height of the scaled image in window pixels
+  pub scaled_height: u32,
+  /// Total width of the bars left and right of the image, 0 when the image
+  /// spans the window (or viewport) horizontally
+  ///
+  /// Centered images have half of it on each side.
+  pub letterbox_x: u32,
+  /// Total height of the bars above and below the image, 0 when the image
+  /// spans the window (or viewport) vertically
+  pub letterbox_y: u32,
+}
+
+/// Two-color checkerboard filling the letterbox area
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Checkerboard {
+  /// Color of the top-left cell, [R, G, B] or [R, G, B, A]
+  pub color_a: Vec<u8>,
+  /// Color of the cells between, [R, G, B] or [R, G, B, A]
+  pub color_b: Vec<u8>,
+  /// Side of each square cell in window pixels
+  pub cell_size: u32,
+}
+
+/// Window size and cache key read together, returned by [`PixelRenderer::window_metrics`]
+#[napi(object, object_from_js = false)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMetrics {
+  /// Inner width of the window in physical pixels
+  pub width: u32,
+  /// Inner height of the window in physical pixels
+  pub height: u32,
+  /// Key of the window's render state, for `clearRenderCache`
+  pub cache_id: u64,
 }
 
 impl Default for RenderOptions {
@@ -56,6 +452,37 @@ impl Default for RenderOptions {
       buffer_height: 600,
       scale_mode: Some(ScaleMode::Fit),
       background_color: Some(vec![0, 0, 0, 255]),
+      background_color_rgba: None,
+      dither: Some(DitherMode::None),
+      origin: Some(Origin::TopLeft),
+      adaptive_quality: Some(false),
+      detailed_timing: Some(false),
+      auto_aspect_tolerance: Some(DEFAULT_AUTO_ASPECT_TOLERANCE),
+      wrap_mode: Some(WrapMode::Clamp),
+      pixel_format: Some(PixelFormat::Rgba),
+      filter_mode: Some(FilterMode::Nearest),
+      alpha_mode: Some(AlphaMode::Straight),
+      composite_mode: Some(CompositeMode::Replace),
+      scale_factor: Some(1.0),
+      tile_offset: Some(vec![0, 0]),
+      flip_horizontal: Some(false),
+      flip_vertical: Some(false),
+      rotation: Some(Rotation::None),
+      gamma: None,
+      brightness: None,
+      tint: None,
+      source_stride_bytes: None,
+      anchor: Some(Anchor::Center),
+      max_aspect_skew: None,
+      balanced_letterbox: Some(false),
+      clip_rect: None,
+      padding: Some(vec![0, 0, 0, 0]),
+      source_rect: None,
+      letterbox_checkerboard: None,
+      manual_resize: Some(false),
+      backend: Some(Backend::Cpu),
+      resize_debounce_ms: Some(0),
+      skip_duplicate_frames: Some(false),
     }
   }
 }
@@ -71,521 +498,5169 @@ pub struct PixelRenderer {
   buffer_height: u32,
   scale_mode: ScaleMode,
   bg_color: [u8; 4],
+  /// Pattern drawn instead of `bg_color` behind the image (None = solid color)
+  checker: Option<CheckerFill>,
+  /// Applied by filters that blend pixels; plain 8-bit copies are never dithered
+  dither: DitherMode,
+  /// Window sub-rectangle that all scaling happens within (None = whole window)
+  viewport: Option<Rect>,
+  /// Background margins inside the viewport, as [top, right, bottom, left]
+  padding: [u32; 4],
+  /// Row order of the source buffer
+  origin: Origin,
+  /// Whether sampling adapts to inter-frame motion
+  adaptive_quality: bool,
+  /// Motion tracking for adaptive quality
+  motion: Mutex<MotionState>,
+  /// Whether each frame stage is timed into `RenderStats`
+  detailed_timing: bool,
+  /// Scaler chosen with `set_scaler` (None = follow the filter mode)
+  scaler: Option<Arc<dyn Scaler>>,
+  /// Aspect ratio tolerance used to resolve `ScaleMode::Auto`
+  auto_tolerance: f64,
+  /// Resolution of out-of-range source coordinates when sampling
+  wrap_mode: WrapMode,
+  /// Byte layout of source buffers
+  pixel_format: PixelFormat,
+  /// Filter for scaled copies when adaptive quality is off
+  filter_mode: FilterMode,
+  /// How alpha is written to the frame
+  alpha_mode: AlphaMode,
+  /// Whether the buffer replaces or blends over the frame's contents
+  composite_mode: CompositeMode,
+  /// Window pixels per buffer pixel (HiDPI scale factor)
+  scale_factor: f64,
+  /// Region position of the first tile in `ScaleMode::Tile` and `ScaleMode::IntegerTile`
+  tile_offset: (i32, i32),
+  /// Whether the buffer is mirrored left to right
+  flip_horizontal: bool,
+  /// Whether the buffer is mirrored top to bottom
+  flip_vertical: bool,
+  /// Rotation of the buffer before scaling
+  rotation: Rotation,
+  /// Gamma and brightness lookup table (None = channels are copied unchanged)
+  tone: Option<ToneCurve>,
+  /// Color multiplied into each pixel after the tone curve (None = untinted)
+  tint: Option<[u8; 4]>,
+  /// Bytes per source row (None = rows are packed)
+  source_stride: Option<u32>,
+  /// Part of the buffer that is displayed (None = whole buffer)
+  source_rect: Option<Rect>,
+  /// Alignment of letterboxed images in the region
+  anchor: Anchor,
+  /// Largest aspect distortion of Stretch (None = unlimited)
+  max_aspect_skew: Option<f64>,
+  /// Whether Fit bars and the Fill crop are rounded from exact positions
+  balanced_letterbox: bool,
+  /// How long a new window size must be stable before the surface follows it
+  resize_debounce: Duration,
+  /// Whether presents of the frame the window already shows are skipped
+  skip_duplicates: bool,
+  /// Whether the surface is only resized by `resize_surface`
+  manual_resize: bool,
+  /// Where buffers are scaled
+  backend: Backend,
+  /// Colors of `Indexed8` pixels
+  palette: Option<Box<Palette>>,
+  /// Called after every successful present (None = no callback)
+  on_present: Option<Arc<ThreadsafeFunction<PresentInfo>>>,
+}
+
+/// Inter-frame motion tracking used by adaptive quality
+#[derive(Debug)]
+struct MotionState {
+  /// Signature of the last rendered buffer
+  last_signature: Option<FrameSignature>,
+  /// Filter chosen for the last rendered buffer
+  filter: FilterMode,
+}
+
+impl Default for MotionState {
+  fn default() -> Self {
+    Self {
+      last_signature: None,
+      filter: FilterMode::Nearest,
+    }
+  }
 }
 
 #[napi]
 impl PixelRenderer {
   /// Creates a new pixel renderer with the given buffer dimensions
+  ///
+  /// Fails if either dimension is 0.
   #[napi(constructor)]
-  pub fn new(buffer_width: u32, buffer_height: u32) -> Self {
-    Self {
+  pub fn new(buffer_width: u32, buffer_height: u32) -> napi::Result<Self> {
+    validate_buffer_dimensions(buffer_width, buffer_height)?;
+    Ok(Self {
       buffer_width,
       buffer_height,
       scale_mode: ScaleMode::Fit,
       bg_color: [0, 0, 0, 255],
-    }
+      checker: None,
+      dither: DitherMode::None,
+      viewport: None,
+      padding: [0; 4],
+      origin: Origin::TopLeft,
+      adaptive_quality: false,
+      motion: Mutex::new(MotionState::default()),
+      detailed_timing: false,
+      scaler: None,
+      auto_tolerance: DEFAULT_AUTO_ASPECT_TOLERANCE,
+      wrap_mode: WrapMode::Clamp,
+      pixel_format: PixelFormat::Rgba,
+      filter_mode: FilterMode::Nearest,
+      alpha_mode: AlphaMode::Straight,
+      composite_mode: CompositeMode::Replace,
+      scale_factor: 1.0,
+      tile_offset: (0, 0),
+      flip_horizontal: false,
+      flip_vertical: false,
+      rotation: Rotation::None,
+      tone: None,
+      tint: None,
+      source_stride: None,
+      source_rect: None,
+      anchor: Anchor::Center,
+      max_aspect_skew: None,
+      balanced_letterbox: false,
+      resize_debounce: Duration::ZERO,
+      skip_duplicates: false,
+      manual_resize: false,
+      backend: Backend::Cpu,
+      palette: None,
+      on_present: None,
+    })
   }
 
   /// Creates a new pixel renderer with options
+  ///
+  /// Fails if either buffer dimension is 0, or if an option holds a value its
+  /// setter would reject. List options must have their documented length, e.g.
+  /// 3 (RGB, opaque) or 4 (RGBA) values for `background_color`, 2 for
+  /// `tile_offset` and 4 for `padding`.
   #[napi(factory)]
-  pub fn with_options(options: RenderOptions) -> Self {
-    let bg_color = options
-      .background_color
+  pub fn with_options(options: RenderOptions) -> napi::Result<Self> {
+    validate_buffer_dimensions(options.buffer_width, options.buffer_height)?;
+    let bg_color = match (options.background_color_rgba, &options.background_color) {
+      (Some(rgba), _) => rgba.to_be_bytes(),
+      (None, Some(color)) => parse_background_color(color)?,
+      (None, None) => [0, 0, 0, 255],
+    };
+
+    let gamma = options.gamma.unwrap_or(1.0);
+    tone::validate_gamma(gamma)?;
+    let brightness = options.brightness.unwrap_or(1.0);
+    tone::validate_brightness(brightness)?;
+    if let Some(max_skew) = options.max_aspect_skew {
+      validate_max_aspect_skew(max_skew)?;
+    }
+    let scale_factor = options.scale_factor.unwrap_or(1.0);
+    validate_scale_factor(scale_factor)?;
+    let auto_tolerance = options
+      .auto_aspect_tolerance
+      .unwrap_or(DEFAULT_AUTO_ASPECT_TOLERANCE);
+    validate_auto_aspect_tolerance(auto_tolerance)?;
+    if let Some(rect) = &options.source_rect {
+      validate_source_rect(rect, options.buffer_width, options.buffer_height)?;
+    }
+    if let Some(stride) = options.source_stride_bytes {
+      validate_source_stride(
+        stride,
+        options.buffer_width,
+        options.pixel_format.unwrap_or(PixelFormat::Rgba),
+      )
+      .map_err(into_napi_error)?;
+    }
+    let checker = options
+      .letterbox_checkerboard
       .as_ref()
-      .and_then(|c| {
-        if c.len() >= 4 {
-          Some([c[0], c[1], c[2], c[3]])
-        } else {
-          None
-        }
-      })
-      .unwrap_or([0, 0, 0, 255]);
+      .map(CheckerFill::parse)
+      .transpose()?;
 
-    Self {
+    Ok(Self {
       buffer_width: options.buffer_width,
       buffer_height: options.buffer_height,
       scale_mode: options.scale_mode.unwrap_or(ScaleMode::Fit),
       bg_color,
-    }
+      checker,
+      dither: options.dither.unwrap_or(DitherMode::None),
+      viewport: options.clip_rect,
+      padding: options
+        .padding
+        .as_deref()
+        .map(parse_padding)
+        .transpose()?
+        .unwrap_or([0; 4]),
+      origin: options.origin.unwrap_or(Origin::TopLeft),
+      adaptive_quality: options.adaptive_quality.unwrap_or(false),
+      motion: Mutex::new(MotionState::default()),
+      detailed_timing: options.detailed_timing.unwrap_or(false),
+      scaler: None,
+      auto_tolerance,
+      wrap_mode: options.wrap_mode.unwrap_or(WrapMode::Clamp),
+      pixel_format: options.pixel_format.unwrap_or(PixelFormat::Rgba),
+      filter_mode: options.filter_mode.unwrap_or(FilterMode::Nearest),
+      alpha_mode: options.alpha_mode.unwrap_or(AlphaMode::Straight),
+      composite_mode: options.composite_mode.unwrap_or(CompositeMode::Replace),
+      scale_factor,
+      tile_offset: options
+        .tile_offset
+        .as_deref()
+        .map(parse_tile_offset)
+        .transpose()?
+        .unwrap_or((0, 0)),
+      flip_horizontal: options.flip_horizontal.unwrap_or(false),
+      flip_vertical: options.flip_vertical.unwrap_or(false),
+      rotation: options.rotation.unwrap_or(Rotation::None),
+      tone: ToneCurve::new(gamma, brightness),
+      tint: options.tint.as_deref().map(parse_tint).transpose()?,
+      source_stride: options.source_stride_bytes,
+      source_rect: options.source_rect,
+      anchor: options.anchor.unwrap_or(Anchor::Center),
+      max_aspect_skew: options.max_aspect_skew,
+      balanced_letterbox: options.balanced_letterbox.unwrap_or(false),
+      resize_debounce: Duration::from_millis(options.resize_debounce_ms.unwrap_or(0) as u64),
+      skip_duplicates: options.skip_duplicate_frames.unwrap_or(false),
+      manual_resize: options.manual_resize.unwrap_or(false),
+      backend: options.backend.unwrap_or(Backend::Cpu),
+      palette: None,
+      on_present: None,
+    })
   }
 
-  /// Sets the scaling mode
+  // Chainable setters: each `with*` method applies the matching `set*` method
+  // and returns the renderer, e.g.
+  // `renderer.withScaleMode(ScaleMode.Fill).withBackgroundColor(0, 0, 0, 255)`.
+
+  /// Sets the scaling mode and returns the renderer for chaining
   #[napi]
-  pub fn set_scale_mode(&mut self, mode: ScaleMode) {
-    self.scale_mode = mode;
+  pub fn with_scale_mode(&mut self, mode: ScaleMode) -> napi::Result<&Self> {
+    self.set_scale_mode(mode);
+    Ok(self)
   }
 
-  /// Sets the background color
+  /// Sets the background color and returns the renderer for chaining
   #[napi]
-  pub fn set_background_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
-    self.bg_color = [r, g, b, a];
+  pub fn with_background_color(&mut self, r: u8, g: u8, b: u8, a: u8) -> napi::Result<&Self> {
+    self.set_background_color(r, g, b, a);
+    Ok(self)
   }
 
-  /// Renders a pixel buffer to the given window
-  ///
-  /// # Arguments
-  /// * `window` - The Tao window to render to
-  /// * `buffer` - RGBA pixel buffer (must be buffer_width * buffer_height * 4 bytes)
-  ///
-  /// # Performance Note
-  /// This method uses a global cache to avoid resource exhaustion errors
-  /// that occur when creating new contexts/surfaces on each render call.
-  /// Resources are cached per-window and reused across all PixelRenderer instances.
+  /// Sets the background color from one 0xRRGGBBAA number and returns the
+  /// renderer for chaining
   #[napi]
-  pub fn render(&self, window: &crate::tao::structs::Window, buffer: Buffer) -> napi::Result<()> {
-    let window_arc = window.inner.as_ref().ok_or_else(|| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        "Window not initialized".to_string(),
-      )
-    })?;
+  pub fn with_background_color_packed(&mut self, rgba: u32) -> napi::Result<&Self> {
+    self.set_background_color_packed(rgba);
+    Ok(self)
+  }
 
-    let window_guard = window_arc.lock().map_err(|_| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        "Failed to lock window".to_string(),
-      )
-    })?;
+  /// Sets the sampling filter and returns the renderer for chaining
+  #[napi]
+  pub fn with_filter_mode(&mut self, mode: FilterMode) -> napi::Result<&Self> {
+    self.set_filter_mode(mode);
+    Ok(self)
+  }
 
-    // Get the window ID for caching
-    let window_id = window_guard.id();
-    let window_id_u64 = unsafe {
-      let mut id_val: u64 = 0;
-      std::ptr::copy_nonoverlapping(
-        &window_id as *const _ as *const u8,
-        &mut id_val as *mut _ as *mut u8,
-        std::mem::size_of_val(&window_id).min(8),
-      );
-      id_val
-    };
+  /// Sets the letterbox anchor and returns the renderer for chaining
+  #[napi]
+  pub fn with_anchor(&mut self, anchor: Anchor) -> napi::Result<&Self> {
+    self.set_anchor(anchor);
+    Ok(self)
+  }
 
-    let window_size = window_guard.inner_size();
-    let window_width = window_size.width;
-    let window_height = window_size.height;
+  /// Sets the source pixel format and returns the renderer for chaining
+  #[napi]
+  pub fn with_pixel_format(&mut self, format: PixelFormat) -> napi::Result<&Self> {
+    self.set_pixel_format(format);
+    Ok(self)
+  }
 
-    // Validate buffer size
-    let expected_len = (self.buffer_width * self.buffer_height * 4) as usize;
-    if buffer.len() != expected_len {
-      return Err(napi::Error::new(
-        napi::Status::GenericFailure,
-        format!(
-          "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
-          buffer.len(),
-          expected_len,
-          self.buffer_width,
-          self.buffer_height
-        ),
-      ));
-    }
+  /// Sets the source row order and returns the renderer for chaining
+  #[napi]
+  pub fn with_origin(&mut self, origin: Origin) -> napi::Result<&Self> {
+    self.set_origin(origin);
+    Ok(self)
+  }
 
-    // Render using cached pixels instance
-    self.render_cached(
-      window_id_u64,
-      &window_guard,
-      &buffer,
-      window_width,
-      window_height,
-    )
+  /// Sets the rotation and returns the renderer for chaining
+  #[napi]
+  pub fn with_rotation(&mut self, rotation: Rotation) -> napi::Result<&Self> {
+    self.set_rotation(rotation);
+    Ok(self)
   }
 
-  /// Render using cached pixels instance (platform-agnostic)
-  fn render_cached(
-    &self,
-    window_id: u64,
-    window: &tao::window::Window,
-    buffer: &[u8],
-    window_width: u32,
-    window_height: u32,
-  ) -> napi::Result<()> {
-    // Get or create the rendering state from the global cache using entry API
-    let cache = RENDER_STATE.lock().map_err(|_| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        "Failed to lock render state cache".to_string(),
-      )
-    })?;
+  /// Sets the horizontal and vertical flips and returns the renderer for chaining
+  #[napi]
+  pub fn with_flip(&mut self, horizontal: bool, vertical: bool) -> napi::Result<&Self> {
+    self.set_flip_horizontal(horizontal);
+    self.set_flip_vertical(vertical);
+    Ok(self)
+  }
 
-    // Use entry API for single lookup - more efficient than contains_key + get_mut
-    let mut cache_ref = cache.borrow_mut();
-    let state = cache_ref.entry(window_id).or_insert_with(|| {
-      // Create new pixels instance with window dimensions
-      let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, window);
-      let new_pixels = pixels::Pixels::new(window_width, window_height, surface_texture)
-        .expect("Failed to create pixels instance");
-
-      // SAFETY: Extending lifetime to 'static is safe because:
-      // 1. The pixels instance is only used while the window is alive
-      // 2. The window ID is unique and won't be reused
-      // 3. We clean up when the window is closed
-      let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(new_pixels) };
-
-      RenderState {
-        pixels: static_pixels,
-        last_window_width: window_width,
-        last_window_height: window_height,
-      }
-    });
+  /// Sets the dithering mode and returns the renderer for chaining
+  #[napi]
+  pub fn with_dither_mode(&mut self, mode: DitherMode) -> napi::Result<&Self> {
+    self.set_dither_mode(mode);
+    Ok(self)
+  }
 
-    // Handle window resize if needed
-    let needs_resize =
-      state.last_window_width != window_width || state.last_window_height != window_height;
+  /// Sets how alpha is written and returns the renderer for chaining
+  #[napi]
+  pub fn with_alpha_mode(&mut self, mode: AlphaMode) -> napi::Result<&Self> {
+    self.set_alpha_mode(mode);
+    Ok(self)
+  }
 
-    if needs_resize {
-      debug_log!(
-        "  window resized: {}x{} -> {}x{}",
-        state.last_window_width,
-        state.last_window_height,
-        window_width,
-        window_height
-      );
+  /// Sets how the buffer combines with the window and returns the renderer for chaining
+  #[napi]
+  pub fn with_composite_mode(&mut self, mode: CompositeMode) -> napi::Result<&Self> {
+    self.set_composite_mode(mode);
+    Ok(self)
+  }
 
-      // Try to resize the surface texture to match the new window size
-      if let Err(e) = state.pixels.resize_surface(window_width, window_height) {
-        debug_log!(
-          "  resize_surface failed: {:?}, recreating pixels instance",
-          e
-        );
-        // If resize fails, fall back to recreating
-        // Drop the current borrow of the hashmap
-        drop(cache_ref);
-
-        // Get mutable access to cache and recreate
-        let mut cache_mut = cache.borrow_mut();
-        cache_mut.remove(&window_id);
-
-        let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, window);
-        let new_pixels = pixels::Pixels::new(window_width, window_height, surface_texture)
-          .map_err(|e| {
-            napi::Error::new(
-              napi::Status::GenericFailure,
-              format!("Failed to create pixels: {:?}", e),
-            )
-          })?;
+  /// Sets the scale factor and returns the renderer for chaining
+  ///
+  /// Fails like `setScaleFactor`.
+  #[napi]
+  pub fn with_scale_factor(&mut self, scale_factor: f64) -> napi::Result<&Self> {
+    self.set_scale_factor(scale_factor)?;
+    Ok(self)
+  }
 
-        let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(new_pixels) };
+  /// Sets the gamma and returns the renderer for chaining
+  ///
+  /// Fails like `setGamma`.
+  #[napi]
+  pub fn with_gamma(&mut self, gamma: Option<f64>) -> napi::Result<&Self> {
+    self.set_gamma(gamma)?;
+    Ok(self)
+  }
 
-        cache_mut.insert(
-          window_id,
-          RenderState {
-            pixels: static_pixels,
-            last_window_width: window_width,
-            last_window_height: window_height,
-          },
-        );
+  /// Sets the scaling mode
+  #[napi]
+  pub fn set_scale_mode(&mut self, mode: ScaleMode) {
+    self.scale_mode = mode;
+  }
 
-        // Get the newly inserted state
-        let state = cache_mut.get_mut(&window_id).ok_or_else(|| {
-          napi::Error::new(
-            napi::Status::GenericFailure,
-            "Render state not available after recreation".to_string(),
-          )
-        })?;
+  /// Sets the aspect ratio tolerance used by `ScaleMode.Auto`
+  ///
+  /// `tolerance` is the relative difference (0.05 = 5%) between the buffer and
+  /// window aspect ratios below which the buffer is stretched instead of fitted.
+  #[napi]
+  pub fn set_auto_aspect_tolerance(&mut self, tolerance: f64) -> napi::Result<()> {
+    validate_auto_aspect_tolerance(tolerance)?;
+    self.auto_tolerance = tolerance;
+    Ok(())
+  }
 
-        // Continue with rendering using the new state
-        return self.render_with_state(state, buffer, window_width, window_height);
-      } else {
-        // Also resize the pixel buffer to match window dimensions
-        if let Err(e) = state.pixels.resize_buffer(window_width, window_height) {
-          debug_log!("  resize_buffer failed: {:?}", e);
-        }
+  /// Returns the scale mode actually used for the window at its current size
+  ///
+  /// Resolves `ScaleMode.Auto` and `ScaleMode.FitDownStretchUp` against the
+  /// window (or viewport) size; other modes are returned unchanged.
+  #[napi]
+  pub fn resolved_scale_mode(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<ScaleMode> {
+    let size = window.inner_size()?;
+    let region = self.target_region(size.width as u32, size.height as u32);
+    Ok(self.resolve_scale_mode(self.scale_mode, region.width, region.height))
+  }
 
-        // Update cached window size
-        state.last_window_width = window_width;
-        state.last_window_height = window_height;
-        debug_log!(
-          "  resized surface and buffer to {}x{}",
-          window_width,
-          window_height
-        );
-      }
-    }
+  /// Sets how many window pixels each buffer pixel covers before scaling
+  ///
+  /// Pass the window's `scaleFactor()` when the buffer is authored at logical
+  /// resolution. The buffer then behaves as if it were `scale_factor` times
+  /// larger, e.g. `ScaleMode.None` shows it at its logical size instead of a
+  /// quarter of the area on a 2x display. Call again when the window moves to a
+  /// monitor with a different scale factor.
+  #[napi]
+  pub fn set_scale_factor(&mut self, scale_factor: f64) -> napi::Result<()> {
+    validate_scale_factor(scale_factor)?;
+    self.scale_factor = scale_factor;
+    Ok(())
+  }
 
-    self.render_with_state(state, buffer, window_width, window_height)
+  /// Sets where the image sits in the window when it doesn't fill it
+  ///
+  /// Applies to the modes that letterbox (Fit, Integer, IntegerFit, None, FitWidth and
+  /// FitHeight, including Auto and FitDownStretchUp when they resolve to Fit). With
+  /// None, FitWidth and FitHeight, an image larger than the window is cropped from
+  /// the side away from the anchor.
+  #[napi]
+  pub fn set_anchor(&mut self, anchor: Anchor) {
+    self.anchor = anchor;
   }
 
-  /// Render using an already acquired state
-  fn render_with_state(
-    &self,
-    state: &mut RenderState,
-    buffer: &[u8],
-    window_width: u32,
-    window_height: u32,
-  ) -> napi::Result<()> {
-    // Apply scaling if needed
-    let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions(
-      self.buffer_width,
-      self.buffer_height,
-      window_width,
-      window_height,
-      self.scale_mode,
-    );
+  /// Caps how far `ScaleMode.Stretch` may distort the aspect ratio
+  ///
+  /// See `RenderOptions.maxAspectSkew` for the formula. Pass `null` to stretch
+  /// without a limit. Fails if the skew is negative or not finite.
+  #[napi]
+  pub fn set_max_aspect_skew(&mut self, max_skew: Option<f64>) -> napi::Result<()> {
+    if let Some(max_skew) = max_skew {
+      validate_max_aspect_skew(max_skew)?;
+    }
+    self.max_aspect_skew = max_skew;
+    Ok(())
+  }
 
-    debug_log!(
-      "render_with_state: buffer={}x{}, window={}x{}, scale_mode={:?}",
-      self.buffer_width,
-      self.buffer_height,
-      window_width,
-      window_height,
-      self.scale_mode
-    );
-    debug_log!(
-      "  calculated: offset=({}, {}), scaled={}x{}",
-      offset_x,
-      offset_y,
-      scaled_width,
-      scaled_height
-    );
+  /// Registers a callback invoked after each successful present
+  ///
+  /// The callback receives the window's render cache ID and the frame's timing.
+  /// It runs later on the JavaScript thread, never inside the render call, so
+  /// it may render again without deadlocking. Frames skipped as duplicates or
+  /// by the window's frame rate limit don't invoke it. Pass `null` to remove it.
+  #[napi(ts_args_type = "callback: ((error: Error | null, info: PresentInfo) => void) | null")]
+  pub fn on_present(&mut self, callback: Option<ThreadsafeFunction<PresentInfo>>) {
+    self.on_present = callback.map(Arc::new);
+  }
 
-    // Copy buffer to pixel frame
-    let frame = state.pixels.frame_mut();
-    debug_log!(
-      "  frame.len()={}, expected={}",
-      frame.len(),
-      window_width * window_height * 4
-    );
+  /// Sets whether `render` skips frames identical to the one the window shows
+  ///
+  /// See `RenderOptions.skipDuplicateFrames`.
+  #[napi]
+  pub fn set_skip_duplicate_frames(&mut self, skip: bool) {
+    self.skip_duplicates = skip;
+  }
 
-    // Clear with background color first
-    for pixel in frame.chunks_exact_mut(4) {
-      pixel.copy_from_slice(&self.bg_color);
-    }
+  /// Sets whether letterbox bars are balanced from the image's exact position
+  ///
+  /// See `RenderOptions.balancedLetterbox`.
+  #[napi]
+  pub fn set_balanced_letterbox(&mut self, balanced: bool) {
+    self.balanced_letterbox = balanced;
+  }
 
-    // Copy source buffer with scaling
-    // The frame buffer is sized to window_width x window_height
-    // We need to scale the source buffer to fit properly
-    match self.scale_mode {
-      ScaleMode::Stretch => {
-        // Stretch mode: scale entire buffer to fill window
-        scale_buffer_nearest_neighbor(
-          frame,
-          buffer,
-          self.buffer_width,
-          self.buffer_height,
-          window_width,
-          window_height,
-        );
-      }
-      ScaleMode::None => {
-        // Center without scaling, crop if buffer is larger than window
-        copy_buffer_centered_crop(
-          frame,
-          buffer,
-          self.buffer_width,
-          self.buffer_height,
-          window_width,
-          window_height,
-        );
-      }
-      ScaleMode::Fill => {
-        // Fill mode: scale buffer maintaining aspect ratio to fill window
-        scale_buffer_fill(
-          frame,
-          buffer,
-          self.buffer_width,
-          self.buffer_height,
-          window_width,
-          window_height,
-        );
-      }
-      _ => {
-        // Fit, Integer - scale buffer maintaining aspect ratio to fit within window
-        scale_buffer_fit(
-          frame,
-          buffer,
-          ScaleBufferFitParams {
-            buffer_width: self.buffer_width,
-            buffer_height: self.buffer_height,
-            window_width,
-            window_height,
-            offset_x,
-            offset_y,
-            scaled_width,
-            scaled_height,
-          },
-        );
-      }
+  /// Sets how long the window size must stay unchanged before the surface is
+  /// resized to it, in milliseconds
+  ///
+  /// Coalesces the surface resizes of a drag-resize into one. Until the size
+  /// settles, frames are drawn at the previous surface size and scaled to the
+  /// window. Pass 0 to resize on every size change.
+  #[napi]
+  pub fn set_resize_debounce_ms(&mut self, millis: u32) {
+    self.resize_debounce = Duration::from_millis(millis as u64);
+  }
+
+  /// Turns off resizing the surface as part of rendering
+  ///
+  /// While enabled, renders to a window whose size no longer matches its
+  /// surface fail with `SurfaceSizeMismatch` until `resizeSurface` is called.
+  #[napi]
+  pub fn set_manual_resize(&mut self, manual: bool) {
+    self.manual_resize = manual;
+  }
+
+  /// Resizes the window's surface to the window's current size
+  ///
+  /// Needed after the window resizes when manual resizing is enabled. Does
+  /// nothing if the window has no surface yet or its size already matches.
+  #[napi]
+  pub fn resize_surface(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<(), RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+    let window_id = crate::tao::structs::window_id_to_u64(window_guard.id());
+    let size = window_guard.inner_size();
+    let mut cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let Some(state) = cache.get_mut(&window_id) else {
+      return Ok(());
+    };
+    let resized = (state.last_window_width, state.last_window_height) == (size.width, size.height)
+      || size.width == 0
+      || size.height == 0
+      || resize_render_state(state, size.width, size.height);
+    if !resized {
+      return Err(RenderError::SurfaceSizeMismatch.error(format!(
+        "Failed to resize the surface to {}x{}",
+        size.width, size.height
+      )));
     }
+    Ok(())
+  }
 
-    // Render
-    state.pixels.render().map_err(|e| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        format!("Failed to render: {:?}", e),
-      )
-    })?;
+  /// Sets where the first tile starts for `ScaleMode.Tile` and `ScaleMode.IntegerTile`
+  ///
+  /// The offset is in window pixels relative to the window (or viewport) and
+  /// may be negative or larger than the buffer; animate it to scroll the pattern.
+  #[napi]
+  pub fn set_tile_offset(&mut self, x: i32, y: i32) {
+    self.tile_offset = (x, y);
+  }
+
+  /// Sets the background color
+  #[napi]
+  pub fn set_background_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
+    self.bg_color = [r, g, b, a];
+  }
+
+  /// Sets the background color from one 0xRRGGBBAA number
+  #[napi]
+  pub fn set_background_color_packed(&mut self, rgba: u32) {
+    self.bg_color = rgba.to_be_bytes();
+  }
 
+  /// Fills the letterbox area with a checkerboard instead of the background color
+  ///
+  /// Cells start at the top-left corner of the window (or viewport). Pass `null`
+  /// to go back to the solid background color. Fails if a color isn't 3 or 4
+  /// values long or the cell size is 0.
+  #[napi]
+  pub fn set_letterbox_checkerboard(
+    &mut self,
+    checkerboard: Option<Checkerboard>,
+  ) -> napi::Result<()> {
+    self.checker = checkerboard.as_ref().map(CheckerFill::parse).transpose()?;
     Ok(())
   }
-}
 
-/// Simple function to render a pixel buffer to a window
-///
-/// This is a convenience function for one-off renders.
-/// For repeated rendering, use [`PixelRenderer`] instead.
-///
-/// # Warning
-/// Using this function repeatedly (200+ times) may cause resource exhaustion errors.
-/// For repeated rendering, create a [`PixelRenderer`] instance and reuse it.
-#[napi]
-pub fn render_pixels(
-  window: &crate::tao::structs::Window,
-  buffer: Buffer,
-  buffer_width: u32,
-  buffer_height: u32,
-) -> napi::Result<()> {
-  let renderer = PixelRenderer::new(buffer_width, buffer_height);
-  renderer.render(window, buffer)
-}
+  /// Sets the dithering mode used when reducing to 8 bits per channel
+  #[napi]
+  pub fn set_dither_mode(&mut self, mode: DitherMode) {
+    self.dither = mode;
+  }
 
-pub mod buffer_ops;
-pub mod scaling;
+  /// Mirrors the buffer left to right, e.g. for front-facing camera frames
+  ///
+  /// Applies before scaling, so scale modes letterbox and crop the mirrored image
+  /// the same way as the original.
+  #[napi]
+  pub fn set_flip_horizontal(&mut self, flip: bool) {
+    self.flip_horizontal = flip;
+  }
 
-/// Scales buffer to fill the entire window using nearest neighbor
-fn scale_buffer_nearest_neighbor(
-  frame: &mut [u8],
-  buffer: &[u8],
-  buffer_width: u32,
-  buffer_height: u32,
-  window_width: u32,
-  window_height: u32,
-) {
-  for y in 0..window_height {
-    for x in 0..window_width {
-      let src_x = (x as f32 * buffer_width as f32 / window_width as f32)
-        .min(buffer_width as f32 - 1.0) as u32;
-      let src_y = (y as f32 * buffer_height as f32 / window_height as f32)
-        .min(buffer_height as f32 - 1.0) as u32;
+  /// Mirrors the buffer top to bottom
+  ///
+  /// Combines with the origin: flipping a `BottomLeft` buffer shows it as if it
+  /// were `TopLeft`.
+  #[napi]
+  pub fn set_flip_vertical(&mut self, flip: bool) {
+    self.flip_vertical = flip;
+  }
 
-      let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
-      let dst_idx = ((y * window_width + x) * 4) as usize;
+  /// Rotates the buffer clockwise before scaling
+  ///
+  /// Rotation happens in source space: the scale mode lays out the rotated
+  /// image, so `Cw90` and `Cw270` fit a landscape buffer as portrait. Flips
+  /// and the origin apply to the buffer before it is rotated.
+  #[napi]
+  pub fn set_rotation(&mut self, rotation: Rotation) {
+    self.rotation = rotation;
+  }
 
-      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
-      }
+  /// Sets the gamma applied to color channels as they are copied
+  ///
+  /// Channels become `255 * (value / 255)^(1 / gamma)`, so values above 1
+  /// brighten the midtones. Pass `null` to turn gamma correction off.
+  #[napi]
+  pub fn set_gamma(&mut self, gamma: Option<f64>) -> napi::Result<()> {
+    let gamma = gamma.unwrap_or(1.0);
+    tone::validate_gamma(gamma)?;
+    let brightness = self.tone.map_or(1.0, |tone| tone.brightness());
+    self.tone = ToneCurve::new(gamma, brightness);
+    Ok(())
+  }
+
+  /// Sets the color multiplied into every buffer pixel as it is copied
+  ///
+  /// See `RenderOptions.tint`. Pass `null` to remove the tint. Fails unless
+  /// the tint has 3 (RGB) or 4 (RGBA) values.
+  #[napi]
+  pub fn set_tint(&mut self, tint: Option<Vec<u8>>) -> napi::Result<()> {
+    self.tint = tint.as_deref().map(parse_tint).transpose()?;
+    Ok(())
+  }
+
+  /// Sets the multiplier applied to color channels after gamma
+  ///
+  /// Pass `null` to turn the brightness adjustment off.
+  #[napi]
+  pub fn set_brightness(&mut self, brightness: Option<f64>) -> napi::Result<()> {
+    let brightness = brightness.unwrap_or(1.0);
+    tone::validate_brightness(brightness)?;
+    let gamma = self.tone.map_or(1.0, |tone| tone.gamma());
+    self.tone = ToneCurve::new(gamma, brightness);
+    Ok(())
+  }
+
+  /// Sets the bytes from the start of one source row to the next
+  ///
+  /// For decoders that pad rows; buffers must then hold `stride * buffer_height`
+  /// bytes. Pass `null` for packed rows (`buffer_width` * bytes per pixel).
+  /// Fails if the stride is shorter than a row in the current pixel format.
+  #[napi]
+  pub fn set_source_stride_bytes(&mut self, stride: Option<u32>) -> napi::Result<()> {
+    if let Some(stride) = stride {
+      validate_source_stride(stride, self.buffer_width, self.pixel_format)
+        .map_err(into_napi_error)?;
     }
+    self.source_stride = stride;
+    Ok(())
   }
-}
 
-/// Centers buffer without scaling, cropping if necessary
-fn copy_buffer_centered_crop(
-  frame: &mut [u8],
-  buffer: &[u8],
-  buffer_width: u32,
-  buffer_height: u32,
-  window_width: u32,
-  window_height: u32,
-) {
-  let crop_x = buffer_width.saturating_sub(window_width) / 2;
-  let crop_y = buffer_height.saturating_sub(window_height) / 2;
-  let copy_width = buffer_width.min(window_width);
-  let copy_height = buffer_height.min(window_height);
-  let start_x = (window_width.saturating_sub(buffer_width)) / 2;
-  let start_y = (window_height.saturating_sub(buffer_height)) / 2;
+  /// Sets the part of the buffer to display
+  ///
+  /// `rect` is in buffer pixels following the configured origin. Only it is
+  /// sampled, and the scale modes treat its size as the buffer size, so moving
+  /// or resizing it between frames pans and zooms. Pass `null` to display the
+  /// whole buffer. Fails if the rectangle is empty or extends past the buffer.
+  #[napi]
+  pub fn set_source_rect(&mut self, rect: Option<Rect>) -> napi::Result<()> {
+    if let Some(rect) = &rect {
+      validate_source_rect(rect, self.buffer_width, self.buffer_height)?;
+    }
+    self.source_rect = rect;
+    Ok(())
+  }
 
-  for y in 0..copy_height {
-    for x in 0..copy_width {
-      let src_x = crop_x + x;
-      let src_y = crop_y + y;
-      let dst_x = start_x + x;
-      let dst_y = start_y + y;
+  /// Sets the row order of source buffers
+  ///
+  /// `BottomLeft` flips the buffer vertically while copying, so OpenGL readbacks
+  /// display upright without a separate flip pass.
+  #[napi]
+  pub fn set_origin(&mut self, origin: Origin) {
+    self.origin = origin;
+  }
 
-      let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
-      let dst_idx = ((dst_y * window_width + dst_x) * 4) as usize;
+  /// Sets the byte layout of source buffers
+  ///
+  /// Channels are reordered while copying, so BGRA video frames or 24-bit RGB
+  /// images can be rendered without converting them in JavaScript first.
+  #[napi]
+  pub fn set_pixel_format(&mut self, format: PixelFormat) {
+    self.pixel_format = format;
+  }
 
-      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
-      }
+  /// Sets the colors of `PixelFormat.Indexed8` pixels
+  ///
+  /// `palette` holds 256 RGBA colors (1024 bytes); pixel value N is drawn with
+  /// color N. Indexed buffers can't be rendered until a palette is set.
+  #[napi]
+  pub fn set_palette(&mut self, palette: Buffer) -> napi::Result<()> {
+    self.palette = Some(Box::new(Palette::from_rgba(&palette)?));
+    Ok(())
+  }
+
+  /// Sets the sampling filter for scaled copies
+  ///
+  /// `Bilinear` blends the four nearest source pixels, which looks smoother
+  /// than `Nearest` when upscaling photos or video. `AreaAverage` averages every
+  /// source pixel behind a window pixel when downscaling, avoiding the moiré of
+  /// skipped pixels in thumbnails of detailed images. `Lanczos` keeps the most
+  /// detail when downscaling photos but is many times slower, so use it for
+  /// still images rather than video. Copies at 1:1 scale always
+  /// use nearest sampling since filtering can't change them. Ignored while
+  /// adaptive quality is enabled.
+  #[napi]
+  pub fn set_filter_mode(&mut self, mode: FilterMode) {
+    self.filter_mode = mode;
+  }
+
+  /// Sets how alpha is written to the window
+  ///
+  /// `Straight` and `Premultiplied` keep the source and background alpha, so a
+  /// semi-transparent HUD can be drawn over the desktop where the platform
+  /// surface supports transparency. `Opaque` writes every pixel fully opaque.
+  #[napi]
+  pub fn set_alpha_mode(&mut self, mode: AlphaMode) {
+    self.alpha_mode = mode;
+  }
+
+  /// Sets whether the buffer replaces or blends over the window's contents
+  ///
+  /// With `SourceOver` the background isn't cleared; the buffer is blended by
+  /// its alpha over whatever the previous present left in the surface, and the
+  /// letterbox bars keep their old contents.
+  #[napi]
+  pub fn set_composite_mode(&mut self, mode: CompositeMode) {
+    self.composite_mode = mode;
+  }
+
+  /// Sets how samples outside the buffer are resolved
+  ///
+  /// Matters for filters that read neighboring pixels: with `Wrap`, the
+  /// bilinear filter blends opposite edges so tiled textures scale seamlessly.
+  #[napi]
+  pub fn set_wrap_mode(&mut self, mode: WrapMode) {
+    self.wrap_mode = mode;
+  }
+
+  /// Enables or disables motion-adaptive sampling quality
+  ///
+  /// When enabled, each rendered buffer is compared with the previous one:
+  /// frames with large changes use nearest sampling (motion masks detail), while
+  /// near-static frames use bilinear filtering. Useful for video playback.
+  #[napi]
+  pub fn set_adaptive_quality(&mut self, enabled: bool) {
+    self.adaptive_quality = enabled;
+    if let Ok(mut motion) = self.motion.lock() {
+      *motion = MotionState::default();
     }
   }
-}
 
-/// Scales buffer to fill window, maintaining aspect ratio by cropping
-fn scale_buffer_fill(
-  frame: &mut [u8],
-  buffer: &[u8],
-  buffer_width: u32,
-  buffer_height: u32,
-  window_width: u32,
-  window_height: u32,
-) {
-  let buffer_aspect = buffer_width as f32 / buffer_height as f32;
-  let window_aspect = window_width as f32 / window_height as f32;
+  /// Returns the filter used for the most recently rendered buffer
+  #[napi]
+  pub fn last_filter_mode(&self) -> FilterMode {
+    self.current_filter()
+  }
 
-  let (crop_x, crop_y, crop_width, crop_height) = if buffer_aspect > window_aspect {
-    let new_width = (buffer_height as f32 * window_aspect) as u32;
-    ((buffer_width - new_width) / 2, 0, new_width, buffer_height)
-  } else {
-    let new_height = (buffer_width as f32 / window_aspect) as u32;
-    (
-      0,
-      (buffer_height - new_height) / 2,
-      buffer_width,
-      new_height,
-    )
-  };
+  /// Enables or disables timing of the clear, copy and present stages
+  ///
+  /// Off by default to keep the extra clock reads out of the hot path.
+  #[napi]
+  pub fn set_detailed_timing(&mut self, enabled: bool) {
+    self.detailed_timing = enabled;
+  }
+
+  /// Returns statistics for the window, or null if it was never rendered to
+  #[napi]
+  pub fn stats(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<Option<RenderStats>, RenderError> {
+    let Some(window_arc) = &window.inner else {
+      return Ok(None);
+    };
+    let window_id = crate::tao::structs::window_id_to_u64(
+      window_arc.lock().map_err(|_| window_lock_error())?.id(),
+    );
+    let cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let stats = cache.get(&window_id).map(|state| state.stats.clone());
+    Ok(stats)
+  }
 
-  for y in 0..window_height {
-    for x in 0..window_width {
-      let src_x = crop_x
-        + (x as f32 * crop_width as f32 / window_width as f32).min(crop_width as f32 - 1.0) as u32;
-      let src_y = crop_y
-        + (y as f32 * crop_height as f32 / window_height as f32).min(crop_height as f32 - 1.0)
-          as u32;
+  /// Returns when the window last presented a frame, or null if it never did
+  ///
+  /// The time is in milliseconds on the clock of `monotonicTimeMicros()`, so
+  /// schedulers can compare it to the current time without extra bookkeeping.
+  #[napi]
+  pub fn last_present_time(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<Option<f64>, RenderError> {
+    let window_id = window
+      .id()
+      .map_err(|e| RenderError::WindowNotInitialized.wrap(e))?;
+    let cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let last_present = cache.get(&window_id).and_then(|state| state.last_present);
+    Ok(last_present.map(|instant| schedule::instant_micros(instant) as f64 / 1000.0))
+  }
 
-      let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
-      let dst_idx = ((y * window_width + x) * 4) as usize;
+  /// Reads back the frame most recently presented to the window
+  ///
+  /// Returns the window-sized RGBA pixels after scaling, letterboxing and the
+  /// alpha mode were applied, e.g. for visual regression tests that diff
+  /// against a golden image. Fails with `NothingRendered` if nothing has been
+  /// rendered to the window yet.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to capture
+  /// * `premultiplied` - Multiply color channels by alpha in the result (default: false);
+  ///   frames already rendered with `AlphaMode.Premultiplied` are returned unchanged
+  ///
+  /// # Returns
+  /// width * height * 4 bytes of RGBA, where width and height are the frame's dimensions
+  #[napi]
+  pub fn capture(
+    &self,
+    window: &crate::tao::structs::Window,
+    premultiplied: Option<bool>,
+  ) -> napi::Result<Buffer, RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_id = crate::tao::structs::window_id_to_u64(
+      window_arc.lock().map_err(|_| window_lock_error())?.id(),
+    );
+    let cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let state = cache.get(&window_id).ok_or_else(|| {
+      RenderError::NothingRendered.error(format!(
+        "Nothing has been rendered to window {} yet, render before capturing",
+        window_id
+      ))
+    })?;
 
-      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
-      }
+    let extent = state.pixels.context().texture_extent;
+    let frame = state.pixels.frame();
+    let mut pixels = unpad_frame(
+      frame,
+      FrameSize::for_frame(frame.len(), extent.width, extent.height),
+    );
+    if premultiplied.unwrap_or(false) && self.alpha_mode != AlphaMode::Premultiplied {
+      alpha::premultiply_in_place(&mut pixels);
     }
+    Ok(pixels.into())
   }
-}
 
-/// Parameters for scaling buffer to fit window
-struct ScaleBufferFitParams {
-  buffer_width: u32,
-  buffer_height: u32,
-  window_width: u32,
-  window_height: u32,
-  offset_x: u32,
-  offset_y: u32,
-  scaled_width: u32,
-  scaled_height: u32,
-}
+  /// Selects the scaling implementation by name
+  ///
+  /// Overrides the filter normally picked for scaled copies (including adaptive
+  /// quality). Pass `null` to go back to the default. See `availableScalers()`
+  /// for the registered names.
+  #[napi]
+  pub fn set_scaler(&mut self, name: Option<String>) -> napi::Result<()> {
+    self.scaler = match name {
+      Some(name) => Some(find_scaler(&name).ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Unknown scaler: {}", name),
+        )
+      })?),
+      None => None,
+    };
+    Ok(())
+  }
 
-/// Scales buffer to fit window, maintaining aspect ratio with letterboxing
-fn scale_buffer_fit(frame: &mut [u8], buffer: &[u8], params: ScaleBufferFitParams) {
-  let ScaleBufferFitParams {
-    buffer_width,
-    buffer_height,
-    window_width,
-    window_height,
-    offset_x,
-    offset_y,
-    scaled_width,
-    scaled_height,
-  } = params;
+  /// Restricts rendering to a sub-rectangle of the window
+  ///
+  /// Scaling and letterboxing happen within the viewport instead of the whole
+  /// window, and pixels outside it are left untouched. This lets several renderers
+  /// share one window (split-screen, picture-in-picture). The viewport is clipped
+  /// to the window size on every frame. Pass `null` to render to the whole window.
+  #[napi]
+  pub fn set_viewport(&mut self, viewport: Option<Rect>) {
+    self.viewport = viewport;
+  }
 
-  // Frame is already cleared with background color
+  /// Sets the background margins inside the window (or viewport), in window pixels
+  ///
+  /// See `RenderOptions.padding`. Pass zeros to remove the margins.
+  #[napi]
+  pub fn set_padding(&mut self, top: u32, right: u32, bottom: u32, left: u32) {
+    self.padding = [top, right, bottom, left];
+  }
 
-  for y in 0..scaled_height {
-    for x in 0..scaled_width {
-      let src_x = (x as f32 * buffer_width as f32 / scaled_width as f32)
-        .min(buffer_width as f32 - 1.0) as u32;
-      let src_y = (y as f32 * buffer_height as f32 / scaled_height as f32)
-        .min(buffer_height as f32 - 1.0) as u32;
+  /// Tells the window where the caret of a self-rendered text field is
+  ///
+  /// `rect` is given in buffer coordinates (following the configured origin) and
+  /// is mapped through the current scale mode and viewport into window
+  /// coordinates before being forwarded to [`Window::set_ime_cursor_area`], so
+  /// IME candidate windows and magnifiers track the caret as drawn on screen.
+  /// The rectangle's width is ignored, since the window only takes the caret's
+  /// position and height.
+  ///
+  /// [`Window::set_ime_cursor_area`]: crate::tao::structs::Window::set_ime_cursor_area
+  #[napi]
+  pub fn set_text_input_rect(
+    &self,
+    window: &crate::tao::structs::Window,
+    rect: Rect,
+  ) -> napi::Result<()> {
+    let size = window.inner_size()?;
+    let (x, y, _, height) =
+      self.buffer_rect_to_window(&rect, size.width as u32, size.height as u32);
+    window.set_ime_cursor_area(x, y, height)
+  }
 
-      let dst_x = offset_x + x;
-      let dst_y = offset_y + y;
+  /// Returns where the buffer is drawn in the window at its current size
+  ///
+  /// Uses the same math and rounding as rendering, so overlays can be placed
+  /// exactly on the letterboxed image. With `ScaleMode.Fill` the image may be
+  /// larger than the window and is cropped around its center.
+  #[napi]
+  pub fn layout(&self, window: &crate::tao::structs::Window) -> napi::Result<RenderLayout> {
+    let size = window.inner_size()?;
+    Ok(self.layout_in(size.width as u32, size.height as u32))
+  }
 
-      if dst_x < window_width && dst_y < window_height {
-        let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
-        let dst_idx = ((dst_y * window_width + dst_x) * 4) as usize;
+  /// Returns the window's inner size and render cache ID
+  ///
+  /// Both are read under a single window lock, so the size matches the window
+  /// the ID refers to even while it is being resized.
+  #[napi]
+  pub fn window_metrics(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<WindowMetrics, RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+    let size = window_guard.inner_size();
+    Ok(WindowMetrics {
+      width: size.width,
+      height: size.height,
+      cache_id: crate::tao::structs::window_id_to_u64(window_guard.id()),
+    })
+  }
 
-        if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-          frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
-        }
+  /// Returns whether frames can be presented to the window
+  ///
+  /// Creates the window's render surface like [`PixelRenderer::prepare`], but
+  /// returns false instead of throwing when that fails (e.g. on a headless CI
+  /// runner without a display or GPU adapter), in which case no render state is
+  /// cached for the window.
+  #[napi]
+  pub fn can_render(&self, window: &crate::tao::structs::Window) -> bool {
+    match self.prepare(window) {
+      Ok(()) => true,
+      Err(e) => {
+        debug_log!("  can_render: {:?}", e);
+        false
       }
     }
   }
+
+  /// Creates and caches the window's render surface without presenting
+  ///
+  /// The surface is sized to the window's current size, so the first `render`
+  /// skips surface creation. Does nothing if the window already has one. Call it
+  /// ahead of time when the first frame must appear within a tight deadline.
+  #[napi]
+  pub fn prepare(&self, window: &crate::tao::structs::Window) -> napi::Result<(), RenderError> {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+    let mut cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let window_id = crate::tao::structs::window_id_to_u64(window_guard.id());
+    if let std::collections::hash_map::Entry::Vacant(entry) = cache.entry(window_id) {
+      // A minimized window gets a minimal surface, resized by the first render
+      let size = window_guard.inner_size();
+      entry.insert(create_render_state(
+        &window_guard,
+        size.width.max(1),
+        size.height.max(1),
+      )?);
+    }
+    Ok(())
+  }
+
+  /// Renders a pixel buffer to the given window
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  /// * `frame_id` - Identifies the frame's content for `skipDuplicateFrames`,
+  ///   instead of hashing the buffer
+  ///
+  /// # Returns
+  /// Whether the frame was presented (false when skipped as a duplicate, within
+  /// the window's minimum present interval, or because the window is hidden or
+  /// minimized, see `Window.isOccluded`)
+  ///
+  /// # Performance Note
+  /// This method uses a global cache to avoid resource exhaustion errors
+  /// that occur when creating new contexts/surfaces on each render call.
+  /// Resources are cached per-window and reused across all PixelRenderer instances.
+  #[napi]
+  pub fn render(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    frame_id: Option<i64>,
+  ) -> napi::Result<bool, RenderError> {
+    self.render_bytes(window, &buffer, frame_id)
+  }
+
+  /// Renders pixels read in place from a `Uint8ClampedArray` or `Uint8Array`
+  ///
+  /// Same as `render`, but the view's bytes are read directly without wrapping
+  /// them in a Buffer, so views over a `SharedArrayBuffer` (e.g. `ImageData.data`
+  /// filled by a worker) cost no allocation per frame. Only the view's own
+  /// range of its underlying buffer is read, and its byte length is validated
+  /// like a Buffer's. Don't write to the memory while this call runs.
+  /// Returns whether the frame was presented, like `render`.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `pixels` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  #[napi]
+  pub fn render_from_typed_array(
+    &self,
+    window: &crate::tao::structs::Window,
+    pixels: Either<Uint8ClampedSlice<'_>, Uint8ArraySlice<'_>>,
+  ) -> napi::Result<bool, RenderError> {
+    let bytes: &[u8] = match &pixels {
+      Either::A(clamped) => clamped,
+      Either::B(array) => array,
+    };
+    self.render_bytes(window, bytes, None)
+  }
+
+  /// Validates and renders a borrowed buffer, the body of `render`
+  fn render_bytes(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: &[u8],
+    frame_id: Option<i64>,
+  ) -> napi::Result<bool, RenderError> {
+    self.validate_buffer(buffer)?;
+    if window.is_occluded().unwrap_or(false) {
+      debug_log!("render: window is occluded, skipping frame");
+      return Ok(false);
+    }
+    let duplicate_key = if self.skip_duplicates {
+      let key = FrameKey::new(window, buffer, frame_id)?;
+      let window_id = window
+        .id()
+        .map_err(|e| RenderError::WindowNotInitialized.wrap(e))?;
+      let cache = RENDER_STATE
+        .lock()
+        .map_err(|_| render_state_poisoned_error())?;
+      if cache.get(&window_id).and_then(|state| state.last_frame) == Some(key) {
+        debug_log!("render: skipping duplicate frame {:?}", key);
+        return Ok(false);
+      }
+      Some((window_id, key))
+    } else {
+      None
+    };
+    self.update_motion(buffer);
+
+    #[cfg(feature = "gpu")]
+    let presented_on_gpu = if self.gpu_eligible() {
+      self.present_gpu(window, buffer)?
+    } else {
+      None
+    };
+    #[cfg(not(feature = "gpu"))]
+    let presented_on_gpu = None;
+    let presented = match presented_on_gpu {
+      Some(presented) => presented,
+      None => self.present(window, |frame, size, timer, scratch| {
+        self.draw_frame(frame, buffer, size, timer, scratch);
+      })?,
+    };
+
+    if let Some((window_id, key)) = duplicate_key {
+      let mut cache = RENDER_STATE
+        .lock()
+        .map_err(|_| render_state_poisoned_error())?;
+      if let Some(state) = cache.get_mut(&window_id) {
+        remember_frame(&mut state.last_frame, key, presented);
+      }
+    }
+    Ok(presented)
+  }
+
+  /// Returns where this renderer scales buffers
+  ///
+  /// `Gpu` only when it was requested and the `gpu` build feature is enabled.
+  #[napi]
+  pub fn backend(&self) -> Backend {
+    if cfg!(feature = "gpu") {
+      self.backend
+    } else {
+      Backend::Cpu
+    }
+  }
+
+  /// Renders a buffer with different dimensions than the renderer's, for this call only
+  ///
+  /// The buffer is validated against the given dimensions, and the scale mode,
+  /// colors, filters and other settings are kept. Rows are taken as packed, since
+  /// a source stride belongs to the configured width. The renderer's own
+  /// dimensions are unchanged afterwards, so one renderer can follow a stream
+  /// that changes resolution mid-session.
+  #[napi]
+  pub fn render_with_dimensions(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    buffer_width: u32,
+    buffer_height: u32,
+  ) -> napi::Result<bool, RenderError> {
+    let renderer = self
+      .with_dimensions(buffer_width, buffer_height)
+      .map_err(|e| RenderError::InvalidArgument.wrap(e))?;
+    let result = renderer.render(window, buffer, None);
+    // Hand motion tracking back so adaptive quality carries across sizes
+    if let (Ok(mut motion), Ok(used)) = (self.motion.lock(), renderer.motion.into_inner()) {
+      *motion = used;
+    }
+    result
+  }
+
+  /// Renders a pixel buffer, pacing presents to the window's minimum present interval
+  ///
+  /// Set the interval with `Window.setTargetFps` or
+  /// `Window.setMinPresentIntervalMicros`. If the window presented too recently,
+  /// the frame is skipped, or with `wait` the call blocks until the interval has
+  /// elapsed and then presents. This lets a tight loop drive the renderer
+  /// without presenting more often than needed. Otherwise it renders like
+  /// `render`, so frames to an occluded window or duplicates under
+  /// `skipDuplicateFrames` are skipped too.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  /// * `wait` - Block until the frame can be presented instead of skipping it (default: false)
+  ///
+  /// # Returns
+  /// Whether the frame was presented
+  #[napi]
+  pub fn render_paced(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    wait: Option<bool>,
+  ) -> napi::Result<bool, RenderError> {
+    self.validate_buffer(&buffer)?;
+    let min_present_interval = window.min_present_interval();
+    let window_id = window
+      .id()
+      .map_err(|e| RenderError::WindowNotInitialized.wrap(e))?;
+    let delay = {
+      let cache = RENDER_STATE
+        .lock()
+        .map_err(|_| render_state_poisoned_error())?;
+      cache.get(&window_id).and_then(|state| {
+        remaining_interval(state.last_present, min_present_interval, Instant::now())
+      })
+    };
+    if let Some(delay) = delay {
+      if !wait.unwrap_or(false) {
+        return Ok(false);
+      }
+      std::thread::sleep(delay);
+    }
+    self.render_bytes(window, &buffer, None)
+  }
+
+  /// Renders a pixel buffer without blocking the JavaScript thread on scaling
+  ///
+  /// Threading model: clearing and scaling the buffer into a window-sized frame
+  /// runs on a libuv worker thread. Presenting then happens back on the
+  /// JavaScript thread when the promise settles, because window surfaces can't
+  /// be used from other threads; the present itself is not parallelized. If the
+  /// window was resized in between, the frame is composed again at the new size
+  /// before presenting.
+  ///
+  /// The task uses a snapshot of the renderer's settings at call time. Don't
+  /// modify `buffer` until the promise settles.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  #[napi(ts_return_type = "Promise<void>")]
+  pub fn render_async(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+  ) -> napi::Result<AsyncTask<task::RenderTask>, RenderError> {
+    self.validate_buffer(&buffer)?;
+    let window_arc = window
+      .inner
+      .clone()
+      .ok_or_else(window_not_initialized_error)?;
+    self.update_motion(&buffer);
+
+    let renderer = self.snapshot();
+    if let Ok(mut motion) = renderer.motion.lock() {
+      motion.filter = self.current_filter();
+    }
+    Ok(AsyncTask::new(task::RenderTask::new(
+      renderer,
+      buffer,
+      window_arc,
+      window.min_present_interval(),
+    )?))
+  }
+
+  /// Renders the same pixel buffer to several windows
+  ///
+  /// The buffer is validated once and the render cache is locked once for the
+  /// whole batch. A failure on one window doesn't stop the others; check the
+  /// returned results, which are in the same order as `windows`.
+  ///
+  /// # Arguments
+  /// * `windows` - The Tao windows to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  #[napi]
+  pub fn render_many(
+    &self,
+    windows: Vec<ClassInstance<'_, crate::tao::structs::Window>>,
+    buffer: Buffer,
+  ) -> napi::Result<Vec<WindowRenderResult>, RenderError> {
+    self.validate_buffer(&buffer)?;
+    self.update_motion(&buffer);
+
+    let mut cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    let results = windows
+      .iter()
+      .map(|window| {
+        let mut window_id = 0;
+        let result = window
+          .inner
+          .as_ref()
+          .ok_or_else(window_not_initialized_error)
+          .and_then(|window_arc| {
+            let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+            window_id = crate::tao::structs::window_id_to_u64(window_guard.id());
+            let size = window_guard.inner_size();
+            self.render_locked(
+              &mut cache,
+              PresentTarget {
+                window_id,
+                window: Some(&window_guard),
+                width: size.width,
+                height: size.height,
+                min_present_interval: window.min_present_interval(),
+              },
+              |frame, size, timer, scratch| self.draw_frame(frame, &buffer, size, timer, scratch),
+            )
+          });
+        let error = result.err();
+        WindowRenderResult {
+          window_id,
+          success: error.is_none(),
+          error_code: error.as_ref().map(|e| e.status),
+          error: error.map(|e| e.reason),
+        }
+      })
+      .collect();
+    Ok(results)
+  }
+
+  /// Renders a pixel buffer into a new RGBA buffer instead of a window
+  ///
+  /// Runs the same pipeline as `render` (background, viewport, scale mode,
+  /// filtering, dithering and alpha mode) against an `out_width` x `out_height`
+  /// frame, which is useful for headless tests and for feeding video encoders.
+  ///
+  /// # Arguments
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  /// * `out_width` - Width of the output frame in pixels
+  /// * `out_height` - Height of the output frame in pixels
+  ///
+  /// # Returns
+  /// out_width * out_height * 4 bytes of RGBA
+  #[napi]
+  pub fn render_to_vec(
+    &self,
+    buffer: Buffer,
+    out_width: u32,
+    out_height: u32,
+  ) -> napi::Result<Buffer, RenderError> {
+    self.validate_buffer(&buffer)?;
+    let len = (out_width as usize)
+      .checked_mul(out_height as usize)
+      .and_then(|pixels| pixels.checked_mul(4))
+      .ok_or_else(|| {
+        RenderError::InvalidArgument.error(format!(
+          "Output size {}x{} is too large",
+          out_width, out_height
+        ))
+      })?;
+
+    let mut frame = vec![0u8; len];
+    let mut timer = StageTimer::new(false);
+    self.draw_frame(
+      &mut frame,
+      &buffer,
+      FrameSize::packed(out_width, out_height),
+      &mut timer,
+      &mut FrameScratch::for_width(out_width),
+    );
+    apply_alpha_mode(&mut frame, self.alpha_mode);
+    Ok(frame.into())
+  }
+
+  /// Redraws only the part of the window showing a changed rectangle of the buffer
+  ///
+  /// The dirty rectangle is mapped through the current scale mode and viewport,
+  /// and only the window pixels it covers (plus a pixel of margin for filtering)
+  /// are recopied; the background isn't cleared. The whole surface is still
+  /// presented since the backend has no damage support, but the copy work is
+  /// limited to the dirty area.
+  ///
+  /// The rest of the window keeps what the previous present drew, so the first
+  /// call after creating the renderer or resizing the window must be a full
+  /// `render`. With `AlphaMode.Premultiplied` the whole frame is redrawn. With
+  /// `CompositeMode.SourceOver` the dirty pixels are blended over what the window
+  /// shows, as `render` does.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - The full buffer, buffer_width * buffer_height pixels in the configured pixel format
+  /// * `x` - Left edge of the changed rectangle in buffer pixels
+  /// * `y` - Top edge of the changed rectangle in buffer pixels
+  /// * `width` - Width of the changed rectangle
+  /// * `height` - Height of the changed rectangle
+  #[napi]
+  pub fn render_region(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+  ) -> napi::Result<(), RenderError> {
+    self.validate_buffer(&buffer)?;
+    let dirty = Rect {
+      x: x.min(i32::MAX as u32) as i32,
+      y: y.min(i32::MAX as u32) as i32,
+      width,
+      height,
+    };
+    let Some(dirty) = dirty.clip_to(self.buffer_width, self.buffer_height) else {
+      debug_log!("  render_region: empty dirty rectangle, nothing to draw");
+      return Ok(());
+    };
+    self.update_motion(&buffer);
+
+    self.present(window, |frame, size, timer, scratch| {
+      if self.alpha_mode == AlphaMode::Premultiplied {
+        // Premultiplying again would darken the untouched pixels
+        self.draw_frame(frame, &buffer, size, timer, scratch);
+      } else {
+        self.draw_dirty(frame, &buffer, size, &dirty, scratch);
+        timer.end_copy();
+      }
+    })?;
+    Ok(())
+  }
+
+  /// Renders a pixel buffer and presents it at a target time
+  ///
+  /// The frame is composed immediately, then presentation waits (blocking) until
+  /// `target_time_micros` on the clock returned by `monotonicTimeMicros()`.
+  /// Nothing is locked while waiting, so other windows and render threads keep
+  /// presenting. Frames whose target passed by more than `max_lateness_micros`
+  /// (default: one 60 Hz frame) are dropped without presenting, so a player
+  /// that falls behind its audio clock catches up instead of drifting. Targets
+  /// more than 100ms ahead are rejected rather than waited for.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  /// * `target_time_micros` - When the frame should appear on screen
+  /// * `max_lateness_micros` - How late a frame may still be presented
+  #[napi]
+  pub fn present_at(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    target_time_micros: i64,
+    max_lateness_micros: Option<u32>,
+  ) -> napi::Result<PresentOutcome, RenderError> {
+    self.validate_buffer(&buffer)?;
+    let max_lateness = max_lateness_micros.unwrap_or(schedule::DEFAULT_MAX_LATENESS_MICROS);
+    match schedule::schedule(target_time_micros, schedule::now_micros(), max_lateness) {
+      schedule::Schedule::Drop => {
+        debug_log!("  present_at: dropping stale frame");
+        return Ok(PresentOutcome::Dropped);
+      }
+      schedule::Schedule::Wait(delay)
+        if delay > Duration::from_micros(schedule::MAX_LEAD_MICROS as u64) =>
+      {
+        return Err(RenderError::InvalidArgument.error(format!(
+          "Target time is {}us ahead, more than the {}us presentAt waits for",
+          delay.as_micros(),
+          schedule::MAX_LEAD_MICROS
+        )));
+      }
+      _ => {}
+    }
+    self.update_motion(&buffer);
+
+    // Compose at the window's current size without holding any lock
+    let size = {
+      let window_guard = window
+        .inner
+        .as_ref()
+        .ok_or_else(window_not_initialized_error)?
+        .lock()
+        .map_err(|_| window_lock_error())?;
+      let window_size = window_guard.inner_size();
+      FrameSize::packed(window_size.width, window_size.height)
+    };
+    let mut composed = vec![0u8; size.width as usize * size.height as usize * 4];
+    self.draw_frame(
+      &mut composed,
+      &buffer,
+      size,
+      &mut StageTimer::new(false),
+      &mut FrameScratch::for_width(size.width),
+    );
+
+    // Re-check after composing, which may itself have taken a while
+    let mut outcome = PresentOutcome::Presented;
+    match schedule::schedule(target_time_micros, schedule::now_micros(), max_lateness) {
+      schedule::Schedule::Wait(delay) => std::thread::sleep(delay),
+      schedule::Schedule::Late => outcome = PresentOutcome::Late,
+      schedule::Schedule::Drop => {
+        debug_log!("  present_at: dropping frame that went stale while composing");
+        return Ok(PresentOutcome::Dropped);
+      }
+    }
+
+    let presented = self.present(window, |frame, frame_size, timer, scratch| {
+      if (frame_size.width, frame_size.height) == (size.width, size.height) {
+        task::copy_rows(frame, frame_size, &composed);
+        timer.end_copy();
+      } else {
+        // The window was resized while waiting, so compose again at the new size
+        self.draw_frame(frame, &buffer, frame_size, timer, scratch);
+      }
+    })?;
+    if !presented {
+      outcome = PresentOutcome::Dropped;
+    }
+    Ok(outcome)
+  }
+
+  /// Clears the window and executes a list of draw commands, presenting once
+  ///
+  /// Commands run in order in window coordinates, clipped to the window (the
+  /// viewport and scale mode don't apply). All commands are validated before
+  /// anything is drawn, so an invalid command leaves the window untouched.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `commands` - Primitives to draw, bottom to top
+  #[napi]
+  pub fn render_commands(
+    &self,
+    window: &crate::tao::structs::Window,
+    commands: Vec<DrawCommand>,
+  ) -> napi::Result<(), RenderError> {
+    let ops = display_list::compile(&commands).map_err(|e| RenderError::InvalidArgument.wrap(e))?;
+
+    self.present(window, |frame, size, timer, _| {
+      clear_frame(frame, &self.bg_color);
+      timer.end_clear();
+      display_list::execute(frame, size, &ops);
+      timer.end_copy();
+    })?;
+    Ok(())
+  }
+
+  /// Creates a handle that presents frames for the window on a background thread
+  ///
+  /// The window's surface is created (and cleared to the background color) here,
+  /// on the window's thread. Later calls to `submit` on the handle only copy the
+  /// frame and hand it to the worker. The handle uses a snapshot of this
+  /// renderer's current settings.
+  #[napi]
+  pub fn create_render_handle(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<RenderHandle, RenderError> {
+    self.present(window, |frame, _, _, _| clear_frame(frame, &self.bg_color))?;
+
+    let window_arc = window
+      .inner
+      .clone()
+      .ok_or_else(window_not_initialized_error)?;
+    let window_id = crate::tao::structs::window_id_to_u64(
+      window_arc.lock().map_err(|_| window_lock_error())?.id(),
+    );
+    RenderHandle::spawn(
+      window_arc,
+      window_id,
+      self.snapshot(),
+      window.min_present_interval(),
+    )
+  }
+
+  /// Queues a pixel buffer for the shared render thread and returns immediately
+  ///
+  /// One process-wide thread scales and presents the frames of every window in
+  /// the order they were queued, using a snapshot of this renderer's settings
+  /// and a copy of `buffer`, at the window's size when it was queued. The
+  /// window's surface is created here first if it has none, on the window's
+  /// thread. Frames are never dropped; call
+  /// `flushRenderThread` to wait for them, and to get errors from failed
+  /// presents, which are otherwise reported by the next call to this method.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  #[napi]
+  pub fn render_threaded(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+  ) -> napi::Result<(), RenderError> {
+    self.validate_buffer(&buffer)?;
+    self.prepare(window)?;
+    let (window_id, window_size) = {
+      let window_guard = window
+        .inner
+        .as_ref()
+        .ok_or_else(window_not_initialized_error)?
+        .lock()
+        .map_err(|_| window_lock_error())?;
+      (
+        crate::tao::structs::window_id_to_u64(window_guard.id()),
+        window_guard.inner_size(),
+      )
+    };
+    self.update_motion(&buffer);
+
+    let renderer = self.snapshot();
+    if let Ok(mut motion) = renderer.motion.lock() {
+      motion.filter = self.current_filter();
+    }
+    thread::enqueue(thread::ThreadedFrame {
+      renderer,
+      buffer: buffer.to_vec(),
+      window_id,
+      window_width: window_size.width,
+      window_height: window_size.height,
+      min_present_interval: window.min_present_interval(),
+    })
+  }
+
+  /// Renders the buffer twice, side by side, using a different scale mode for each half
+  ///
+  /// This is a developer tool for comparing scale modes (e.g. Fit vs Fill) on the
+  /// same content. The left and right halves are scaled independently and
+  /// separated by a thin divider line. Like `render`, it draws within the
+  /// viewport, inside the padding, over the background color or letterbox
+  /// checkerboard. Fails with `InvalidConfiguration` in `CompositeMode.SourceOver`,
+  /// since the halves always replace the frame.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - buffer_width * buffer_height pixels in the configured pixel format (RGBA by default)
+  /// * `left_mode` - Scaling mode for the left half
+  /// * `right_mode` - Scaling mode for the right half
+  #[napi]
+  pub fn render_split(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    left_mode: ScaleMode,
+    right_mode: ScaleMode,
+  ) -> napi::Result<(), RenderError> {
+    self.validate_buffer(&buffer)?;
+    if self.composite_mode == CompositeMode::SourceOver {
+      return Err(
+        RenderError::InvalidConfiguration
+          .error("renderSplit replaces the frame and doesn't support CompositeMode.SourceOver"),
+      );
+    }
+
+    self.present(window, |frame, size, timer, scratch| {
+      self.draw_split(
+        frame,
+        &buffer,
+        size,
+        [left_mode, right_mode],
+        timer,
+        scratch,
+      );
+    })?;
+    Ok(())
+  }
+
+  /// Draws a side-by-side comparison frame for [`Self::render_split`]
+  fn draw_split(
+    &self,
+    frame: &mut [u8],
+    buffer: &[u8],
+    size: FrameSize,
+    [left_mode, right_mode]: [ScaleMode; 2],
+    timer: &mut StageTimer,
+    scratch: &mut FrameScratch,
+  ) {
+    let region = match &self.viewport {
+      Some(viewport) => FrameRegion::clipped(viewport, size),
+      None => Some(FrameRegion::full(size)),
+    };
+    // A viewport entirely outside the window draws nothing
+    let Some(region) = region else {
+      return;
+    };
+
+    match &self.checker {
+      Some(checker) => checker.fill(frame, region),
+      None => fill_region(frame, region, &self.bg_color),
+    }
+    timer.end_clear();
+
+    if let Some(region) = region.inset(self.padding) {
+      let (left, divider, right) = region.split(SPLIT_DIVIDER_WIDTH);
+      self.draw_scaled(frame, buffer, left, left_mode, scratch);
+      self.draw_scaled(frame, buffer, right, right_mode, scratch);
+      fill_region(frame, divider, &SPLIT_DIVIDER_COLOR);
+    }
+    timer.end_copy();
+  }
+
+  /// Maps a rectangle in buffer coordinates to window coordinates
+  ///
+  /// Returns (x, y, width, height) in physical window pixels.
+  fn buffer_rect_to_window(
+    &self,
+    rect: &Rect,
+    window_width: u32,
+    window_height: u32,
+  ) -> (f64, f64, f64, f64) {
+    let region = self.target_region(window_width, window_height);
+    let (logical_width, logical_height) = self.logical_buffer_size();
+    let mode = self.resolve_scale_mode(self.scale_mode, region.width, region.height);
+    let mut transform = calculate_buffer_transform(
+      logical_width,
+      logical_height,
+      region.width,
+      region.height,
+      mode,
+    );
+    if self.anchor != Anchor::Center && is_anchored_mode(mode) {
+      let (_, _, scaled_width, scaled_height) = calculate_scaled_dimensions(
+        logical_width,
+        logical_height,
+        region.width,
+        region.height,
+        mode,
+      );
+      let (x, y) = anchor_position(
+        region.width,
+        region.height,
+        scaled_width,
+        scaled_height,
+        self.anchor,
+      );
+      transform.offset_x = x as f64;
+      transform.offset_y = y as f64;
+    }
+    if (mode == ScaleMode::Stretch && self.max_aspect_skew.is_some()) || self.balances_fit(mode) {
+      let (x, y, scaled_width, scaled_height) =
+        self.scaled_dimensions(logical_width, logical_height, region, mode);
+      transform.scale_x = scaled_width as f64 / logical_width as f64;
+      transform.scale_y = scaled_height as f64 / logical_height as f64;
+      transform.offset_x = x as f64;
+      transform.offset_y = y as f64;
+    }
+    // The transform maps logical pixels; rescale it to map rotated buffer pixels
+    let (oriented_width, oriented_height) = self.oriented_buffer_size();
+    transform.scale_x *= logical_width as f64 / oriented_width.max(1) as f64;
+    transform.scale_y *= logical_height as f64 / oriented_height.max(1) as f64;
+
+    // Coordinates relative to the displayed part of the buffer
+    let (source_x, source_y, source_width, source_height) = self.source_bounds();
+    let (x, y) = (
+      rect.x as f64 - source_x as f64,
+      rect.y as f64 - source_y as f64,
+    );
+    let left = if self.flip_horizontal {
+      source_width as f64 - x - rect.width as f64
+    } else {
+      x
+    };
+    let top = if self.rows_flipped() {
+      source_height as f64 - y - rect.height as f64
+    } else {
+      y
+    };
+    let (left, top, width, height) = self.rotation.rotate_rect(
+      (left, top, rect.width as f64, rect.height as f64),
+      source_width as f64,
+      source_height as f64,
+    );
+    let (left, top) = transform.map_point(left, top);
+    (
+      region.x as f64 + left,
+      region.y as f64 + top,
+      width * transform.scale_x,
+      height * transform.scale_y,
+    )
+  }
+
+  /// Computes where the buffer is drawn in a window of the given size
+  fn layout_in(&self, window_width: u32, window_height: u32) -> RenderLayout {
+    let region = self.target_region(window_width, window_height);
+    let (logical_width, logical_height) = self.logical_buffer_size();
+    let mode = self.resolve_scale_mode(self.scale_mode, region.width, region.height);
+    let (offset_x, offset_y, scaled_width, scaled_height) =
+      self.scaled_dimensions(logical_width, logical_height, region, mode);
+    RenderLayout {
+      offset_x: region.x + offset_x,
+      offset_y: region.y + offset_y,
+      scaled_width,
+      scaled_height,
+      letterbox_x: region.width.saturating_sub(scaled_width),
+      letterbox_y: region.height.saturating_sub(scaled_height),
+    }
+  }
+
+  /// Scaled size of the buffer in the region, with offsets following the anchor
+  ///
+  /// Offsets are clamped to the region, so cropped images report 0.
+  fn scaled_dimensions(
+    &self,
+    logical_width: u32,
+    logical_height: u32,
+    region: FrameRegion,
+    mode: ScaleMode,
+  ) -> (u32, u32, u32, u32) {
+    if let (ScaleMode::Stretch, Some(max_skew)) = (mode, self.max_aspect_skew) {
+      return calculate_capped_stretch(
+        logical_width,
+        logical_height,
+        region.width,
+        region.height,
+        max_skew,
+      );
+    }
+    if self.balances_fit(mode) {
+      return calculate_balanced_fit(logical_width, logical_height, region.width, region.height);
+    }
+    let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions(
+      logical_width,
+      logical_height,
+      region.width,
+      region.height,
+      mode,
+    );
+    if !is_anchored_mode(mode) {
+      return (offset_x, offset_y, scaled_width, scaled_height);
+    }
+    let (x, y) = anchor_position(
+      region.width,
+      region.height,
+      scaled_width,
+      scaled_height,
+      self.anchor,
+    );
+    (
+      x.max(0) as u32,
+      y.max(0) as u32,
+      scaled_width,
+      scaled_height,
+    )
+  }
+
+  /// Whether Fit is placed by [`calculate_balanced_fit`] instead of the anchor
+  fn balances_fit(&self, mode: ScaleMode) -> bool {
+    mode == ScaleMode::Fit && self.balanced_letterbox && self.anchor == Anchor::Center
+  }
+
+  /// Whether buffer rows are displayed bottom to top, from the origin and vertical flip
+  fn rows_flipped(&self) -> bool {
+    (self.origin == Origin::BottomLeft) != self.flip_vertical
+  }
+
+  /// Region of the window that buffers are scaled into (viewport or whole
+  /// window, less the padding)
+  fn target_region(&self, window_width: u32, window_height: u32) -> FrameRegion {
+    let size = FrameSize::packed(window_width, window_height);
+    let region = self
+      .viewport
+      .as_ref()
+      .and_then(|viewport| FrameRegion::clipped(viewport, size))
+      .unwrap_or_else(|| FrameRegion::full(size));
+    // Padding that leaves nothing draws no image; map through the whole region
+    region.inset(self.padding).unwrap_or(region)
+  }
+
+  /// Replaces hybrid scale modes with the concrete mode for a region size
+  fn resolve_scale_mode(&self, mode: ScaleMode, width: u32, height: u32) -> ScaleMode {
+    let (logical_width, logical_height) = self.logical_buffer_size();
+    match mode {
+      ScaleMode::Auto => resolve_auto_mode(
+        logical_width,
+        logical_height,
+        width,
+        height,
+        self.auto_tolerance,
+      ),
+      ScaleMode::FitDownStretchUp => {
+        resolve_fit_down_stretch_up(logical_width, logical_height, width, height)
+      }
+      mode => mode,
+    }
+  }
+
+  /// Buffer size in window pixels before the scale mode applies
+  fn logical_buffer_size(&self) -> (u32, u32) {
+    let (width, height) = self.oriented_buffer_size();
+    let scale = |size: u32| ((size as f64 * self.scale_factor).round() as u32).max(1);
+    if self.scale_factor == 1.0 {
+      (width, height)
+    } else {
+      (scale(width), scale(height))
+    }
+  }
+
+  /// Bytes from the start of one source row to the next
+  fn source_row_bytes(&self) -> u32 {
+    self
+      .source_stride
+      .unwrap_or(self.buffer_width * self.pixel_format.bytes_per_pixel())
+  }
+
+  /// Size of the displayed part of the buffer after rotation
+  fn oriented_buffer_size(&self) -> (u32, u32) {
+    let (_, _, width, height) = self.source_bounds();
+    self.rotation.rotated_size(width, height)
+  }
+
+  /// Displayed part of the buffer as (x, y, width, height) in buffer pixels
+  fn source_bounds(&self) -> (u32, u32, u32, u32) {
+    match &self.source_rect {
+      Some(rect) => (rect.x as u32, rect.y as u32, rect.width, rect.height),
+      None => (0, 0, self.buffer_width, self.buffer_height),
+    }
+  }
+
+  /// Copies the renderer's settings, starting with fresh motion tracking
+  fn snapshot(&self) -> Self {
+    Self {
+      buffer_width: self.buffer_width,
+      buffer_height: self.buffer_height,
+      scale_mode: self.scale_mode,
+      bg_color: self.bg_color,
+      checker: self.checker,
+      dither: self.dither,
+      viewport: self.viewport,
+      padding: self.padding,
+      origin: self.origin,
+      adaptive_quality: self.adaptive_quality,
+      motion: Mutex::new(MotionState::default()),
+      detailed_timing: self.detailed_timing,
+      scaler: self.scaler.clone(),
+      auto_tolerance: self.auto_tolerance,
+      wrap_mode: self.wrap_mode,
+      pixel_format: self.pixel_format,
+      filter_mode: self.filter_mode,
+      alpha_mode: self.alpha_mode,
+      composite_mode: self.composite_mode,
+      scale_factor: self.scale_factor,
+      tile_offset: self.tile_offset,
+      flip_horizontal: self.flip_horizontal,
+      flip_vertical: self.flip_vertical,
+      rotation: self.rotation,
+      tone: self.tone,
+      tint: self.tint,
+      source_stride: self.source_stride,
+      source_rect: self.source_rect,
+      anchor: self.anchor,
+      max_aspect_skew: self.max_aspect_skew,
+      balanced_letterbox: self.balanced_letterbox,
+      resize_debounce: self.resize_debounce,
+      skip_duplicates: self.skip_duplicates,
+      manual_resize: self.manual_resize,
+      backend: self.backend,
+      palette: self.palette.clone(),
+      on_present: self.on_present.clone(),
+    }
+  }
+
+  /// Copies the renderer's settings for a buffer of another size
+  ///
+  /// Motion tracking moves to the copy; callers hand it back when done.
+  fn with_dimensions(&self, buffer_width: u32, buffer_height: u32) -> napi::Result<Self> {
+    validate_buffer_dimensions(buffer_width, buffer_height)?;
+    let mut renderer = self.snapshot();
+    renderer.buffer_width = buffer_width;
+    renderer.buffer_height = buffer_height;
+    renderer.source_stride = None;
+    renderer.source_rect = None;
+    if let Ok(mut motion) = self.motion.lock() {
+      renderer.motion = Mutex::new(std::mem::take(&mut *motion));
+    }
+    Ok(renderer)
+  }
+
+  /// Compares the buffer with the previous one and picks the filter for it
+  fn update_motion(&self, buffer: &[u8]) {
+    if !self.adaptive_quality {
+      return;
+    }
+    let Ok(mut motion) = self.motion.lock() else {
+      return;
+    };
+    let signature = FrameSignature::new(
+      buffer,
+      self.buffer_width,
+      self.buffer_height,
+      self.source_row_bytes(),
+      self.pixel_format,
+    );
+    let moving = motion
+      .last_signature
+      .as_ref()
+      .is_some_and(|previous| signature.is_moving_from(previous));
+    motion.filter = if moving {
+      FilterMode::Nearest
+    } else {
+      FilterMode::Bilinear
+    };
+    motion.last_signature = Some(signature);
+    debug_log!("  adaptive quality: moving={}", moving);
+  }
+
+  /// Filter used by scaled copies for the current frame
+  fn current_filter(&self) -> FilterMode {
+    if !self.adaptive_quality {
+      return self.filter_mode;
+    }
+    self
+      .motion
+      .lock()
+      .map(|motion| motion.filter)
+      .unwrap_or(FilterMode::Nearest)
+  }
+
+  /// Validates that the buffer matches the configured dimensions
+  fn validate_buffer(&self, buffer: &[u8]) -> napi::Result<(), RenderError> {
+    if self.pixel_format == PixelFormat::Indexed8 && self.palette.is_none() {
+      return Err(
+        RenderError::InvalidConfiguration
+          .error("Indexed8 buffers need a palette, call setPalette first"),
+      );
+    }
+    let bytes_per_pixel = self.pixel_format.bytes_per_pixel();
+    let too_large = || {
+      RenderError::InvalidConfiguration.error(buffer_too_large_message(
+        self.buffer_width,
+        self.buffer_height,
+        bytes_per_pixel,
+      ))
+    };
+    if let Some(stride) = self.source_stride {
+      validate_source_stride(stride, self.buffer_width, self.pixel_format)?;
+      let expected_len = buffer_len(stride, self.buffer_height, 1).ok_or_else(too_large)?;
+      if buffer.len() != expected_len {
+        return Err(RenderError::BufferSizeMismatch.error(format!(
+          "Buffer size mismatch: got {} bytes, expected {} bytes for {} rows of {} bytes",
+          buffer.len(),
+          expected_len,
+          self.buffer_height,
+          stride
+        )));
+      }
+      return Ok(());
+    }
+    let expected_len =
+      buffer_len(self.buffer_width, self.buffer_height, bytes_per_pixel).ok_or_else(too_large)?;
+    if buffer.len() != expected_len {
+      return Err(
+        RenderError::BufferSizeMismatch.error(buffer_size_mismatch_message(
+          buffer.len(),
+          expected_len,
+          self.buffer_width,
+          self.buffer_height,
+          bytes_per_pixel as usize,
+        )),
+      );
+    }
+    Ok(())
+  }
+
+  /// Draws into the window's cached frame and presents it
+  ///
+  /// `draw` receives the window-sized RGBA frame along with its layout, and a
+  /// timer to mark the end of the clear and copy stages on. Returns whether the
+  /// frame was presented, see [`Self::ready_state`] for when it isn't.
+  fn present<F>(
+    &self,
+    window: &crate::tao::structs::Window,
+    draw: F,
+  ) -> napi::Result<bool, RenderError>
+  where
+    F: FnOnce(&mut [u8], FrameSize, &mut StageTimer, &mut FrameScratch),
+  {
+    let window_arc = window
+      .inner
+      .as_ref()
+      .ok_or_else(window_not_initialized_error)?;
+
+    let window_guard = window_arc.lock().map_err(|_| window_lock_error())?;
+
+    // Get the window ID for caching
+    let window_id_u64 = crate::tao::structs::window_id_to_u64(window_guard.id());
+
+    let window_size = window_guard.inner_size();
+    let window_width = window_size.width;
+    let window_height = window_size.height;
+
+    // Render using cached pixels instance
+    self.render_cached(
+      window_id_u64,
+      Some(&window_guard),
+      window_width,
+      window_height,
+      window.min_present_interval(),
+      draw,
+    )
+  }
+
+  /// Render using cached pixels instance (platform-agnostic)
+  ///
+  /// `window` is only needed to create or recreate the surface. Without it
+  /// (e.g. when presenting from a [`RenderHandle`] worker), the window must
+  /// already have a cached state.
+  fn render_cached<F>(
+    &self,
+    window_id: u64,
+    window: Option<&crate::tao::structs::NativeWindow>,
+    window_width: u32,
+    window_height: u32,
+    min_present_interval: Duration,
+    draw: F,
+  ) -> napi::Result<bool, RenderError>
+  where
+    F: FnOnce(&mut [u8], FrameSize, &mut StageTimer, &mut FrameScratch),
+  {
+    // Get or create the rendering state from the global cache using entry API
+    let mut cache = RENDER_STATE
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    self.render_locked(
+      &mut cache,
+      PresentTarget {
+        window_id,
+        window,
+        width: window_width,
+        height: window_height,
+        min_present_interval,
+      },
+      draw,
+    )
+  }
+
+  /// Presents to a window while the render state cache is already locked
+  ///
+  /// Returns whether the frame was presented.
+  fn render_locked<F>(
+    &self,
+    cache: &mut std::collections::HashMap<u64, RenderState>,
+    target: PresentTarget,
+    draw: F,
+  ) -> napi::Result<bool, RenderError>
+  where
+    F: FnOnce(&mut [u8], FrameSize, &mut StageTimer, &mut FrameScratch),
+  {
+    let Some(state) = self.ready_state(cache, &target)? else {
+      return Ok(false);
+    };
+
+    // The GPU backend sizes the frame to the buffer; drawing here needs it window-sized
+    #[cfg(feature = "gpu")]
+    gpu::restore_frame_size(state);
+
+    self.render_with_state(state, target.width, target.height, draw)?;
+    self.notify_present(target.window_id, state);
+    Ok(true)
+  }
+
+  /// Queues the `on_present` callback for a frame just presented to a window
+  ///
+  /// Only queues the call, so it's safe while the render state cache is locked.
+  fn notify_present(&self, window_id: u64, state: &RenderState) {
+    if let Some(callback) = &self.on_present {
+      callback.call(
+        Ok(PresentInfo::for_state(window_id, state)),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+    }
+  }
+
+  /// Gets the window's render state ready for a frame, creating and resizing its surface
+  ///
+  /// Returns `None` when this frame shouldn't be presented: the window is
+  /// minimized or presented too recently.
+  fn ready_state<'c>(
+    &self,
+    cache: &'c mut std::collections::HashMap<u64, RenderState>,
+    target: &PresentTarget,
+  ) -> napi::Result<Option<&'c mut RenderState>, RenderError> {
+    let PresentTarget {
+      window_id,
+      window,
+      width: window_width,
+      height: window_height,
+      min_present_interval,
+    } = *target;
+
+    // A minimized window has nothing to present to
+    if window_width == 0 || window_height == 0 {
+      debug_log!(
+        "  window is {}x{}, skipping present",
+        window_width,
+        window_height
+      );
+      return Ok(None);
+    }
+
+    // Use entry API for single lookup - more efficient than contains_key + get_mut
+    let state = match cache.entry(window_id) {
+      std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+      std::collections::hash_map::Entry::Vacant(entry) => {
+        let window = window.ok_or_else(surface_unavailable_error)?;
+        entry.insert(create_render_state(window, window_width, window_height)?)
+      }
+    };
+
+    // Drop the frame if this window presented too recently
+    if remaining_interval(state.last_present, min_present_interval, Instant::now()).is_some() {
+      debug_log!("  skipping frame: min present interval not elapsed");
+      return Ok(None);
+    }
+
+    // Handle window resize if needed
+    let needs_resize =
+      state.last_window_width != window_width || state.last_window_height != window_height;
+
+    if !needs_resize {
+      state.pending_resize = None;
+    } else if self.manual_resize {
+      return Err(RenderError::SurfaceSizeMismatch.error(format!(
+        "Surface is {}x{} but the window is {}x{}; call resizeSurface() first or disable manualResize",
+        state.last_window_width, state.last_window_height, window_width, window_height
+      )));
+    } else if !resize_settled(
+      &mut state.pending_resize,
+      window_width,
+      window_height,
+      Instant::now(),
+      self.resize_debounce,
+    ) {
+      debug_log!(
+        "  deferring resize to {}x{} until the size settles",
+        window_width,
+        window_height
+      );
+    } else {
+      // On failure the last size is left as is, so the resize is retried on the next frame
+      resize_render_state(state, window_width, window_height);
+    }
+
+    Ok(Some(state))
+  }
+
+  /// Render using an already acquired state
+  fn render_with_state<F>(
+    &self,
+    state: &mut RenderState,
+    window_width: u32,
+    window_height: u32,
+    draw: F,
+  ) -> napi::Result<(), RenderError>
+  where
+    F: FnOnce(&mut [u8], FrameSize, &mut StageTimer, &mut FrameScratch),
+  {
+    // Draw into the frame's real layout, which lags behind the window if
+    // resizing the buffer failed
+    let extent = state.pixels.context().texture_extent;
+    let frame = state.pixels.frame_mut();
+    let size = FrameSize::for_frame(frame.len(), extent.width, extent.height);
+    debug_log!(
+      "  frame.len()={}, size={:?}, window={}x{}",
+      frame.len(),
+      size,
+      window_width,
+      window_height
+    );
+
+    let started = Instant::now();
+    let mut timer = StageTimer::new(self.detailed_timing);
+    draw(frame, size, &mut timer, &mut state.scratch);
+    apply_alpha_mode(frame, self.alpha_mode);
+    timer.end_copy();
+
+    // Render
+    state
+      .pixels
+      .render()
+      .map_err(|e| RenderError::PresentFailed.error(format!("Failed to render: {:?}", e)))?;
+    state.record_present(started, timer);
+
+    Ok(())
+  }
+
+  /// Clears the frame and draws the buffer over the whole window or the viewport
+  fn draw_frame(
+    &self,
+    frame: &mut [u8],
+    buffer: &[u8],
+    size: FrameSize,
+    timer: &mut StageTimer,
+    scratch: &mut FrameScratch,
+  ) {
+    if self.composite_mode == CompositeMode::SourceOver {
+      let region = match &self.viewport {
+        Some(viewport) => FrameRegion::clipped(viewport, size),
+        None => Some(FrameRegion::full(size)),
+      };
+      if let Some(region) = region.and_then(|region| region.inset(self.padding)) {
+        self.draw_over(frame, buffer, region, scratch);
+      }
+      timer.end_copy();
+      return;
+    }
+
+    let Some(viewport) = &self.viewport else {
+      // Clear with background color first
+      match &self.checker {
+        Some(checker) => checker.fill(frame, FrameRegion::full(size)),
+        None => clear_frame(frame, &self.bg_color),
+      }
+      timer.end_clear();
+
+      if let Some(region) = FrameRegion::full(size).inset(self.padding) {
+        self.draw_scaled(frame, buffer, region, self.scale_mode, scratch);
+      }
+      timer.end_copy();
+      return;
+    };
+
+    // A viewport entirely outside the window draws nothing
+    let Some(region) = FrameRegion::clipped(viewport, size) else {
+      debug_log!("  viewport outside window, nothing to draw");
+      return;
+    };
+
+    match &self.checker {
+      Some(checker) => checker.fill(frame, region),
+      None => fill_region(frame, region, &self.bg_color),
+    }
+    timer.end_clear();
+    if let Some(region) = region.inset(self.padding) {
+      self.draw_scaled(frame, buffer, region, self.scale_mode, scratch);
+    }
+    timer.end_copy();
+  }
+
+  /// Draws the buffer into a region, blending it over the frame's current contents
+  fn draw_over(
+    &self,
+    frame: &mut [u8],
+    buffer: &[u8],
+    region: FrameRegion,
+    scratch: &mut FrameScratch,
+  ) {
+    let mut backdrop = std::mem::take(&mut scratch.backdrop);
+    backdrop.clear();
+    backdrop.extend_from_slice(frame);
+    self.draw_scaled(frame, buffer, region, self.scale_mode, scratch);
+
+    // Only the pixels covered by the image and the clip were drawn; the rest must stay as is
+    let mode = self.resolve_scale_mode(self.scale_mode, region.width, region.height);
+    let (logical_width, logical_height) = self.logical_buffer_size();
+    let (offset_x, offset_y, scaled_width, scaled_height) =
+      self.scaled_dimensions(logical_width, logical_height, region, mode);
+    let (mut x0, mut y0) = (offset_x, offset_y);
+    let mut x1 = (offset_x + scaled_width).min(region.width);
+    let mut y1 = (offset_y + scaled_height).min(region.height);
+    if let Some(clip) = region.clip {
+      let (clip_x, clip_y) = (clip.x.max(0) as u32, clip.y.max(0) as u32);
+      x0 = x0.max(clip_x);
+      y0 = y0.max(clip_y);
+      x1 = x1.min(clip_x.saturating_add(clip.width));
+      y1 = y1.min(clip_y.saturating_add(clip.height));
+    }
+    for y in y0..y1 {
+      let start = region.index(x0, y);
+      let end = start + x1.saturating_sub(x0) as usize * 4;
+      if let (Some(row), Some(under)) = (frame.get_mut(start..end), backdrop.get(start..end)) {
+        alpha::blend_source_over(row, under);
+      }
+    }
+    scratch.backdrop = backdrop;
+  }
+
+  /// Redraws the window pixels covering a dirty rectangle of the buffer
+  fn draw_dirty(
+    &self,
+    frame: &mut [u8],
+    buffer: &[u8],
+    size: FrameSize,
+    dirty: &Rect,
+    scratch: &mut FrameScratch,
+  ) {
+    let region = match &self.viewport {
+      Some(viewport) => FrameRegion::clipped(viewport, size),
+      None => Some(FrameRegion::full(size)),
+    };
+    let Some(region) = region.and_then(|region| region.inset(self.padding)) else {
+      return;
+    };
+    let (left, top, width, height) = self.buffer_rect_to_window(dirty, size.width, size.height);
+    // One pixel of margin covers filtered pixels that blend in the dirty ones
+    let x0 = left.floor() - 1.0 - region.x as f64;
+    let y0 = top.floor() - 1.0 - region.y as f64;
+    let x1 = (left + width).ceil() + 1.0 - region.x as f64;
+    let y1 = (top + height).ceil() + 1.0 - region.y as f64;
+    let clip = Rect {
+      x: x0 as i32,
+      y: y0 as i32,
+      width: (x1 - x0).max(0.0) as u32,
+      height: (y1 - y0).max(0.0) as u32,
+    };
+    let Some(clip) = clip.clip_to(region.width, region.height) else {
+      debug_log!("  dirty rectangle outside the visible image");
+      return;
+    };
+    debug_log!("  render_region: clip={:?}", clip);
+    // Every tile shows the dirty pixels, so tiling redraws the whole region
+    let tiled = matches!(self.scale_mode, ScaleMode::Tile | ScaleMode::IntegerTile);
+    let clip = (!tiled).then_some(clip);
+    let region = FrameRegion { clip, ..region };
+    if self.composite_mode == CompositeMode::SourceOver {
+      self.draw_over(frame, buffer, region, scratch);
+    } else {
+      self.draw_scaled(frame, buffer, region, self.scale_mode, scratch);
+    }
+  }
+
+  /// Draws the buffer into a region of the frame using the given scale mode
+  ///
+  /// The region is assumed to already be cleared with the background color.
+  fn draw_scaled(
+    &self,
+    frame: &mut [u8],
+    buffer: &[u8],
+    region: FrameRegion,
+    mode: ScaleMode,
+    scratch: &mut FrameScratch,
+  ) {
+    let mode = self.resolve_scale_mode(mode, region.width, region.height);
+    let (logical_width, logical_height) = self.logical_buffer_size();
+
+    // Apply scaling if needed
+    let (offset_x, offset_y, scaled_width, scaled_height) =
+      self.scaled_dimensions(logical_width, logical_height, region, mode);
+
+    debug_log!(
+      "draw_scaled: buffer={}x{}, region={}x{}+{}+{}, scale_mode={:?}",
+      self.buffer_width,
+      self.buffer_height,
+      region.width,
+      region.height,
+      region.x,
+      region.y,
+      mode
+    );
+    debug_log!(
+      "  calculated: offset=({}, {}), scaled={}x{}",
+      offset_x,
+      offset_y,
+      scaled_width,
+      scaled_height
+    );
+
+    let (oriented_width, oriented_height) = self.oriented_buffer_size();
+    // A source rectangle is addressed as an image starting at its first pixel
+    let (source_x, source_y, _, _) = self.source_bounds();
+    let stride = self.source_row_bytes();
+    let start = source_y as usize * stride as usize
+      + source_x as usize * self.pixel_format.bytes_per_pixel() as usize;
+    let source = SourceImage {
+      data: buffer.get(start..).unwrap_or_default(),
+      width: oriented_width,
+      height: oriented_height,
+      flip_x: self.flip_horizontal,
+      flip_y: self.rows_flipped(),
+      rotation: self.rotation,
+      tone: self.tone.as_ref(),
+      tint: self.tint,
+      stride,
+      palette: match self.pixel_format {
+        PixelFormat::Indexed8 => self.palette.as_deref(),
+        _ => None,
+      },
+      wrap: self.wrap_mode,
+      format: self.pixel_format,
+    };
+    let sampling = Sampling {
+      scaler: match &self.scaler {
+        Some(scaler) => scaler.as_ref(),
+        None => scaler_for_filter(self.current_filter()),
+      },
+      dither: self.dither,
+      scratch,
+    };
+
+    // Copy source buffer with scaling
+    // The frame buffer is sized to window_width x window_height
+    // We need to scale the source buffer to fit properly within the region
+    match mode {
+      ScaleMode::Stretch if (scaled_width, scaled_height) != (region.width, region.height) => {
+        // Stretch capped by the max aspect skew, letterboxed on the capped axis
+        scale_buffer_fit(
+          frame,
+          &source,
+          ScaleBufferFitParams {
+            region,
+            offset_x,
+            offset_y,
+            scaled_width,
+            scaled_height,
+            sampling,
+          },
+        );
+      }
+      ScaleMode::Stretch => {
+        // Stretch mode: scale entire buffer to fill the region
+        scale_buffer_stretch(frame, &source, region, sampling);
+      }
+      ScaleMode::None if (logical_width, logical_height) == (source.width, source.height) => {
+        // Place at the anchor without scaling, crop if buffer is larger than the region
+        let position = anchor_position(
+          region.width,
+          region.height,
+          source.width,
+          source.height,
+          self.anchor,
+        );
+        copy_buffer_positioned(frame, &source, region, position);
+      }
+      ScaleMode::None => {
+        // Place at the logical size, crop if that is larger than the region
+        let position = anchor_position(
+          region.width,
+          region.height,
+          logical_width,
+          logical_height,
+          self.anchor,
+        );
+        scale_buffer_positioned(
+          frame,
+          &source,
+          region,
+          ScaledPlacement {
+            size: (logical_width, logical_height),
+            position,
+          },
+          sampling,
+        );
+      }
+      ScaleMode::FitWidth | ScaleMode::FitHeight => {
+        // Fill one axis, letterbox or crop the other around the anchor
+        let position = anchor_position(
+          region.width,
+          region.height,
+          scaled_width,
+          scaled_height,
+          self.anchor,
+        );
+        scale_buffer_positioned(
+          frame,
+          &source,
+          region,
+          ScaledPlacement {
+            size: (scaled_width, scaled_height),
+            position,
+          },
+          sampling,
+        );
+      }
+      ScaleMode::Fill => {
+        // Fill mode: scale buffer maintaining aspect ratio to fill the region
+        scale_buffer_fill(frame, &source, region, self.balanced_letterbox, sampling);
+      }
+      ScaleMode::Tile => {
+        // Tile mode: repeat the buffer across the whole region
+        copy_buffer_tile(frame, &source, region, self.tile_offset, self.scale_factor);
+      }
+      ScaleMode::IntegerTile => {
+        // Whole-pixel tiles: the scale ignores the scale factor so it stays an integer
+        let (buffer_width, buffer_height) = self.oriented_buffer_size();
+        let scale = integer_scale(buffer_width, buffer_height, region.width, region.height);
+        copy_buffer_tile(frame, &source, region, self.tile_offset, scale as f64);
+      }
+      _ => {
+        // Fit, Integer, IntegerFit - scale buffer maintaining aspect ratio to fit within the region
+        scale_buffer_fit(
+          frame,
+          &source,
+          ScaleBufferFitParams {
+            region,
+            offset_x,
+            offset_y,
+            scaled_width,
+            scaled_height,
+            sampling,
+          },
+        );
+      }
+    }
+  }
+}
+
+/// Simple function to render a pixel buffer to a window
+///
+/// This is a convenience function for one-off renders.
+/// For repeated rendering, use [`PixelRenderer`] instead.
+///
+/// # Warning
+/// Using this function repeatedly (200+ times) may cause resource exhaustion errors.
+/// For repeated rendering, create a [`PixelRenderer`] instance and reuse it.
+#[napi]
+pub fn render_pixels(
+  window: &crate::tao::structs::Window,
+  buffer: Buffer,
+  buffer_width: u32,
+  buffer_height: u32,
+) -> napi::Result<(), RenderError> {
+  let renderer = PixelRenderer::new(buffer_width, buffer_height)
+    .map_err(|e| RenderError::InvalidArgument.wrap(e))?;
+  renderer.render(window, buffer, None).map(|_| ())
+}
+
+/// Blocks until every frame queued with `renderThreaded` has been presented
+///
+/// Throws the error of the most recent failed threaded present, if any.
+#[napi]
+pub fn flush_render_thread() -> napi::Result<(), RenderError> {
+  thread::flush()
+}
+
+/// Returns the IDs of windows that currently have cached render state
+///
+/// IDs match `Window.id`, so tooling can reconcile the cache against live
+/// windows and release entries left behind by windows closed without cleanup.
+#[napi]
+pub fn cached_render_windows() -> napi::Result<Vec<u64>, RenderError> {
+  let cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  let ids = cache.keys().copied().collect();
+  Ok(ids)
+}
+
+/// Describes the window's render surface, for attaching to bug reports
+///
+/// Fields other than `cached` are null until something has been rendered to the window.
+#[napi]
+pub fn renderer_info(
+  window: &crate::tao::structs::Window,
+) -> napi::Result<RendererInfo, RenderError> {
+  let window_id = window
+    .id()
+    .map_err(|e| RenderError::WindowNotInitialized.wrap(e))?;
+  let cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  let Some(state) = cache.get(&window_id) else {
+    return Ok(RendererInfo::default());
+  };
+  let adapter = state.pixels.adapter().get_info();
+  Ok(RendererInfo {
+    cached: true,
+    backend: Some(adapter.backend.to_str().to_string()),
+    adapter_name: Some(adapter.name),
+    software: Some(adapter.device_type == pixels::wgpu::DeviceType::Cpu),
+    last_window_width: Some(state.last_window_width),
+    last_window_height: Some(state.last_window_height),
+  })
+}
+
+/// Returns the number of windows with cached render state
+#[napi]
+pub fn render_cache_len() -> napi::Result<u32, RenderError> {
+  let cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  let len = cache.len() as u32;
+  Ok(len)
+}
+
+/// Reports how many windows have cached render state and roughly how much
+/// memory their surfaces hold
+///
+/// A count that keeps growing as windows open and close points at windows
+/// closed without releasing their state.
+#[napi]
+pub fn render_cache_stats() -> napi::Result<CacheStats, RenderError> {
+  let cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  Ok(CacheStats::for_surfaces(cache.values().map(|state| {
+    (state.last_window_width, state.last_window_height)
+  })))
+}
+
+/// Releases the cached render state for a window ID (see `Window.renderCacheId()`)
+///
+/// Windows release their state automatically when closed or garbage collected,
+/// so this is only needed to free a surface early.
+///
+/// Returns true if the window had cached state. The next render to the window
+/// creates a fresh surface.
+#[napi]
+pub fn clear_render_cache(window_id: BigInt) -> napi::Result<bool, RenderError> {
+  let (_, id, lossless) = window_id.get_u64();
+  if !lossless {
+    return Ok(false);
+  }
+  evict_render_state(id)
+}
+
+/// Drops the cached render state of a window, returning whether it had any
+pub(crate) fn evict_render_state(window_id: u64) -> napi::Result<bool, RenderError> {
+  let mut cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  let removed = cache.remove(&window_id).is_some();
+  Ok(removed)
+}
+
+/// Releases the cached render state of every window
+#[napi]
+pub fn clear_render_caches() -> napi::Result<(), RenderError> {
+  let mut cache = RENDER_STATE
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  cache.clear();
+  Ok(())
+}
+
+pub mod alpha;
+pub mod buffer_ops;
+pub mod clipboard;
+pub mod display_list;
+pub mod dither;
+pub mod error;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod handle;
+#[cfg(feature = "image")]
+pub mod image_file;
+pub mod motion;
+pub mod pixel_format;
+pub mod rotation;
+pub mod row_copy;
+pub mod scaler;
+pub mod scaling;
+pub mod schedule;
+pub mod scratch;
+pub mod task;
+pub mod thread;
+pub mod tone;
+pub mod validation;
+
+/// Width in pixels of the divider drawn by [`PixelRenderer::render_split`]
+const SPLIT_DIVIDER_WIDTH: u32 = 2;
+
+/// Color of the divider drawn by [`PixelRenderer::render_split`]
+const SPLIT_DIVIDER_COLOR: [u8; 4] = [255, 255, 255, 255];
+
+/// Creates the pixels surface and state for a window
+fn create_render_state(
+  window: &crate::tao::structs::NativeWindow,
+  window_width: u32,
+  window_height: u32,
+) -> napi::Result<RenderState, RenderError> {
+  let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, &**window);
+  let new_pixels =
+    pixels::Pixels::new(window_width, window_height, surface_texture).map_err(|e| {
+      RenderError::SurfaceCreationFailed.error(format!("Failed to create pixels: {:?}", e))
+    })?;
+
+  // SAFETY: `Pixels` borrows the window, but the cache needs it `'static`.
+  // Render state is only created from a `NativeWindow`, whose `WindowRelease`
+  // guard is dropped before the native window and evicts its entry from
+  // `RENDER_STATE`, so the surface never outlives the window. Entries are keyed
+  // by the ID from the `WINDOW_IDS` registry, which never hands out an ID twice,
+  // so a later window can't pick up this state. If the cache lock is poisoned
+  // the eviction is skipped, but then every cache access fails and the entry is
+  // never used again.
+  let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(new_pixels) };
+
+  Ok(RenderState {
+    pixels: static_pixels,
+    last_window_width: window_width,
+    last_window_height: window_height,
+    pending_resize: None,
+    last_present: None,
+    last_frame: None,
+    stats: RenderStats::default(),
+    history: PresentHistory::default(),
+    scratch: FrameScratch::for_width(window_width),
+    #[cfg(feature = "gpu")]
+    gpu: None,
+  })
+}
+
+/// Resizes a window's surface and frame, returning false if the surface kept its size
+fn resize_render_state(state: &mut RenderState, window_width: u32, window_height: u32) -> bool {
+  debug_log!(
+    "  window resized: {}x{} -> {}x{}",
+    state.last_window_width,
+    state.last_window_height,
+    window_width,
+    window_height
+  );
+
+  // Resize the existing surface in place; recreating the context on every
+  // size change would stall interactive drag-resizing
+  if let Err(e) = state.pixels.resize_surface(window_width, window_height) {
+    // Keep presenting at the previous size
+    debug_log!("  resize_surface failed: {:?}, keeping previous size", e);
+    return false;
+  }
+
+  // Also resize the pixel buffer to match window dimensions
+  if let Err(e) = state.pixels.resize_buffer(window_width, window_height) {
+    debug_log!("  resize_buffer failed: {:?}", e);
+  }
+
+  state.scratch.resize(window_width);
+
+  // Update cached window size
+  state.last_window_width = window_width;
+  state.last_window_height = window_height;
+  state.pending_resize = None;
+  debug_log!(
+    "  resized surface and buffer to {}x{}",
+    window_width,
+    window_height
+  );
+  true
+}
+
+/// Records the frame a window shows after an attempt to present `key`
+///
+/// A frame that wasn't presented leaves the window showing the previous one,
+/// so submitting it again mustn't be skipped as a duplicate.
+fn remember_frame(last_frame: &mut Option<FrameKey>, key: FrameKey, presented: bool) {
+  if presented {
+    *last_frame = Some(key);
+  }
+}
+
+/// Time left until a window that last presented at `last_present` may present again
+fn remaining_interval(
+  last_present: Option<Instant>,
+  min_present_interval: Duration,
+  now: Instant,
+) -> Option<Duration> {
+  let remaining = (last_present? + min_present_interval).checked_duration_since(now)?;
+  (!remaining.is_zero()).then_some(remaining)
+}
+
+/// Error for a poisoned render state cache lock
+fn render_state_poisoned_error() -> napi::Error<RenderError> {
+  RenderError::LockPoisoned.error("Failed to lock render state cache")
+}
+
+/// Validates a maximum aspect skew
+fn validate_max_aspect_skew(max_skew: f64) -> napi::Result<()> {
+  if !max_skew.is_finite() || max_skew < 0.0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid max aspect skew: {}", max_skew),
+    ));
+  }
+  Ok(())
+}
+
+/// Validates an auto aspect tolerance, which must be finite and not negative
+fn validate_auto_aspect_tolerance(tolerance: f64) -> napi::Result<()> {
+  if !tolerance.is_finite() || tolerance < 0.0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid auto aspect tolerance: {}", tolerance),
+    ));
+  }
+  Ok(())
+}
+
+/// Validates a scale factor, which must be finite and positive
+fn validate_scale_factor(scale_factor: f64) -> napi::Result<()> {
+  if !scale_factor.is_finite() || scale_factor <= 0.0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid scale factor: {}", scale_factor),
+    ));
+  }
+  Ok(())
+}
+
+/// Validates that a source stride holds at least one row of `width` pixels
+fn validate_source_stride(
+  stride: u32,
+  width: u32,
+  format: PixelFormat,
+) -> napi::Result<(), RenderError> {
+  let row_len = width.checked_mul(format.bytes_per_pixel());
+  if row_len.is_none_or(|row_len| stride < row_len) {
+    return Err(RenderError::InvalidConfiguration.error(format!(
+      "Source stride {} is smaller than a row of {} {:?} pixels",
+      stride, width, format
+    )));
+  }
+  Ok(())
+}
+
+/// Validates that a source rectangle is non-empty and lies within the buffer
+fn validate_source_rect(rect: &Rect, buffer_width: u32, buffer_height: u32) -> napi::Result<()> {
+  let within = |start: i32, len: u32, limit: u32| {
+    start >= 0 && len > 0 && start as u64 + len as u64 <= limit as u64
+  };
+  if !within(rect.x, rect.width, buffer_width) || !within(rect.y, rect.height, buffer_height) {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid source rect: {}x{}+{}+{} is not within the {}x{} buffer",
+        rect.width, rect.height, rect.x, rect.y, buffer_width, buffer_height
+      ),
+    ));
+  }
+  Ok(())
+}
+
+/// Reads an [R, G, B] or [R, G, B, A] background color
+fn parse_background_color(color: &[u8]) -> napi::Result<[u8; 4]> {
+  match *color {
+    [r, g, b] => Ok([r, g, b, 255]),
+    [r, g, b, a] => Ok([r, g, b, a]),
+    _ => Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid background color: expected 3 (RGB) or 4 (RGBA) values, got {}",
+        color.len()
+      ),
+    )),
+  }
+}
+
+/// Reads [top, right, bottom, left] padding
+fn parse_padding(padding: &[u32]) -> napi::Result<[u32; 4]> {
+  <[u32; 4]>::try_from(padding).map_err(|_| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid padding: expected 4 values (top, right, bottom, left), got {}",
+        padding.len()
+      ),
+    )
+  })
+}
+
+/// Reads an [x, y] tile offset
+fn parse_tile_offset(offset: &[i32]) -> napi::Result<(i32, i32)> {
+  match *offset {
+    [x, y] => Ok((x, y)),
+    _ => Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid tile offset: expected 2 values (x, y), got {}",
+        offset.len()
+      ),
+    )),
+  }
+}
+
+/// Parses a tint, [R, G, B] (opaque) or [R, G, B, A]
+fn parse_tint(tint: &[u8]) -> napi::Result<[u8; 4]> {
+  parse_background_color(tint).map_err(|_| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid tint: expected 3 (RGB) or 4 (RGBA) values, got {}",
+        tint.len()
+      ),
+    )
+  })
+}
+
+/// Error for presents that would need a surface but have no window to create it from
+fn surface_unavailable_error() -> napi::Error<RenderError> {
+  RenderError::SurfaceUnavailable
+    .error("Render surface is not available; render to the window from its own thread first")
+}
+
+/// Error for rendering to a window that has no native window
+fn window_not_initialized_error() -> napi::Error<RenderError> {
+  RenderError::WindowNotInitialized.error("Window not initialized")
+}
+
+/// Error for a poisoned window lock
+pub(super) fn window_lock_error() -> napi::Error<RenderError> {
+  RenderError::LockPoisoned.error("Failed to lock window")
+}
+
+/// Measures how long each stage of a frame takes when detailed timing is enabled
+struct StageTimer {
+  /// End of the previous stage (None when timing is disabled)
+  last: Option<Instant>,
+  clear: Duration,
+  copy: Duration,
+  present: Duration,
+}
+
+impl StageTimer {
+  fn new(enabled: bool) -> Self {
+    Self {
+      last: enabled.then(Instant::now),
+      clear: Duration::ZERO,
+      copy: Duration::ZERO,
+      present: Duration::ZERO,
+    }
+  }
+
+  /// Time since the previous stage ended, restarting the clock
+  fn lap(&mut self) -> Duration {
+    let Some(last) = self.last else {
+      return Duration::ZERO;
+    };
+    let now = Instant::now();
+    self.last = Some(now);
+    now - last
+  }
+
+  fn end_clear(&mut self) {
+    let lap = self.lap();
+    self.clear += lap;
+  }
+
+  fn end_copy(&mut self) {
+    let lap = self.lap();
+    self.copy += lap;
+  }
+
+  fn end_present(&mut self) {
+    let lap = self.lap();
+    self.present += lap;
+  }
+
+  /// Stores the measured stages, or clears them when timing was disabled
+  fn write_to(&self, stats: &mut RenderStats) {
+    let micros = |d: Duration| {
+      self
+        .last
+        .map(|_| d.as_micros().min(u32::MAX as u128) as u32)
+    };
+    stats.clear_us = micros(self.clear);
+    stats.copy_us = micros(self.copy);
+    stats.present_us = micros(self.present);
+  }
+}
+
+/// Window that a frame is presented to
+#[derive(Clone, Copy)]
+struct PresentTarget<'a> {
+  window_id: u64,
+  /// Only needed to create or recreate the window's surface
+  window: Option<&'a crate::tao::structs::NativeWindow>,
+  /// Current inner width of the window in pixels
+  width: u32,
+  /// Current inner height of the window in pixels
+  height: u32,
+  /// Minimum time between presents to the window
+  min_present_interval: Duration,
+}
+
+/// A window size seen while the surface still has another size
+#[derive(Debug, Clone, Copy)]
+struct PendingResize {
+  width: u32,
+  height: u32,
+  /// When the window first had this size
+  since: Instant,
+}
+
+/// Whether the surface should be resized to a window size seen at `now`
+///
+/// Tracks the size in `pending` and reports true once it has stayed the same for
+/// `debounce`; any other size restarts the wait.
+fn resize_settled(
+  pending: &mut Option<PendingResize>,
+  width: u32,
+  height: u32,
+  now: Instant,
+  debounce: Duration,
+) -> bool {
+  if debounce.is_zero() {
+    return true;
+  }
+  match pending {
+    Some(resize) if (resize.width, resize.height) == (width, height) => {
+      now.saturating_duration_since(resize.since) >= debounce
+    }
+    _ => {
+      *pending = Some(PendingResize {
+        width,
+        height,
+        since: now,
+      });
+      false
+    }
+  }
+}
+
+/// Number of recent frames that `RenderStats` averages over
+const FRAME_HISTORY_LEN: usize = 32;
+
+/// Ring buffer of the durations of recent frames
+#[derive(Debug, Default)]
+struct FrameHistory {
+  micros: [u32; FRAME_HISTORY_LEN],
+  /// Slot the next duration is written to
+  next: usize,
+  /// Number of recorded durations (at most `FRAME_HISTORY_LEN`)
+  len: usize,
+  /// Total number of durations ever recorded
+  total: u32,
+}
+
+impl FrameHistory {
+  fn push(&mut self, duration: Duration) {
+    self.micros[self.next] = duration.as_micros().min(u32::MAX as u128) as u32;
+    self.next = (self.next + 1) % FRAME_HISTORY_LEN;
+    self.len = (self.len + 1).min(FRAME_HISTORY_LEN);
+    self.total = self.total.saturating_add(1);
+  }
+
+  /// Most recently recorded duration in microseconds
+  fn last(&self) -> Option<u32> {
+    (self.len > 0).then(|| self.micros[(self.next + FRAME_HISTORY_LEN - 1) % FRAME_HISTORY_LEN])
+  }
+
+  /// Mean of the recorded durations in microseconds
+  fn average(&self) -> Option<f64> {
+    let sum: u64 = self.micros[..self.len].iter().map(|&us| us as u64).sum();
+    (self.len > 0).then(|| sum as f64 / self.len as f64)
+  }
+}
+
+/// Timing of recent presents to a window
+#[derive(Debug, Default)]
+struct PresentHistory {
+  /// Time from starting to draw a frame until it was presented
+  frame_times: FrameHistory,
+  /// Time between consecutive presents
+  intervals: FrameHistory,
+}
+
+impl PresentHistory {
+  fn write_to(&self, stats: &mut RenderStats) {
+    stats.last_frame_us = self.frame_times.last();
+    stats.avg_frame_us = self.frame_times.average().map(|us| us.round() as u32);
+    stats.fps = self
+      .intervals
+      .average()
+      .filter(|&us| us > 0.0)
+      .map(|us| 1_000_000.0 / us);
+    stats.frames_presented = self.frame_times.total;
+  }
+}
+
+/// Destination rectangle within a window-sized frame
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameRegion {
+  /// Distance between the starts of consecutive frame rows, in pixels
+  stride: u32,
+  /// Left edge of the region
+  x: u32,
+  /// Top edge of the region
+  y: u32,
+  /// Width of the region
+  width: u32,
+  /// Height of the region
+  height: u32,
+  /// Region-relative rectangle that copies are limited to (the whole region when `None`)
+  clip: Option<Rect>,
+}
+
+impl FrameRegion {
+  /// Region covering the whole frame
+  fn full(size: FrameSize) -> Self {
+    Self {
+      stride: size.stride,
+      x: 0,
+      y: 0,
+      width: size.width,
+      height: size.height,
+      clip: None,
+    }
+  }
+
+  /// Region covering the part of `rect` that lies inside the frame
+  ///
+  /// Returns `None` when the intersection is empty.
+  fn clipped(rect: &Rect, size: FrameSize) -> Option<Self> {
+    let clipped = rect.clip_to(size.width, size.height)?;
+    Some(Self {
+      stride: size.stride,
+      x: clipped.x as u32,
+      y: clipped.y as u32,
+      width: clipped.width,
+      height: clipped.height,
+      clip: None,
+    })
+  }
+
+  /// Region left after removing `[top, right, bottom, left]` margins
+  ///
+  /// Returns `None` when the margins leave nothing.
+  fn inset(self, [top, right, bottom, left]: [u32; 4]) -> Option<Self> {
+    let width = self.width.checked_sub(left.checked_add(right)?)?;
+    let height = self.height.checked_sub(top.checked_add(bottom)?)?;
+    (width > 0 && height > 0).then_some(Self {
+      x: self.x + left,
+      y: self.y + top,
+      width,
+      height,
+      ..self
+    })
+  }
+
+  /// Splits the region into left and right halves around a centered divider
+  ///
+  /// Returns the (left, divider, right) regions.
+  fn split(self, divider_width: u32) -> (Self, Self, Self) {
+    let start = (self.width / 2).saturating_sub(divider_width / 2);
+    let end = (start + divider_width).min(self.width);
+    (
+      Self {
+        width: start,
+        ..self
+      },
+      Self {
+        x: self.x + start,
+        width: end - start,
+        ..self
+      },
+      Self {
+        x: self.x + end,
+        width: self.width - end,
+        ..self
+      },
+    )
+  }
+
+  /// Whether a region-relative pixel lies inside the clip rectangle
+  fn in_clip(&self, x: u32, y: u32) -> bool {
+    self.clip.is_none_or(|clip| {
+      let (x, y) = (x as i64, y as i64);
+      x >= clip.x as i64
+        && y >= clip.y as i64
+        && x < clip.x as i64 + clip.width as i64
+        && y < clip.y as i64 + clip.height as i64
+    })
+  }
+
+  /// Byte index into the frame of the given region-relative pixel
+  fn index(&self, x: u32, y: u32) -> usize {
+    ((self.y + y) as usize * self.stride as usize + (self.x + x) as usize) * 4
+  }
+}
+
+/// Dimensions and row layout of a frame being drawn into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FrameSize {
+  /// Visible width in pixels
+  pub(crate) width: u32,
+  /// Height in pixels
+  pub(crate) height: u32,
+  /// Distance between the starts of consecutive rows, in pixels (at least `width`)
+  pub(crate) stride: u32,
+}
+
+impl FrameSize {
+  /// Layout of a frame whose rows are stored back to back
+  pub(crate) fn packed(width: u32, height: u32) -> Self {
+    Self {
+      width,
+      height,
+      stride: width,
+    }
+  }
+
+  /// Layout of a `len`-byte RGBA frame holding `width` x `height` visible pixels
+  ///
+  /// Bytes beyond `width * height * 4` are treated as row padding, so surfaces
+  /// that align their rows are addressed correctly instead of drifting.
+  pub(crate) fn for_frame(len: usize, width: u32, height: u32) -> Self {
+    let stride = if height == 0 {
+      width
+    } else {
+      ((len / 4 / height as usize) as u32).max(width)
+    };
+    debug_assert!(
+      len >= stride as usize * height as usize * 4,
+      "frame of {} bytes is smaller than {}x{}",
+      len,
+      width,
+      height
+    );
+    Self {
+      width,
+      height,
+      stride,
+    }
+  }
+}
+
+/// Source pixel buffer along with the layout needed to address its pixels
+pub(crate) struct SourceImage<'a> {
+  data: &'a [u8],
+  /// Width of the image as displayed (after rotation)
+  width: u32,
+  /// Height of the image as displayed (after rotation)
+  height: u32,
+  /// Whether columns are read right to left
+  flip_x: bool,
+  /// Whether rows are read bottom to top (bottom-left origin or vertical flip)
+  flip_y: bool,
+  /// Rotation from the stored buffer to the displayed image
+  rotation: Rotation,
+  /// Gamma and brightness applied to each pixel read
+  tone: Option<&'a ToneCurve>,
+  /// Color multiplied into each pixel read, after the tone curve
+  tint: Option<[u8; 4]>,
+  /// Bytes from the start of one stored row to the next
+  stride: u32,
+  /// Colors looked up by `Indexed8` pixels
+  palette: Option<&'a Palette>,
+  wrap: WrapMode,
+  format: PixelFormat,
+}
+
+impl SourceImage<'_> {
+  /// Returns the pixel displayed at (x, y) as RGBA, with y counted from the top
+  ///
+  /// Rotation and flips are applied here, so scalers see the image as it
+  /// should appear. Returns `None` for coordinates outside the image.
+  fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+    if x >= self.width || y >= self.height {
+      return None;
+    }
+    // Undoing a rotation swaps the axes back the same way
+    let (stored_width, stored_height) = self.rotation.rotated_size(self.width, self.height);
+    let (x, y) = self
+      .rotation
+      .source_pixel(x, y, stored_width, stored_height);
+    let column = if self.flip_x { stored_width - 1 - x } else { x };
+    let row = if self.flip_y {
+      stored_height - 1 - y
+    } else {
+      y
+    };
+    let bytes_per_pixel = self.format.bytes_per_pixel();
+    let idx = row as usize * self.stride as usize + column as usize * bytes_per_pixel as usize;
+    let pixel = self
+      .data
+      .get(idx..idx + bytes_per_pixel as usize)
+      .map(|pixel| match self.palette {
+        Some(palette) => palette.color(pixel[0]),
+        None => self.format.to_rgba(pixel),
+      })?;
+    let pixel = match self.tone {
+      Some(tone) => tone.apply(pixel),
+      None => pixel,
+    };
+    Some(match self.tint {
+      Some(tint) => apply_tint(pixel, tint),
+      None => pixel,
+    })
+  }
+
+  /// Whether pixels are displayed exactly as stored (no rotation, mirroring,
+  /// tone curve, tint or palette), so rows can be copied directly
+  fn is_stored_layout(&self) -> bool {
+    !self.flip_x
+      && self.rotation == Rotation::None
+      && self.tone.is_none()
+      && self.tint.is_none()
+      && self.palette.is_none()
+  }
+
+  /// Stored bytes of `len` pixels starting at displayed pixel (x, y)
+  ///
+  /// Only meaningful for images in their stored layout (see `is_stored_layout`).
+  fn stored_row(&self, x: u32, y: u32, len: u32) -> Option<&[u8]> {
+    if x + len > self.width || y >= self.height {
+      return None;
+    }
+    let row = if self.flip_y { self.height - 1 - y } else { y };
+    let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+    let start = row as usize * self.stride as usize + x as usize * bytes_per_pixel;
+    self.data.get(start..start + len as usize * bytes_per_pixel)
+  }
+
+  /// Blends the four pixels around (x, y) with bilinear weights
+  ///
+  /// Neighbors outside the buffer are resolved with the image's wrap mode.
+  #[cfg(test)]
+  fn sample_bilinear(&self, x: f32, y: f32) -> [f32; 4] {
+    self.blend_taps(self.tap_x(x), self.tap_y(y))
+  }
+
+  /// Neighboring columns and blend weight for a horizontal sample position
+  fn tap_x(&self, x: f32) -> BilinearTap {
+    BilinearTap::new(x, self.width, self.wrap)
+  }
+
+  /// Neighboring rows and blend weight for a vertical sample position
+  fn tap_y(&self, y: f32) -> BilinearTap {
+    BilinearTap::new(y, self.height, self.wrap)
+  }
+
+  /// Blends the four pixels selected by a column and a row tap
+  ///
+  /// Lets scalers compute taps once per column and row instead of per pixel.
+  fn blend_taps(&self, tap_x: BilinearTap, tap_y: BilinearTap) -> [f32; 4] {
+    let BilinearTap {
+      near: x0,
+      far: x1,
+      weight: tx,
+    } = tap_x;
+    let BilinearTap {
+      near: y0,
+      far: y1,
+      weight: ty,
+    } = tap_y;
+
+    let fetch = |px: u32, py: u32| self.pixel(px, py).unwrap_or([0, 0, 0, 0]);
+    let (p00, p10, p01, p11) = (fetch(x0, y0), fetch(x1, y0), fetch(x0, y1), fetch(x1, y1));
+
+    let mut out = [0.0; 4];
+    for (c, value) in out.iter_mut().enumerate() {
+      let top = p00[c] as f32 + (p10[c] as f32 - p00[c] as f32) * tx;
+      let bottom = p01[c] as f32 + (p11[c] as f32 - p01[c] as f32) * tx;
+      *value = top + (bottom - top) * ty;
+    }
+    out
+  }
+}
+
+/// The two source pixels along one axis that a bilinear sample blends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BilinearTap {
+  /// Pixel at or before the sample position
+  near: u32,
+  /// Pixel after the sample position
+  far: u32,
+  /// Weight of `far` (0.0..1.0)
+  weight: f32,
+}
+
+impl BilinearTap {
+  fn new(position: f32, size: u32, wrap: WrapMode) -> Self {
+    let floor = position.floor();
+    let index = floor as i64;
+    Self {
+      near: wrap_coord(index, size, wrap),
+      far: wrap_coord(index + 1, size, wrap),
+      weight: position - floor,
+    }
+  }
+}
+
+/// Maps a possibly out-of-range coordinate into `0..size` using the wrap mode
+fn wrap_coord(coord: i64, size: u32, mode: WrapMode) -> u32 {
+  if size == 0 {
+    return 0;
+  }
+  let size = size as i64;
+  let wrapped = match mode {
+    WrapMode::Clamp => coord.clamp(0, size - 1),
+    WrapMode::Wrap => coord.rem_euclid(size),
+    WrapMode::Mirror => {
+      // Reflection repeats every two sizes: 0..size forward, then backward
+      let period = coord.rem_euclid(size * 2);
+      if period < size {
+        period
+      } else {
+        size * 2 - 1 - period
+      }
+    }
+  };
+  wrapped as u32
+}
+
+/// Rewrites the frame's alpha channel for the alpha mode
+fn apply_alpha_mode(frame: &mut [u8], mode: AlphaMode) {
+  match mode {
+    AlphaMode::Straight => {}
+    AlphaMode::Opaque => {
+      for pixel in frame.chunks_exact_mut(4) {
+        pixel[3] = 255;
+      }
+    }
+    AlphaMode::Premultiplied => alpha::premultiply_in_place(frame),
+  }
+}
+
+/// Copies the visible pixels of a frame, dropping any row padding
+fn unpad_frame(frame: &[u8], size: FrameSize) -> Vec<u8> {
+  let row_len = size.width as usize * 4;
+  let stride = size.stride as usize * 4;
+  if row_len == 0 {
+    return Vec::new();
+  }
+  frame
+    .chunks(stride)
+    .take(size.height as usize)
+    .flat_map(|row| &row[..row_len.min(row.len())])
+    .copied()
+    .collect()
+}
+
+/// Fills the whole frame with a single color
+fn clear_frame(frame: &mut [u8], color: &[u8; 4]) {
+  for pixel in frame.chunks_exact_mut(4) {
+    pixel.copy_from_slice(color);
+  }
+}
+
+/// Fills a region of the frame with a single color
+fn fill_region(frame: &mut [u8], region: FrameRegion, color: &[u8; 4]) {
+  for y in 0..region.height {
+    for x in 0..region.width {
+      let dst_idx = region.index(x, y);
+      if dst_idx + 4 <= frame.len() {
+        frame[dst_idx..dst_idx + 4].copy_from_slice(color);
+      }
+    }
+  }
+}
+
+/// Parsed [`Checkerboard`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CheckerFill {
+  colors: [[u8; 4]; 2],
+  cell_size: u32,
+}
+
+impl CheckerFill {
+  fn parse(checkerboard: &Checkerboard) -> napi::Result<Self> {
+    if checkerboard.cell_size == 0 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Invalid checkerboard: cell size must be at least 1".to_string(),
+      ));
+    }
+    Ok(Self {
+      colors: [
+        parse_background_color(&checkerboard.color_a)?,
+        parse_background_color(&checkerboard.color_b)?,
+      ],
+      cell_size: checkerboard.cell_size,
+    })
+  }
+
+  /// Fills a region with the pattern, cells counted from its top-left corner
+  fn fill(&self, frame: &mut [u8], region: FrameRegion) {
+    for y in 0..region.height {
+      let row_parity = (y / self.cell_size) % 2;
+      for x in 0..region.width {
+        let color = &self.colors[((x / self.cell_size + row_parity) % 2) as usize];
+        let dst_idx = region.index(x, y);
+        if let Some(pixel) = frame.get_mut(dst_idx..dst_idx + 4) {
+          pixel.copy_from_slice(color);
+        }
+      }
+    }
+  }
+}
+
+/// How scaled copies sample the source buffer
+struct Sampling<'a> {
+  scaler: &'a dyn Scaler,
+  /// Quantization used when filtering produces values between 8-bit levels
+  dither: DitherMode,
+  /// Working memory of the window being drawn
+  scratch: &'a mut FrameScratch,
+}
+
+/// A scaled copy of part of the source into part of a region
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScaledCopy {
+  /// Left edge of the sampled source rectangle
+  src_x: u32,
+  /// Top edge of the sampled source rectangle
+  src_y: u32,
+  /// Width of the sampled source rectangle
+  src_width: u32,
+  /// Height of the sampled source rectangle
+  src_height: u32,
+  /// Region-relative X where the scaled image starts
+  offset_x: u32,
+  /// Region-relative Y where the scaled image starts
+  offset_y: u32,
+  /// Width of the scaled image (may extend past the region)
+  scaled_width: u32,
+  /// Height of the scaled image (may extend past the region)
+  scaled_height: u32,
+}
+
+/// Scales buffer to fill the entire region, ignoring aspect ratio
+fn scale_buffer_stretch(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  sampling: Sampling,
+) {
+  let copy = ScaledCopy {
+    src_x: 0,
+    src_y: 0,
+    src_width: source.width,
+    src_height: source.height,
+    offset_x: 0,
+    offset_y: 0,
+    scaled_width: region.width,
+    scaled_height: region.height,
+  };
+  copy_scaled(frame, source, region, copy, sampling);
+}
+
+/// Copies the buffer without scaling with its top-left corner at `position`
+/// in the region, cropping what doesn't fit
+fn copy_buffer_positioned(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  position: (i64, i64),
+) {
+  let crop_x = (-position.0).max(0) as u32;
+  let crop_y = (-position.1).max(0) as u32;
+  let start_x = position.0.max(0) as u32;
+  let start_y = position.1.max(0) as u32;
+  let copy_width = source
+    .width
+    .saturating_sub(crop_x)
+    .min(region.width.saturating_sub(start_x));
+  let copy_height = source
+    .height
+    .saturating_sub(crop_y)
+    .min(region.height.saturating_sub(start_y));
+
+  for y in 0..copy_height {
+    for x in 0..copy_width {
+      let src_x = crop_x + x;
+      let src_y = crop_y + y;
+      let dst_x = start_x + x;
+      let dst_y = start_y + y;
+      if !region.in_clip(dst_x, dst_y) {
+        continue;
+      }
+
+      let dst_idx = region.index(dst_x, dst_y);
+
+      if let Some(pixel) = source.pixel(src_x, src_y) {
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&pixel);
+        }
+      }
+    }
+  }
+}
+
+/// Repeats the buffer across the region with the first tile at `offset`
+///
+/// Each buffer pixel covers `scale_factor` region pixels in both directions.
+fn copy_buffer_tile(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  offset: (i32, i32),
+  scale_factor: f64,
+) {
+  if source.width == 0 || source.height == 0 {
+    return;
+  }
+  let tile_coord = |position: u32, offset: i32, size: u32| {
+    let logical = ((position as f64 - offset as f64) / scale_factor).floor() as i64;
+    wrap_coord(logical, size, WrapMode::Wrap)
+  };
+  // Every row repeats the same column pattern
+  let columns: Vec<u32> = (0..region.width)
+    .map(|x| tile_coord(x, offset.0, source.width))
+    .collect();
+
+  for y in 0..region.height {
+    let src_y = tile_coord(y, offset.1, source.height);
+    for (x, &src_x) in (0..region.width).zip(&columns) {
+      if !region.in_clip(x, y) {
+        continue;
+      }
+      let dst_idx = region.index(x, y);
+      if let Some(pixel) = source.pixel(src_x, src_y) {
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&pixel);
+        }
+      }
+    }
+  }
+}
+
+/// Size and region position of a scaled image that may be cropped
+struct ScaledPlacement {
+  size: (u32, u32),
+  /// Position of the image's top-left corner (negative when cropped)
+  position: (i64, i64),
+}
+
+/// Draws the buffer scaled to `placement.size` at `placement.position`,
+/// cropping what doesn't fit the region
+fn scale_buffer_positioned(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  placement: ScaledPlacement,
+  sampling: Sampling,
+) {
+  let (scaled_width, scaled_height) = placement.size;
+  let (position_x, position_y) = placement.position;
+  let offset_x = position_x.max(0) as u32;
+  let offset_y = position_y.max(0) as u32;
+  let cropped_x = (-position_x).max(0) as u32;
+  let cropped_y = (-position_y).max(0) as u32;
+  let visible_width = scaled_width
+    .saturating_sub(cropped_x)
+    .min(region.width.saturating_sub(offset_x));
+  let visible_height = scaled_height
+    .saturating_sub(cropped_y)
+    .min(region.height.saturating_sub(offset_y));
+  // Source pixels per scaled pixel
+  let ratio_x = source.width as f64 / scaled_width as f64;
+  let ratio_y = source.height as f64 / scaled_height as f64;
+  let crop_x = cropped_x as f64 * ratio_x;
+  let crop_y = cropped_y as f64 * ratio_y;
+
+  let copy = ScaledCopy {
+    src_x: crop_x as u32,
+    src_y: crop_y as u32,
+    src_width: ((visible_width as f64 * ratio_x).round() as u32).clamp(1, source.width),
+    src_height: ((visible_height as f64 * ratio_y).round() as u32).clamp(1, source.height),
+    offset_x,
+    offset_y,
+    scaled_width: visible_width,
+    scaled_height: visible_height,
+  };
+  copy_scaled(frame, source, region, copy, sampling);
+}
+
+/// Scales buffer to fill the region, maintaining aspect ratio by cropping
+///
+/// With `balanced`, the crop is rounded from its exact position by
+/// [`balanced_span`] instead of truncated.
+fn scale_buffer_fill(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  balanced: bool,
+  sampling: Sampling,
+) {
+  let buffer_width = source.width;
+  let buffer_height = source.height;
+  let buffer_aspect = buffer_width as f32 / buffer_height as f32;
+  let window_aspect = region.width as f32 / region.height as f32;
+  let crop_span = |outer: u32, length: f32| {
+    if balanced {
+      balanced_span(outer, length as f64)
+    } else {
+      let length = length as u32;
+      ((outer - length) / 2, length)
+    }
+  };
+
+  let (crop_x, crop_y, crop_width, crop_height) = if buffer_aspect > window_aspect {
+    let (crop_x, new_width) = crop_span(buffer_width, buffer_height as f32 * window_aspect);
+    (crop_x, 0, new_width, buffer_height)
+  } else {
+    let (crop_y, new_height) = crop_span(buffer_height, buffer_width as f32 / window_aspect);
+    (0, crop_y, buffer_width, new_height)
+  };
+
+  let copy = ScaledCopy {
+    src_x: crop_x,
+    src_y: crop_y,
+    src_width: crop_width,
+    src_height: crop_height,
+    offset_x: 0,
+    offset_y: 0,
+    scaled_width: region.width,
+    scaled_height: region.height,
+  };
+  copy_scaled(frame, source, region, copy, sampling);
+}
+
+/// Parameters for scaling buffer to fit a region
+struct ScaleBufferFitParams<'a> {
+  region: FrameRegion,
+  offset_x: u32,
+  offset_y: u32,
+  scaled_width: u32,
+  scaled_height: u32,
+  sampling: Sampling<'a>,
+}
+
+/// Scales buffer to fit the region, maintaining aspect ratio with letterboxing
+fn scale_buffer_fit(frame: &mut [u8], source: &SourceImage, params: ScaleBufferFitParams) {
+  let ScaleBufferFitParams {
+    region,
+    offset_x,
+    offset_y,
+    scaled_width,
+    scaled_height,
+    sampling,
+  } = params;
+
+  // Frame is already cleared with background color
+  let copy = ScaledCopy {
+    src_x: 0,
+    src_y: 0,
+    src_width: source.width,
+    src_height: source.height,
+    offset_x,
+    offset_y,
+    scaled_width,
+    scaled_height,
+  };
+  copy_scaled(frame, source, region, copy, sampling);
+}
+
+/// Scales a source rectangle into a region using the sampling's scaler
+///
+/// Unscaled copies move whole rows when the source allows it, and otherwise use
+/// nearest sampling, which is exact and cheapest.
+fn copy_scaled(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  copy: ScaledCopy,
+  sampling: Sampling,
+) {
+  let unscaled = copy.scaled_width == copy.src_width && copy.scaled_height == copy.src_height;
+  if unscaled && row_copy::copy_unscaled_rows(frame, source, region, copy) {
+    return;
+  }
+  let scaler = if unscaled {
+    &scaler::NearestScaler
+  } else {
+    sampling.scaler
+  };
+  scaler.scale(
+    frame,
+    source,
+    region,
+    copy,
+    sampling.dither,
+    sampling.scratch,
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use super::scaler::{AreaAverageScaler, BilinearScaler, LanczosScaler, NearestScaler};
+  use super::*;
+
+  /// 1x2 buffer: red on row 0, blue on row 1
+  const TWO_ROWS: [u8; 8] = [255, 0, 0, 255, 0, 0, 255, 255];
+
+  fn source(data: &[u8], width: u32, height: u32, origin: Origin) -> SourceImage<'_> {
+    SourceImage {
+      data,
+      width,
+      height,
+      flip_x: false,
+      flip_y: origin == Origin::BottomLeft,
+      rotation: Rotation::None,
+      tone: None,
+      tint: None,
+      stride: width * 4,
+      palette: None,
+      wrap: WrapMode::Clamp,
+      format: PixelFormat::Rgba,
+    }
+  }
+
+  #[test]
+  fn test_source_pixel_top_left() {
+    let image = source(&TWO_ROWS, 1, 2, Origin::TopLeft);
+    assert_eq!(image.pixel(0, 0), Some([255, 0, 0, 255]));
+    assert_eq!(image.pixel(0, 1), Some([0, 0, 255, 255]));
+  }
+
+  #[test]
+  fn test_source_pixel_bottom_left_flips_rows() {
+    let image = source(&TWO_ROWS, 1, 2, Origin::BottomLeft);
+    assert_eq!(image.pixel(0, 0), Some([0, 0, 255, 255]));
+    assert_eq!(image.pixel(0, 1), Some([255, 0, 0, 255]));
+  }
+
+  #[test]
+  fn test_source_pixel_converts_format() {
+    let bgr = [0, 0, 255, 255, 0, 0];
+    let image = SourceImage {
+      format: PixelFormat::Bgr,
+      stride: 3,
+      ..source(&bgr, 1, 2, Origin::TopLeft)
+    };
+    assert_eq!(image.pixel(0, 0), Some([255, 0, 0, 255]));
+    assert_eq!(image.pixel(0, 1), Some([0, 0, 255, 255]));
+  }
+
+  #[test]
+  fn test_source_pixel_out_of_bounds() {
+    let image = source(&TWO_ROWS, 1, 2, Origin::BottomLeft);
+    assert_eq!(image.pixel(1, 0), None);
+    assert_eq!(image.pixel(0, 2), None);
+    assert_eq!(source(&[], 0, 0, Origin::BottomLeft).pixel(0, 0), None);
+  }
+
+  #[test]
+  fn test_stretch_bottom_left_renders_upright() {
+    let image = source(&TWO_ROWS, 1, 2, Origin::BottomLeft);
+    let mut frame = vec![0u8; 2 * 4];
+    scale_buffer_stretch(
+      &mut frame,
+      &image,
+      FrameRegion::full(FrameSize::packed(1, 2)),
+      Sampling {
+        scaler: &NearestScaler,
+        dither: DitherMode::None,
+        scratch: &mut FrameScratch::default(),
+      },
+    );
+    assert_eq!(&frame[0..4], &TWO_ROWS[4..8]);
+    assert_eq!(&frame[4..8], &TWO_ROWS[0..4]);
+  }
+
+  #[test]
+  fn test_sample_bilinear_blends_and_clamps() {
+    let data = [0, 0, 0, 255, 200, 100, 50, 255];
+    let image = source(&data, 2, 1, Origin::TopLeft);
+    assert_eq!(image.sample_bilinear(0.5, 0.0), [100.0, 50.0, 25.0, 255.0]);
+    assert_eq!(image.sample_bilinear(5.0, 3.0), [200.0, 100.0, 50.0, 255.0]);
+    assert_eq!(image.sample_bilinear(-1.0, -1.0), [0.0, 0.0, 0.0, 255.0]);
+  }
+
+  #[test]
+  fn test_apply_alpha_mode() {
+    let pixels = [200, 100, 50, 128];
+    let mut frame = pixels;
+    apply_alpha_mode(&mut frame, AlphaMode::Straight);
+    assert_eq!(frame, pixels);
+    apply_alpha_mode(&mut frame, AlphaMode::Premultiplied);
+    assert_eq!(frame, [100, 50, 25, 128]);
+    apply_alpha_mode(&mut frame, AlphaMode::Opaque);
+    assert_eq!(frame, [100, 50, 25, 255]);
+  }
+
+  #[test]
+  fn test_wrap_coord_modes() {
+    let resolve = |mode| -> Vec<u32> { (-3..6).map(|c| wrap_coord(c, 3, mode)).collect() };
+    assert_eq!(resolve(WrapMode::Clamp), vec![0, 0, 0, 0, 1, 2, 2, 2, 2]);
+    assert_eq!(resolve(WrapMode::Wrap), vec![0, 1, 2, 0, 1, 2, 0, 1, 2]);
+    assert_eq!(resolve(WrapMode::Mirror), vec![2, 1, 0, 0, 1, 2, 2, 1, 0]);
+    assert_eq!(wrap_coord(5, 0, WrapMode::Wrap), 0);
+  }
+
+  #[test]
+  fn test_sample_bilinear_wrap_blends_opposite_edges() {
+    let data = [0, 0, 0, 255, 200, 100, 50, 255];
+    let image = SourceImage {
+      wrap: WrapMode::Wrap,
+      ..source(&data, 2, 1, Origin::TopLeft)
+    };
+    // Halfway between the last column and the first one
+    assert_eq!(image.sample_bilinear(1.5, 0.0), [100.0, 50.0, 25.0, 255.0]);
+    let mirrored = SourceImage {
+      wrap: WrapMode::Mirror,
+      ..source(&data, 2, 1, Origin::TopLeft)
+    };
+    assert_eq!(
+      mirrored.sample_bilinear(1.5, 0.0),
+      [200.0, 100.0, 50.0, 255.0]
+    );
+  }
+
+  #[test]
+  fn test_stretch_bilinear_produces_gradient() {
+    let data = [0, 0, 0, 255, 255, 255, 255, 255];
+    let image = source(&data, 2, 1, Origin::TopLeft);
+    let mut frame = vec![0u8; 4 * 4];
+    scale_buffer_stretch(
+      &mut frame,
+      &image,
+      FrameRegion::full(FrameSize::packed(4, 1)),
+      Sampling {
+        scaler: &BilinearScaler,
+        dither: DitherMode::None,
+        scratch: &mut FrameScratch::default(),
+      },
+    );
+    let reds: Vec<u8> = frame.chunks_exact(4).map(|p| p[0]).collect();
+    assert_eq!(reds, vec![0, 64, 191, 255]);
+  }
+
+  #[test]
+  fn test_frame_size_detects_row_padding() {
+    // 3 visible pixels per row, rows aligned to 4 pixels
+    assert_eq!(
+      FrameSize::for_frame(4 * 2 * 4, 3, 2),
+      FrameSize {
+        width: 3,
+        height: 2,
+        stride: 4
+      }
+    );
+    assert_eq!(
+      FrameSize::for_frame(3 * 2 * 4, 3, 2),
+      FrameSize::packed(3, 2)
+    );
+    assert_eq!(FrameSize::for_frame(0, 0, 0), FrameSize::packed(0, 0));
+  }
+
+  #[test]
+  fn test_stretch_into_padded_frame() {
+    let image = source(&TWO_ROWS, 1, 2, Origin::TopLeft);
+    // 1x2 visible pixels with one padding pixel per row
+    let mut frame = vec![7u8; 2 * 2 * 4];
+    let size = FrameSize::for_frame(frame.len(), 1, 2);
+    scale_buffer_stretch(
+      &mut frame,
+      &image,
+      FrameRegion::full(size),
+      Sampling {
+        scaler: &NearestScaler,
+        dither: DitherMode::None,
+        scratch: &mut FrameScratch::default(),
+      },
+    );
+    assert_eq!(&frame[0..4], &TWO_ROWS[0..4]);
+    assert_eq!(&frame[4..8], &[7; 4]);
+    assert_eq!(&frame[8..12], &TWO_ROWS[4..8]);
+    assert_eq!(&frame[12..16], &[7; 4]);
+  }
+
+  #[test]
+  fn test_unscaled_copy_skips_filtering() {
+    let data = [0, 0, 0, 255, 255, 255, 255, 255];
+    let image = source(&data, 2, 1, Origin::TopLeft);
+    let mut frame = vec![0u8; 2 * 4];
+    scale_buffer_stretch(
+      &mut frame,
+      &image,
+      FrameRegion::full(FrameSize::packed(2, 1)),
+      Sampling {
+        scaler: &BilinearScaler,
+        dither: DitherMode::None,
+        scratch: &mut FrameScratch::default(),
+      },
+    );
+    assert_eq!(frame, data);
+  }
+
+  #[test]
+  fn test_fit_filters_fill_only_the_scaled_area() {
+    // A uniform 4x3 buffer fits a 16x9 window as 12x9, pillarboxed by 2 columns
+    let data = [128u8, 64, 32, 255].repeat(4 * 3);
+    let image = source(&data, 4, 3, Origin::TopLeft);
+    let (offset_x, offset_y, scaled_width, scaled_height) =
+      calculate_scaled_dimensions(4, 3, 16, 9, ScaleMode::Fit);
+    assert_eq!(
+      (offset_x, offset_y, scaled_width, scaled_height),
+      (2, 0, 12, 9)
+    );
+    let scalers: [&dyn Scaler; 2] = [&NearestScaler, &BilinearScaler];
+    let mut scratch = FrameScratch::default();
+    for scaler in scalers {
+      let mut frame = vec![0u8; 16 * 9 * 4];
+      scale_buffer_fit(
+        &mut frame,
+        &image,
+        ScaleBufferFitParams {
+          region: FrameRegion::full(FrameSize::packed(16, 9)),
+          offset_x,
+          offset_y,
+          scaled_width,
+          scaled_height,
+          sampling: Sampling {
+            scaler,
+            dither: DitherMode::None,
+            scratch: &mut scratch,
+          },
+        },
+      );
+      for (i, pixel) in frame.chunks(4).enumerate() {
+        let x = i % 16;
+        let expected: &[u8] = if (2..14).contains(&x) {
+          &[128, 64, 32, 255]
+        } else {
+          &[0, 0, 0, 0]
+        };
+        assert_eq!(pixel, expected, "{} at pixel {}", scaler.name(), i);
+      }
+    }
+  }
+
+  #[test]
+  fn test_downscale_filters_keep_uniform_color() {
+    let data = [200u8, 100, 50, 255].repeat(32 * 16);
+    let image = source(&data, 32, 16, Origin::TopLeft);
+    let region = FrameRegion::full(FrameSize::packed(8, 4));
+    let scalers: [&dyn Scaler; 4] = [
+      &NearestScaler,
+      &BilinearScaler,
+      &AreaAverageScaler,
+      &LanczosScaler,
+    ];
+    let mut scratch = FrameScratch::default();
+    for scaler in scalers {
+      let mut frame = vec![0u8; 8 * 4 * 4];
+      scale_buffer_stretch(
+        &mut frame,
+        &image,
+        region,
+        Sampling {
+          scaler,
+          dither: DitherMode::None,
+          scratch: &mut scratch,
+        },
+      );
+      assert!(
+        frame.chunks(4).all(|pixel| pixel == [200, 100, 50, 255]),
+        "{} changed a uniform color",
+        scaler.name()
+      );
+    }
+  }
+
+  #[test]
+  fn test_scratch_is_reused_across_same_size_frames() {
+    let data = vec![128u8; 160 * 120 * 4];
+    let image = source(&data, 160, 120, Origin::TopLeft);
+    let mut frame = vec![0u8; 320 * 240 * 4];
+    let region = FrameRegion::full(FrameSize::packed(320, 240));
+    let mut scratch = FrameScratch::for_width(320);
+    let mut draw = |scratch: &mut FrameScratch| {
+      scale_buffer_stretch(
+        &mut frame,
+        &image,
+        region,
+        Sampling {
+          scaler: &BilinearScaler,
+          dither: DitherMode::FloydSteinberg,
+          scratch,
+        },
+      );
+    };
+
+    draw(&mut scratch);
+    let taps = (scratch.column_taps.as_ptr(), scratch.column_taps.capacity());
+    for _ in 0..3 {
+      draw(&mut scratch);
+      assert_eq!(
+        (scratch.column_taps.as_ptr(), scratch.column_taps.capacity()),
+        taps
+      );
+    }
+  }
+
+  #[test]
+  fn test_render_to_vec_stretches_into_output() {
+    let mut renderer = PixelRenderer::new(1, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    let out = renderer
+      .render_to_vec(TWO_ROWS.to_vec().into(), 2, 4)
+      .unwrap();
+    assert_eq!(out.len(), 2 * 4 * 4);
+    assert_eq!(&out[..4], &[255, 0, 0, 255]);
+    assert_eq!(&out[out.len() - 4..], &[0, 0, 255, 255]);
+  }
+
+  #[test]
+  fn test_render_to_vec_letterboxes_with_background() {
+    let mut renderer = PixelRenderer::new(1, 2).unwrap();
+    renderer.set_background_color(1, 2, 3, 255);
+    let out = renderer
+      .render_to_vec(TWO_ROWS.to_vec().into(), 3, 2)
+      .unwrap();
+    assert_eq!(&out[..4], &[1, 2, 3, 255]);
+    assert_eq!(&out[4..8], &[255, 0, 0, 255]);
+    assert!(renderer.render_to_vec(vec![0u8; 4].into(), 3, 2).is_err());
+  }
+
+  #[test]
+  fn test_packed_background_color_takes_precedence() {
+    let renderer = PixelRenderer::with_options(RenderOptions {
+      background_color: Some(vec![1, 2, 3]),
+      background_color_rgba: Some(0x11223344),
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(renderer.bg_color, [0x11, 0x22, 0x33, 0x44]);
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer.set_background_color_packed(0xff0000ff);
+    assert_eq!(renderer.bg_color, [255, 0, 0, 255]);
+  }
+
+  #[test]
+  fn test_clip_rect_leaves_outside_pixels_untouched() {
+    let renderer = PixelRenderer::with_options(RenderOptions {
+      buffer_width: 1,
+      buffer_height: 2,
+      background_color: Some(vec![1, 2, 3]),
+      clip_rect: Some(Rect {
+        x: 1,
+        y: 0,
+        width: 2,
+        height: 2,
+      }),
+      ..Default::default()
+    })
+    .unwrap();
+    let out = renderer
+      .render_to_vec(TWO_ROWS.to_vec().into(), 4, 2)
+      .unwrap();
+    // Columns 0 and 3 are outside the clip; centering the 1x2 image rounds down to column 1
+    let row: Vec<u8> = out[..16].to_vec();
+    assert_eq!(row, [0, 0, 0, 0, 255, 0, 0, 255, 1, 2, 3, 255, 0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_draw_dirty_only_touches_mapped_pixels() {
+    let mut renderer = PixelRenderer::new(4, 4).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    let buffer = [255u8; 4 * 4 * 4];
+    let mut frame = vec![0u8; 8 * 8 * 4];
+    let dirty = Rect {
+      x: 0,
+      y: 0,
+      width: 1,
+      height: 1,
+    };
+    renderer.draw_dirty(
+      &mut frame,
+      &buffer,
+      FrameSize::packed(8, 8),
+      &dirty,
+      &mut FrameScratch::default(),
+    );
+    let touched = |x: usize, y: usize| frame[(y * 8 + x) * 4] == 255;
+    // The dirty pixel covers 2x2 window pixels, plus a pixel of margin
+    assert!(touched(0, 0) && touched(2, 2));
+    assert!(!touched(3, 0) && !touched(0, 3) && !touched(7, 7));
+  }
+
+  #[test]
+  fn test_unpad_frame_drops_row_padding() {
+    let frame = [1, 1, 1, 1, 9, 9, 9, 9, 2, 2, 2, 2, 9, 9, 9, 9];
+    let size = FrameSize {
+      width: 1,
+      height: 2,
+      stride: 2,
+    };
+    assert_eq!(unpad_frame(&frame, size), vec![1, 1, 1, 1, 2, 2, 2, 2]);
+    assert_eq!(unpad_frame(&frame, FrameSize::packed(2, 2)), frame.to_vec());
+  }
+
+  #[test]
+  fn test_scale_factor_enlarges_unscaled_buffer() {
+    let mut renderer = PixelRenderer::new(1, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::None);
+    renderer.set_scale_factor(2.0).unwrap();
+    let out = renderer
+      .render_to_vec(TWO_ROWS.to_vec().into(), 4, 4)
+      .unwrap();
+    let pixel = |x: usize, y: usize| &out[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    // 1x2 logical pixels become a centered 2x4 block
+    assert_eq!(pixel(0, 0), &[0, 0, 0, 255]);
+    assert_eq!(pixel(1, 0), &[255, 0, 0, 255]);
+    assert_eq!(pixel(2, 1), &[255, 0, 0, 255]);
+    assert_eq!(pixel(1, 2), &[0, 0, 255, 255]);
+    assert_eq!(pixel(3, 3), &[0, 0, 0, 255]);
+    assert!(renderer.set_scale_factor(0.0).is_err());
+  }
+
+  #[test]
+  fn test_scale_factor_crops_centered() {
+    let image: Vec<u8> = (0..4u8).flat_map(|i| [i, i, i, 255]).collect();
+    let mut renderer = PixelRenderer::new(4, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::None);
+    renderer.set_scale_factor(2.0).unwrap();
+    // 8 logical pixels wide, the middle 4 are visible
+    let out = renderer.render_to_vec(image.into(), 4, 2).unwrap();
+    let row: Vec<u8> = out[..16].chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(row, vec![1, 1, 2, 2]);
+  }
+
+  #[test]
+  fn test_frame_history_ring_buffer() {
+    let mut history = FrameHistory::default();
+    assert_eq!(history.last(), None);
+    assert_eq!(history.average(), None);
+    for us in 0..FRAME_HISTORY_LEN as u64 + 10 {
+      history.push(Duration::from_micros(us));
+    }
+    // Only the newest FRAME_HISTORY_LEN durations (10..42) are kept
+    assert_eq!(history.last(), Some(FRAME_HISTORY_LEN as u32 + 9));
+    assert_eq!(history.average(), Some(25.5));
+    assert_eq!(history.total, FRAME_HISTORY_LEN as u32 + 10);
+  }
+
+  #[test]
+  fn test_present_history_stats() {
+    let mut history = PresentHistory::default();
+    history.frame_times.push(Duration::from_micros(1000));
+    let mut stats = RenderStats::default();
+    history.write_to(&mut stats);
+    assert_eq!(stats.fps, None);
+
+    history.frame_times.push(Duration::from_micros(3000));
+    history.intervals.push(Duration::from_millis(20));
+    history.write_to(&mut stats);
+    assert_eq!(stats.last_frame_us, Some(3000));
+    assert_eq!(stats.avg_frame_us, Some(2000));
+    assert_eq!(stats.fps, Some(50.0));
+    assert_eq!(stats.frames_presented, 2);
+  }
+
+  #[test]
+  fn test_integer_tile_maps_each_pixel_to_one_source_pixel() {
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::IntegerTile);
+    renderer.set_filter_mode(FilterMode::Bilinear);
+    renderer.set_tile_offset(1, 0);
+    let buffer: Vec<u8> = [10u8, 200].iter().flat_map(|&v| [v, v, v, 255]).collect();
+    let out = renderer.render_to_vec(buffer.into(), 5, 3).unwrap();
+    // Scale 2 (5x3 fits 2x1 twice), shifted right by one window pixel
+    let row: Vec<u8> = out[..5 * 4].chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(row, vec![200, 10, 10, 200, 200]);
+    assert!(out.chunks(4).all(|pixel| pixel[0] == 10 || pixel[0] == 200));
+  }
+
+  #[test]
+  fn test_tile_repeats_from_offset() {
+    let image: Vec<u8> = (0..2u8).flat_map(|i| [i, i, i, 255]).collect();
+    let mut frame = vec![0u8; 5 * 4];
+    let region = FrameRegion::full(FrameSize::packed(5, 1));
+    copy_buffer_tile(
+      &mut frame,
+      &source(&image, 2, 1, Origin::TopLeft),
+      region,
+      (1, 0),
+      1.0,
+    );
+    let row: Vec<u8> = frame.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(row, vec![1, 0, 1, 0, 1]);
+
+    copy_buffer_tile(
+      &mut frame,
+      &source(&image, 2, 1, Origin::TopLeft),
+      region,
+      (-1, 0),
+      2.0,
+    );
+    let row: Vec<u8> = frame.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(row, vec![0, 1, 1, 0, 0]);
+  }
+
+  #[test]
+  fn test_parse_background_color() {
+    assert_eq!(
+      parse_background_color(&[255, 0, 0]).unwrap(),
+      [255, 0, 0, 255]
+    );
+    assert_eq!(parse_background_color(&[1, 2, 3, 4]).unwrap(), [1, 2, 3, 4]);
+    assert!(parse_background_color(&[1, 2]).is_err());
+    assert!(parse_background_color(&[1, 2, 3, 4, 5]).is_err());
+  }
+
+  #[test]
+  fn test_zero_buffer_dimensions_are_rejected() {
+    assert!(PixelRenderer::new(0, 2).is_err());
+    assert!(PixelRenderer::new(2, 0).is_err());
+    let options = RenderOptions {
+      buffer_width: 0,
+      ..Default::default()
+    };
+    assert!(PixelRenderer::with_options(options).is_err());
+  }
+
+  #[test]
+  fn test_with_options_rejects_bad_background_color() {
+    let options = |color: Vec<u8>| RenderOptions {
+      background_color: Some(color),
+      ..Default::default()
+    };
+    assert_eq!(
+      PixelRenderer::with_options(options(vec![255, 0, 0]))
+        .unwrap()
+        .bg_color,
+      [255, 0, 0, 255]
+    );
+    assert!(PixelRenderer::with_options(options(vec![255])).is_err());
+  }
+
+  #[test]
+  fn test_with_options_rejects_bad_padding() {
+    let options = |padding: Vec<u32>| RenderOptions {
+      padding: Some(padding),
+      ..Default::default()
+    };
+    assert_eq!(
+      PixelRenderer::with_options(options(vec![1, 2, 3, 4]))
+        .unwrap()
+        .padding,
+      [1, 2, 3, 4]
+    );
+    assert!(PixelRenderer::with_options(options(vec![1, 2])).is_err());
+    assert!(PixelRenderer::with_options(options(vec![1, 2, 3, 4, 5])).is_err());
+  }
+
+  #[test]
+  fn test_with_options_rejects_bad_auto_aspect_tolerance() {
+    let options = |tolerance: f64| RenderOptions {
+      auto_aspect_tolerance: Some(tolerance),
+      ..Default::default()
+    };
+    assert_eq!(
+      PixelRenderer::with_options(options(0.0))
+        .unwrap()
+        .auto_tolerance,
+      0.0
+    );
+    for invalid in [-0.1, f64::NAN, f64::INFINITY] {
+      assert!(PixelRenderer::with_options(options(invalid)).is_err());
+    }
+  }
+
+  #[test]
+  fn test_with_options_rejects_bad_scale_factor() {
+    let options = |scale_factor: f64| RenderOptions {
+      scale_factor: Some(scale_factor),
+      ..Default::default()
+    };
+    assert_eq!(
+      PixelRenderer::with_options(options(2.0))
+        .unwrap()
+        .scale_factor,
+      2.0
+    );
+    for invalid in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+      assert!(PixelRenderer::with_options(options(invalid)).is_err());
+    }
+  }
+
+  #[test]
+  fn test_with_options_rejects_bad_tile_offset() {
+    let options = |offset: Vec<i32>| RenderOptions {
+      tile_offset: Some(offset),
+      ..Default::default()
+    };
+    assert_eq!(
+      PixelRenderer::with_options(options(vec![-3, 5]))
+        .unwrap()
+        .tile_offset,
+      (-3, 5)
+    );
+    assert!(PixelRenderer::with_options(options(vec![1])).is_err());
+    assert!(PixelRenderer::with_options(options(vec![1, 2, 3])).is_err());
+  }
+
+  /// 2x2 buffer with a distinct value in each corner
+  const CORNERS: [u8; 16] = [1, 1, 1, 255, 2, 2, 2, 255, 3, 3, 3, 255, 4, 4, 4, 255];
+
+  fn corner_values(out: &[u8], width: usize, height: usize) -> [u8; 4] {
+    let value = |x: usize, y: usize| out[(y * width + x) * 4];
+    [
+      value(0, 0),
+      value(width - 1, 0),
+      value(0, height - 1),
+      value(width - 1, height - 1),
+    ]
+  }
+
+  #[test]
+  fn test_flip_horizontal_swaps_columns() {
+    let mut renderer = PixelRenderer::new(2, 2).unwrap();
+    renderer.set_flip_horizontal(true);
+    let out = renderer
+      .render_to_vec(CORNERS.to_vec().into(), 4, 4)
+      .unwrap();
+    assert_eq!(corner_values(&out, 4, 4), [2, 1, 4, 3]);
+  }
+
+  #[test]
+  fn test_flip_vertical_combines_with_origin() {
+    let mut renderer = PixelRenderer::new(2, 2).unwrap();
+    renderer.set_flip_vertical(true);
+    let out = renderer
+      .render_to_vec(CORNERS.to_vec().into(), 2, 2)
+      .unwrap();
+    assert_eq!(corner_values(&out, 2, 2), [3, 4, 1, 2]);
+
+    renderer.set_origin(Origin::BottomLeft);
+    let out = renderer
+      .render_to_vec(CORNERS.to_vec().into(), 2, 2)
+      .unwrap();
+    assert_eq!(corner_values(&out, 2, 2), [1, 2, 3, 4]);
+  }
+
+  #[test]
+  fn test_flipped_fit_letterboxes_the_same() {
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_flip_horizontal(true);
+    renderer.set_flip_vertical(true);
+    let out = renderer
+      .render_to_vec(CORNERS[..8].to_vec().into(), 4, 4)
+      .unwrap();
+    // Rows 1 and 2 hold the mirrored image; rows 0 and 3 are letterbox
+    let row = |y: usize| -> Vec<u8> { out[y * 16..(y + 1) * 16].chunks(4).map(|p| p[0]).collect() };
+    assert_eq!(row(0), vec![0, 0, 0, 0]);
+    assert_eq!(row(1), vec![2, 2, 1, 1]);
+    assert_eq!(row(2), vec![2, 2, 1, 1]);
+    assert_eq!(row(3), vec![0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_unscaled_row_copy_matches_nearest() {
+    // 3x2 BGRA, bottom-left origin, with a clip that cuts off the first column
+    let data: Vec<u8> = (0..24).collect();
+    let image = SourceImage {
+      format: PixelFormat::Bgra,
+      ..source(&data, 3, 2, Origin::BottomLeft)
+    };
+    let region = FrameRegion {
+      clip: Some(Rect {
+        x: 2,
+        y: 0,
+        width: 4,
+        height: 4,
+      }),
+      ..FrameRegion::full(FrameSize::packed(4, 3))
+    };
+    let copy = ScaledCopy {
+      src_x: 0,
+      src_y: 0,
+      src_width: 3,
+      src_height: 2,
+      offset_x: 1,
+      offset_y: 1,
+      scaled_width: 3,
+      scaled_height: 2,
+    };
+    let mut fast = vec![0u8; 4 * 3 * 4];
+    assert!(row_copy::copy_unscaled_rows(
+      &mut fast, &image, region, copy
+    ));
+    let mut reference = vec![0u8; 4 * 3 * 4];
+    NearestScaler.scale(
+      &mut reference,
+      &image,
+      region,
+      copy,
+      DitherMode::None,
+      &mut FrameScratch::default(),
+    );
+    assert_eq!(fast, reference);
+
+    let mirrored = SourceImage {
+      flip_x: true,
+      ..source(&data, 3, 2, Origin::TopLeft)
+    };
+    assert!(!row_copy::copy_unscaled_rows(
+      &mut fast, &mirrored, region, copy
+    ));
+  }
+
+  #[test]
+  fn test_checkerboard_fills_letterbox() {
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer
+      .set_letterbox_checkerboard(Some(Checkerboard {
+        color_a: vec![10, 10, 10],
+        color_b: vec![20, 20, 20],
+        cell_size: 1,
+      }))
+      .unwrap();
+    renderer.set_scale_mode(ScaleMode::None);
+    // The unscaled 1x1 image is surrounded by the pattern
+    let out = renderer
+      .render_to_vec(vec![255, 0, 0, 255].into(), 3, 3)
+      .unwrap();
+    let red: Vec<u8> = out.chunks(4).map(|pixel| pixel[0]).collect();
+    #[rustfmt::skip]
+    assert_eq!(red, vec![
+      10, 20, 10,
+      20, 255, 20,
+      10, 20, 10,
+    ]);
+
+    let invalid = Checkerboard {
+      color_a: vec![0, 0, 0],
+      color_b: vec![0, 0, 0],
+      cell_size: 0,
+    };
+    assert!(renderer.set_letterbox_checkerboard(Some(invalid)).is_err());
+  }
+
+  #[test]
+  fn test_source_rect_samples_only_the_rectangle() {
+    #[rustfmt::skip]
+    let buffer: Vec<u8> = [
+      1, 2, 3, 4,
+      5, 6, 7, 8,
+    ].iter().flat_map(|&v| [v, v, v, 255]).collect();
+    let mut renderer = PixelRenderer::new(4, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    renderer
+      .set_source_rect(Some(Rect {
+        x: 1,
+        y: 1,
+        width: 2,
+        height: 1,
+      }))
+      .unwrap();
+    // The 2x1 rectangle is stretched over a 4x1 window
+    let out = renderer.render_to_vec(buffer.into(), 4, 1).unwrap();
+    let red: Vec<u8> = out.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(red, vec![6, 6, 7, 7]);
+
+    let outside = Rect {
+      x: 3,
+      y: 0,
+      width: 2,
+      height: 1,
+    };
+    assert!(renderer.set_source_rect(Some(outside)).is_err());
+    assert!(renderer
+      .set_source_rect(Some(Rect {
+        width: 0,
+        ..outside
+      }))
+      .is_err());
+  }
+
+  #[test]
+  fn test_resize_settles_after_stable_debounce() {
+    let debounce = Duration::from_millis(50);
+    let start = Instant::now();
+    let mut pending = None;
+    assert!(resize_settled(&mut pending, 10, 10, start, Duration::ZERO));
+    assert!(!resize_settled(&mut pending, 10, 10, start, debounce));
+    // A new size during the wait restarts it
+    let later = start + Duration::from_millis(40);
+    assert!(!resize_settled(&mut pending, 12, 10, later, debounce));
+    assert!(!resize_settled(
+      &mut pending,
+      12,
+      10,
+      start + Duration::from_millis(60),
+      debounce
+    ));
+    assert!(resize_settled(
+      &mut pending,
+      12,
+      10,
+      later + debounce,
+      debounce
+    ));
+  }
+
+  #[test]
+  fn test_native_argb_words_render_as_rgba() {
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_pixel_format(PixelFormat::NativeArgb);
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    let buffer: Vec<u8> = [0xff102030u32, 0x80405060]
+      .iter()
+      .flat_map(|word| word.to_ne_bytes())
+      .collect();
+    // 1:1 takes the row copy path, 2:1 samples each pixel
+    let out = renderer.render_to_vec(buffer.clone().into(), 2, 1).unwrap();
+    assert_eq!(&out[..], &[0x10, 0x20, 0x30, 0xff, 0x40, 0x50, 0x60, 0x80]);
+    let out = renderer.render_to_vec(buffer.into(), 4, 1).unwrap();
+    assert_eq!(&out[..8], &[0x10, 0x20, 0x30, 0xff, 0x10, 0x20, 0x30, 0xff]);
+  }
+
+  #[test]
+  fn test_gray8_buffer_is_one_byte_per_pixel() {
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_pixel_format(PixelFormat::Gray8);
+    assert!(renderer.validate_buffer(&[0; 8]).is_err());
+    let out = renderer.render_to_vec(vec![10, 200].into(), 2, 1).unwrap();
+    assert_eq!(out.to_vec(), vec![10, 10, 10, 255, 200, 200, 200, 255]);
+  }
+
+  #[test]
+  fn test_indexed8_looks_up_palette() {
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_pixel_format(PixelFormat::Indexed8);
+    assert!(renderer.validate_buffer(&[0, 1]).is_err());
+
+    let mut palette = vec![0u8; 1024];
+    palette[4..8].copy_from_slice(&[255, 128, 0, 255]);
+    renderer.set_palette(palette.into()).unwrap();
+    let out = renderer.render_to_vec(vec![1, 0].into(), 2, 1).unwrap();
+    assert_eq!(out.to_vec(), vec![255, 128, 0, 255, 0, 0, 0, 0]);
+    assert!(renderer.set_palette(vec![0u8; 768].into()).is_err());
+  }
+
+  #[test]
+  fn test_anchor_places_letterboxed_image() {
+    let buffer = || -> Buffer { vec![9, 9, 9, 255, 8, 8, 8, 255].into() };
+    let red = |out: Buffer| -> Vec<u8> { out.chunks(4).map(|pixel| pixel[0]).collect() };
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::Integer);
+    renderer.set_anchor(Anchor::TopLeft);
+    #[rustfmt::skip]
+    assert_eq!(red(renderer.render_to_vec(buffer(), 5, 3).unwrap()), vec![
+      9, 9, 8, 8, 0,
+      9, 9, 8, 8, 0,
+      0, 0, 0, 0, 0,
+    ]);
+    assert_eq!(
+      renderer.layout_in(5, 3),
+      RenderLayout {
+        offset_x: 0,
+        offset_y: 0,
+        scaled_width: 4,
+        scaled_height: 2,
+        letterbox_x: 1,
+        letterbox_y: 1,
+      }
+    );
+
+    renderer.set_scale_mode(ScaleMode::None);
+    renderer.set_anchor(Anchor::BottomRight);
+    #[rustfmt::skip]
+    assert_eq!(red(renderer.render_to_vec(buffer(), 3, 2).unwrap()), vec![
+      0, 0, 0,
+      0, 9, 8,
+    ]);
+    // Cropped from the side away from the anchor
+    assert_eq!(
+      red(renderer.render_to_vec(buffer(), 1, 1).unwrap()),
+      vec![8]
+    );
+  }
+
+  #[test]
+  fn test_integer_fit_matches_integer_when_buffer_fits() {
+    let render = |mode: ScaleMode, width: u32, height: u32| {
+      let mut renderer = PixelRenderer::new(width, height).unwrap();
+      renderer.set_scale_mode(mode);
+      let buffer = vec![255u8; width as usize * height as usize * 4];
+      let out = renderer.render_to_vec(buffer.into(), 350, 250).unwrap();
+      (out.to_vec(), renderer.layout_in(350, 250))
+    };
+
+    // 100x100 scales 2x to 200x200 in both modes, centered with bars
+    let (integer, integer_layout) = render(ScaleMode::Integer, 100, 100);
+    let (integer_fit, integer_fit_layout) = render(ScaleMode::IntegerFit, 100, 100);
+    assert_eq!(integer, integer_fit);
+    assert_eq!(
+      integer_fit_layout,
+      RenderLayout {
+        offset_x: 75,
+        offset_y: 25,
+        scaled_width: 200,
+        scaled_height: 200,
+        letterbox_x: 150,
+        letterbox_y: 50,
+      }
+    );
+    assert_eq!(integer_layout, integer_fit_layout);
+
+    // 400x100 doesn't fit at 1x: Integer crops it, IntegerFit halves it
+    let (integer, _) = render(ScaleMode::Integer, 400, 100);
+    let (integer_fit, layout) = render(ScaleMode::IntegerFit, 400, 100);
+    let left_middle = 125 * 350 * 4;
+    assert_eq!(
+      &integer[left_middle..left_middle + 4],
+      &[255, 255, 255, 255]
+    );
+    assert_eq!(&integer_fit[left_middle..left_middle + 4], &[0, 0, 0, 255]);
+    assert_eq!(
+      (
+        layout.offset_x,
+        layout.offset_y,
+        layout.scaled_width,
+        layout.scaled_height
+      ),
+      (75, 100, 200, 50)
+    );
+  }
+
+  #[test]
+  fn test_with_dimensions_keeps_settings() {
+    let mut renderer = PixelRenderer::new(4, 4).unwrap();
+    renderer.set_background_color(1, 2, 3, 255);
+    let resized = renderer.with_dimensions(1, 2).unwrap();
+    assert_eq!((resized.buffer_width, resized.buffer_height), (1, 2));
+    assert_eq!((renderer.buffer_width, renderer.buffer_height), (4, 4));
+    let out = resized
+      .render_to_vec(TWO_ROWS.to_vec().into(), 3, 2)
+      .unwrap();
+    assert_eq!(&out[..4], &[1, 2, 3, 255]);
+    assert!(renderer.with_dimensions(0, 2).is_err());
+  }
+
+  #[test]
+  fn test_max_aspect_skew_letterboxes_stretch() {
+    let mut renderer = PixelRenderer::new(1, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    renderer.set_max_aspect_skew(Some(0.5)).unwrap();
+    // sx = 4, sy = 1: width is capped at 1 * 1.5 = 1 pixel, centered in 4
+    let out = renderer
+      .render_to_vec(TWO_ROWS.to_vec().into(), 4, 2)
+      .unwrap();
+    assert_eq!(&out[..4], &[0, 0, 0, 255]);
+    assert_eq!(&out[4..8], &[255, 0, 0, 255]);
+    assert_eq!(
+      renderer.layout_in(4, 2),
+      RenderLayout {
+        offset_x: 1,
+        offset_y: 0,
+        scaled_width: 1,
+        scaled_height: 2,
+        letterbox_x: 3,
+        letterbox_y: 0,
+      }
+    );
+    assert!(renderer.set_max_aspect_skew(Some(-1.0)).is_err());
+  }
+
+  #[test]
+  fn test_frame_content_identifies_duplicates() {
+    let frame = [1u8, 2, 3, 4];
+    assert_eq!(
+      FrameContent::new(&frame, None),
+      FrameContent::new(&frame, None)
+    );
+    assert_ne!(
+      FrameContent::new(&frame, None),
+      FrameContent::new(&[1, 2, 3, 5], None)
+    );
+    // Caller IDs win over the content
+    assert_eq!(
+      FrameContent::new(&frame, Some(7)),
+      FrameContent::new(&[0; 4], Some(7))
+    );
+  }
+
+  #[test]
+  fn test_area_average_downscales_checkerboard_to_gray() {
+    let checkerboard: Vec<u8> = (0..64u32)
+      .flat_map(|i| {
+        let v = if (i % 8 + i / 8) % 2 == 0 { 255 } else { 0 };
+        [v, v, v, 255]
+      })
+      .collect();
+    let mut renderer = PixelRenderer::new(8, 8).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    // Nearest picks every fourth pixel, which are all the same color
+    let nearest = renderer
+      .render_to_vec(checkerboard.clone().into(), 2, 2)
+      .unwrap();
+    assert!(nearest.chunks(4).all(|p| p[0] == 255));
+    renderer.set_filter_mode(FilterMode::AreaAverage);
+    let averaged = renderer.render_to_vec(checkerboard.into(), 2, 2).unwrap();
+    for pixel in averaged.chunks(4) {
+      assert!((127..=128).contains(&pixel[0]), "{:?}", pixel);
+      assert_eq!(pixel[3], 255);
+    }
+  }
+
+  #[test]
+  fn test_lanczos_keeps_more_detail_than_bilinear() {
+    // A sine with an 8 pixel period, still resolvable after a 2x downscale
+    let width = 64;
+    let buffer: Vec<u8> = (0..width)
+      .flat_map(|x| {
+        let phase = 2.0 * std::f32::consts::PI * x as f32 / 8.0;
+        let v = (127.5 + 127.5 * phase.sin()) as u8;
+        [v, v, v, 255]
+      })
+      .collect();
+    // Variance around the mean: 127.5^2 / 2 for the source
+    let energy = |out: &[u8]| {
+      let values: Vec<f32> = out.chunks(4).map(|pixel| pixel[0] as f32).collect();
+      let mean = values.iter().sum::<f32>() / values.len() as f32;
+      values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32
+    };
+    let mut renderer = PixelRenderer::new(width, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    renderer.set_filter_mode(FilterMode::Bilinear);
+    let bilinear = renderer
+      .render_to_vec(buffer.clone().into(), width / 2, 1)
+      .unwrap();
+    renderer.set_filter_mode(FilterMode::Lanczos);
+    let lanczos = renderer.render_to_vec(buffer.into(), width / 2, 1).unwrap();
+    let source_energy = 127.5f32.powi(2) / 2.0;
+    assert!(energy(&lanczos) > energy(&bilinear) * 1.1);
+    assert!((energy(&lanczos) / source_energy - 1.0).abs() < 0.05);
+  }
+
+  #[test]
+  fn test_chainable_setters_apply_settings() {
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    let chained = renderer.with_scale_mode(ScaleMode::Fill).unwrap();
+    assert_eq!(chained.scale_mode, ScaleMode::Fill);
+    renderer.with_background_color(1, 2, 3, 4).unwrap();
+    assert_eq!(renderer.bg_color, [1, 2, 3, 4]);
+    assert!(renderer.with_scale_factor(0.0).is_err());
+  }
+
+  #[test]
+  fn test_cache_stats_sum_surface_sizes() {
+    let stats = CacheStats::for_surfaces([(800, 600), (2, 2)].into_iter());
+    assert_eq!(stats.window_count, 2);
+    assert_eq!(stats.approx_bytes, 1_920_016.0);
+    assert_eq!(
+      CacheStats::for_surfaces(std::iter::empty()).approx_bytes,
+      0.0
+    );
+  }
+
+  #[test]
+  fn test_fill_centers_the_crop_of_a_wide_buffer() {
+    // Each pixel stores its column
+    let buffer: Vec<u8> = (0..100)
+      .flat_map(|_| (0..200u32).flat_map(|x| [x as u8, 0, 0, 255]))
+      .collect();
+    let mut renderer = PixelRenderer::new(200, 100).unwrap();
+    renderer.set_scale_mode(ScaleMode::Fill);
+    let out = renderer.render_to_vec(buffer.into(), 100, 100).unwrap();
+    // Columns 50..150 are shown, 50 cropped from each side
+    assert_eq!(out[0], 50);
+    assert_eq!(out[99 * 4], 149);
+    assert_eq!(out[99 * 100 * 4], 50);
+  }
+
+  #[test]
+  fn test_fit_height_crops_wide_buffer() {
+    let row: Vec<u8> = (1..=4).flat_map(|v| [v, v, v, 255]).collect();
+    let mut renderer = PixelRenderer::new(4, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::FitHeight);
+    // 4 columns at 1x in a 2 pixel window: the middle two are visible
+    let out = renderer.render_to_vec(row.clone().into(), 2, 1).unwrap();
+    assert_eq!(&out[..], &[2, 2, 2, 255, 3, 3, 3, 255]);
+    renderer.set_anchor(Anchor::Left);
+    let out = renderer.render_to_vec(row.clone().into(), 2, 1).unwrap();
+    assert_eq!(&out[..], &[1, 1, 1, 255, 2, 2, 2, 255]);
+
+    renderer.set_anchor(Anchor::Center);
+    renderer.set_scale_mode(ScaleMode::FitWidth);
+    // 4 columns in 4 pixels, letterboxed in the middle row of 3
+    let out = renderer.render_to_vec(row.into(), 4, 3).unwrap();
+    assert_eq!(&out[..4], &[0, 0, 0, 255]);
+    assert_eq!(&out[16..20], &[1, 1, 1, 255]);
+    assert_eq!(renderer.layout_in(4, 3).offset_y, 1);
+  }
+
+  #[test]
+  fn test_balanced_letterbox_rounds_fit_edges() {
+    let mut renderer = PixelRenderer::new(3, 2).unwrap();
+    renderer.set_scale_mode(ScaleMode::Fit);
+    assert_eq!(renderer.layout_in(10, 5).offset_x, 1);
+    assert_eq!(renderer.layout_in(10, 5).scaled_width, 7);
+    renderer.set_balanced_letterbox(true);
+    let layout = renderer.layout_in(10, 5);
+    // The exact image spans 1.25..8.75, so both bars are 1 pixel
+    assert_eq!((layout.offset_x, layout.scaled_width), (1, 8));
+    assert_eq!(layout.letterbox_x, 2);
+    // Other anchors keep their alignment
+    renderer.set_anchor(Anchor::Left);
+    assert_eq!(renderer.layout_in(10, 5).offset_x, 0);
+  }
+
+  #[test]
+  fn test_source_over_blends_onto_existing_pixels() {
+    // 50% red 1x1 square fitted into the middle of a 3x1 green frame
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer.set_composite_mode(CompositeMode::SourceOver);
+    let green = [0, 255, 0, 255];
+    let mut frame = green.repeat(3);
+    renderer.draw_frame(
+      &mut frame,
+      &[255, 0, 0, 128],
+      FrameSize::packed(3, 1),
+      &mut StageTimer::new(false),
+      &mut FrameScratch::default(),
+    );
+    assert_eq!(&frame[..4], &green);
+    assert_eq!(&frame[4..8], &[128, 127, 0, 255]);
+    assert_eq!(&frame[8..], &green);
+  }
+
+  #[test]
+  fn test_draw_dirty_blends_source_over() {
+    // 50% red 2x1 buffer stretched over a 2x1 green frame, only the left pixel dirty
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_scale_mode(ScaleMode::Stretch);
+    renderer.set_composite_mode(CompositeMode::SourceOver);
+    let green = [0, 255, 0, 255];
+    let mut frame = green.repeat(4);
+    let dirty = Rect {
+      x: 0,
+      y: 0,
+      width: 1,
+      height: 1,
+    };
+    renderer.draw_dirty(
+      &mut frame,
+      &[255, 0, 0, 128].repeat(2),
+      FrameSize::packed(4, 1),
+      &dirty,
+      &mut FrameScratch::default(),
+    );
+    // The dirty pixel covers 2 window pixels, plus a pixel of margin
+    assert_eq!(&frame[..12], &[128, 127, 0, 255].repeat(3)[..]);
+    assert_eq!(&frame[12..], &green);
+  }
+
+  #[test]
+  fn test_frame_skipped_by_interval_is_not_recorded() {
+    let now = Instant::now();
+    let interval = Duration::from_millis(16);
+    let key = |id| FrameKey {
+      content: FrameContent::Id(id),
+      window_width: 4,
+      window_height: 4,
+    };
+    let mut last_frame = None;
+
+    // Submitted 10ms after the last present, so the interval skips it
+    let presented =
+      remaining_interval(Some(now), interval, now + Duration::from_millis(10)).is_none();
+    remember_frame(&mut last_frame, key(1), presented);
+    assert_eq!(last_frame, None);
+
+    // Resubmitted after the interval, it isn't a duplicate and presents
+    let presented = remaining_interval(Some(now), interval, now + interval).is_none();
+    remember_frame(&mut last_frame, key(1), presented);
+    assert_eq!(last_frame, Some(key(1)));
+
+    // A later skipped frame leaves the presented one recorded
+    remember_frame(&mut last_frame, key(2), false);
+    assert_eq!(last_frame, Some(key(1)));
+  }
+
+  #[test]
+  fn test_remaining_interval() {
+    let now = Instant::now();
+    let interval = Duration::from_millis(16);
+    assert_eq!(remaining_interval(None, interval, now), None);
+    assert_eq!(
+      remaining_interval(Some(now), interval, now + Duration::from_millis(10)),
+      Some(Duration::from_millis(6))
+    );
+    assert_eq!(
+      remaining_interval(Some(now), interval, now + interval),
+      None
+    );
+    assert_eq!(remaining_interval(Some(now), Duration::ZERO, now), None);
+  }
+
+  #[test]
+  fn test_source_stride_skips_row_padding() {
+    let mut renderer = PixelRenderer::new(1, 2).unwrap();
+    renderer.set_source_stride_bytes(Some(8)).unwrap();
+    let padded = vec![1, 1, 1, 255, 9, 9, 9, 9, 2, 2, 2, 255, 9, 9, 9, 9];
+    let out = renderer.render_to_vec(padded.into(), 1, 2).unwrap();
+    assert_eq!(out.to_vec(), vec![1, 1, 1, 255, 2, 2, 2, 255]);
+
+    assert!(renderer.validate_buffer(&[0; 8]).is_err());
+    assert!(renderer.set_source_stride_bytes(Some(2)).is_err());
+    assert_eq!(renderer.source_stride, Some(8));
+
+    // A stride checked against RGB rows is still too short once the format widens
+    renderer.set_pixel_format(PixelFormat::Rgb);
+    renderer.set_source_stride_bytes(Some(3)).unwrap();
+    renderer.set_pixel_format(PixelFormat::Rgba);
+    assert!(renderer.validate_buffer(&[0; 6]).is_err());
+  }
+
+  #[test]
+  fn test_with_options_rejects_short_source_stride() {
+    let options = |format: PixelFormat| RenderOptions {
+      buffer_width: 2,
+      pixel_format: Some(format),
+      source_stride_bytes: Some(6),
+      ..Default::default()
+    };
+    assert!(PixelRenderer::with_options(options(PixelFormat::Rgb)).is_ok());
+    assert!(PixelRenderer::with_options(options(PixelFormat::Rgba)).is_err());
+  }
+
+  #[test]
+  fn test_layout_matches_letterbox() {
+    let mut renderer = PixelRenderer::new(4, 2).unwrap();
+    let expected = RenderLayout {
+      offset_x: 0,
+      offset_y: 2,
+      scaled_width: 8,
+      scaled_height: 4,
+      letterbox_x: 0,
+      letterbox_y: 4,
+    };
+    assert_eq!(renderer.layout_in(8, 8), expected);
+
+    renderer.set_viewport(Some(Rect {
+      x: 10,
+      y: 20,
+      width: 8,
+      height: 8,
+    }));
+    let layout = renderer.layout_in(100, 100);
+    assert_eq!((layout.offset_x, layout.offset_y), (10, 22));
+
+    // Fill crops instead of leaving bars
+    renderer.set_scale_mode(ScaleMode::Fill);
+    let layout = renderer.layout_in(100, 100);
+    assert_eq!((layout.letterbox_x, layout.letterbox_y), (0, 0));
+  }
+
+  #[test]
+  fn test_padding_insets_image_and_keeps_background() {
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer.set_padding(1, 1, 1, 1);
+    let out = renderer
+      .render_to_vec(vec![255, 0, 0, 255].into(), 4, 4)
+      .unwrap();
+    let pixel = |x: usize, y: usize| out[(y * 4 + x) * 4..][..4].to_vec();
+    assert_eq!(pixel(0, 0), vec![0, 0, 0, 255]);
+    assert_eq!(pixel(3, 2), vec![0, 0, 0, 255]);
+    assert_eq!(pixel(1, 1), vec![255, 0, 0, 255]);
+    assert_eq!(pixel(2, 2), vec![255, 0, 0, 255]);
+    let layout = renderer.layout_in(4, 4);
+    assert_eq!((layout.offset_x, layout.scaled_width), (1, 2));
+
+    // Margins that leave nothing draw only the background
+    renderer.set_padding(2, 2, 2, 2);
+    let out = renderer
+      .render_to_vec(vec![255, 0, 0, 255].into(), 4, 4)
+      .unwrap();
+    assert!(out.chunks(4).all(|pixel| pixel == [0, 0, 0, 255]));
+  }
+
+  #[test]
+  fn test_split_honors_viewport_and_padding() {
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer.set_viewport(Some(Rect {
+      x: 2,
+      y: 0,
+      width: 8,
+      height: 2,
+    }));
+    renderer.set_padding(0, 1, 0, 1);
+    let size = FrameSize::packed(10, 2);
+    let mut frame = vec![7u8; 10 * 2 * 4];
+    renderer.draw_split(
+      &mut frame,
+      &[255, 0, 0, 255],
+      size,
+      [ScaleMode::Stretch, ScaleMode::Stretch],
+      &mut StageTimer::new(false),
+      &mut FrameScratch::for_width(10),
+    );
+    let row: Vec<&[u8]> = frame[..10 * 4].chunks(4).collect();
+    // Outside the viewport stays untouched, padding shows the background
+    assert_eq!(row[1], [7, 7, 7, 7]);
+    assert_eq!(row[2], [0, 0, 0, 255]);
+    assert_eq!(row[9], [0, 0, 0, 255]);
+    // The padded viewport is 6 wide: halves of 2 around a 2-pixel divider
+    assert_eq!(row[3], [255, 0, 0, 255]);
+    assert_eq!(row[5], SPLIT_DIVIDER_COLOR);
+    assert_eq!(row[6], SPLIT_DIVIDER_COLOR);
+    assert_eq!(row[8], [255, 0, 0, 255]);
+  }
+
+  #[test]
+  fn test_brightness_applies_while_copying() {
+    let mut renderer = PixelRenderer::new(1, 1).unwrap();
+    renderer.set_brightness(Some(0.5)).unwrap();
+    let out = renderer
+      .render_to_vec(vec![200, 100, 50, 255].into(), 1, 1)
+      .unwrap();
+    assert_eq!(out.to_vec(), vec![100, 50, 25, 255]);
+
+    renderer.set_brightness(None).unwrap();
+    assert!(renderer.tone.is_none());
+    assert!(renderer.set_gamma(Some(0.0)).is_err());
+  }
+
+  #[test]
+  fn test_tint_multiplies_while_copying() {
+    let mut renderer = PixelRenderer::new(2, 2).unwrap();
+    renderer.set_tint(Some(vec![128, 0, 0])).unwrap();
+    let out = renderer.render_to_vec(vec![255; 16].into(), 2, 2).unwrap();
+    assert!(out.chunks(4).all(|pixel| pixel == [128, 0, 0, 255]));
+
+    renderer.set_tint(None).unwrap();
+    let out = renderer.render_to_vec(vec![255; 16].into(), 2, 2).unwrap();
+    assert!(out.iter().all(|&value| value == 255));
+    assert!(renderer.set_tint(Some(vec![1, 2])).is_err());
+  }
+
+  #[test]
+  fn test_rotation_turns_corners_clockwise() {
+    let mut renderer = PixelRenderer::new(2, 2).unwrap();
+    renderer.set_rotation(Rotation::Cw90);
+    let out = renderer
+      .render_to_vec(CORNERS.to_vec().into(), 2, 2)
+      .unwrap();
+    assert_eq!(corner_values(&out, 2, 2), [3, 1, 4, 2]);
+
+    renderer.set_rotation(Rotation::Cw270);
+    let out = renderer
+      .render_to_vec(CORNERS.to_vec().into(), 2, 2)
+      .unwrap();
+    assert_eq!(corner_values(&out, 2, 2), [2, 4, 1, 3]);
+  }
+
+  #[test]
+  fn test_rotation_swaps_fit_layout() {
+    // A 2x1 buffer turned a quarter is 1x2, so Fit pillarboxes it in a 4x4 window
+    let mut renderer = PixelRenderer::new(2, 1).unwrap();
+    renderer.set_rotation(Rotation::Cw90);
+    let out = renderer
+      .render_to_vec(CORNERS[..8].to_vec().into(), 4, 4)
+      .unwrap();
+    let column = |x: usize| -> Vec<u8> { (0..4).map(|y| out[(y * 4 + x) * 4]).collect() };
+    assert_eq!(column(0), vec![0, 0, 0, 0]);
+    assert_eq!(column(1), vec![1, 1, 2, 2]);
+    assert_eq!(column(3), vec![0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_stage_timer_disabled_records_nothing() {
+    let mut timer = StageTimer::new(false);
+    timer.end_clear();
+    timer.end_copy();
+    timer.end_present();
+    let mut stats = RenderStats {
+      clear_us: Some(5),
+      ..Default::default()
+    };
+    timer.write_to(&mut stats);
+    assert_eq!(stats.clear_us, None);
+    assert_eq!(stats.copy_us, None);
+    assert_eq!(stats.present_us, None);
+  }
+
+  #[test]
+  fn test_stage_timer_enabled_records_each_stage() {
+    let mut timer = StageTimer::new(true);
+    timer.end_clear();
+    std::thread::sleep(Duration::from_millis(2));
+    timer.end_copy();
+    timer.end_present();
+    let mut stats = RenderStats::default();
+    timer.write_to(&mut stats);
+    assert!(stats.clear_us.is_some());
+    assert!(stats.copy_us.unwrap() >= 2000);
+    assert!(stats.present_us.is_some());
+  }
 }
@@ -0,0 +1,125 @@
+//! Inter-frame motion estimation
+//!
+//! This module reduces frames to small luma thumbnails so consecutive frames
+//! can be compared cheaply, e.g. to pick faster sampling while content moves.
+
+use crate::tao::enums::PixelFormat;
+
+/// Number of sample points along each axis of a signature
+const SIGNATURE_SIZE: u32 = 16;
+
+/// Mean luma change (0.0..=1.0) above which a frame counts as moving
+pub const MOTION_THRESHOLD: f32 = 0.02;
+
+/// Coarse luma thumbnail of a frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameSignature {
+  cells: Vec<u8>,
+}
+
+impl FrameSignature {
+  /// Computes the signature by point-sampling a grid of pixels
+  ///
+  /// # Arguments
+  /// * `buffer` - Pixel buffer of `width * height` pixels in `format`
+  /// * `width` - Width of the buffer in pixels
+  /// * `height` - Height of the buffer in pixels
+  /// * `stride` - Bytes from the start of one row to the next
+  /// * `format` - Byte layout of the buffer's pixels
+  pub fn new(buffer: &[u8], width: u32, height: u32, stride: u32, format: PixelFormat) -> Self {
+    if width == 0 || height == 0 {
+      return Self { cells: Vec::new() };
+    }
+
+    let mut cells = Vec::with_capacity((SIGNATURE_SIZE * SIGNATURE_SIZE) as usize);
+    for gy in 0..SIGNATURE_SIZE {
+      let y = ((gy * 2 + 1) as u64 * height as u64 / (SIGNATURE_SIZE * 2) as u64) as usize;
+      for gx in 0..SIGNATURE_SIZE {
+        let x = ((gx * 2 + 1) as u64 * width as u64 / (SIGNATURE_SIZE * 2) as u64) as usize;
+        let bytes_per_pixel = format.bytes_per_pixel() as usize;
+        let idx = y * stride as usize + x * bytes_per_pixel;
+        let luma = buffer.get(idx..idx + bytes_per_pixel).map_or(0, |p| {
+          let [r, g, b, _] = format.to_rgba(p);
+          ((r as u32 * 77 + g as u32 * 150 + b as u32 * 29) >> 8) as u8
+        });
+        cells.push(luma);
+      }
+    }
+    Self { cells }
+  }
+
+  /// Mean absolute luma difference to another signature, from 0.0 to 1.0
+  ///
+  /// Signatures of differently sized frames are treated as entirely different.
+  pub fn difference(&self, other: &Self) -> f32 {
+    if self.cells.len() != other.cells.len() {
+      return 1.0;
+    }
+    if self.cells.is_empty() {
+      return 0.0;
+    }
+    let total: u32 = self
+      .cells
+      .iter()
+      .zip(&other.cells)
+      .map(|(&a, &b)| a.abs_diff(b) as u32)
+      .sum();
+    total as f32 / (self.cells.len() as f32 * 255.0)
+  }
+
+  /// Returns true if the change from `previous` exceeds [`MOTION_THRESHOLD`]
+  pub fn is_moving_from(&self, previous: &Self) -> bool {
+    self.difference(previous) > MOTION_THRESHOLD
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn solid(width: u32, height: u32, value: u8) -> Vec<u8> {
+    vec![value; (width * height * 4) as usize]
+  }
+
+  #[test]
+  fn test_identical_frames_do_not_move() {
+    let a = FrameSignature::new(&solid(64, 48, 90), 64, 48, 64 * 4, PixelFormat::Rgba);
+    let b = FrameSignature::new(&solid(64, 48, 90), 64, 48, 64 * 4, PixelFormat::Rgba);
+    assert_eq!(a.difference(&b), 0.0);
+    assert!(!b.is_moving_from(&a));
+  }
+
+  #[test]
+  fn test_inverted_frame_moves() {
+    let a = FrameSignature::new(&solid(64, 48, 0), 64, 48, 64 * 4, PixelFormat::Rgba);
+    let b = FrameSignature::new(&solid(64, 48, 255), 64, 48, 64 * 4, PixelFormat::Rgba);
+    assert!(b.difference(&a) > 0.99);
+    assert!(b.is_moving_from(&a));
+  }
+
+  #[test]
+  fn test_small_change_is_static() {
+    let a = FrameSignature::new(&solid(64, 48, 100), 64, 48, 64 * 4, PixelFormat::Rgba);
+    let b = FrameSignature::new(&solid(64, 48, 102), 64, 48, 64 * 4, PixelFormat::Rgba);
+    assert!(!b.is_moving_from(&a));
+  }
+
+  #[test]
+  fn test_channel_order_is_respected() {
+    // Pure blue in BGRA and pure red in RGBA have different luma
+    let blue = FrameSignature::new(&[255, 0, 0, 255], 1, 1, 4, PixelFormat::Bgra);
+    let red = FrameSignature::new(&[255, 0, 0, 255], 1, 1, 4, PixelFormat::Rgba);
+    assert!(blue.difference(&red) > 0.0);
+    let rgb = FrameSignature::new(&[255, 0, 0], 1, 1, 3, PixelFormat::Rgb);
+    assert_eq!(rgb.difference(&red), 0.0);
+  }
+
+  #[test]
+  fn test_tiny_and_empty_frames() {
+    let one = FrameSignature::new(&[10, 20, 30, 255], 1, 1, 4, PixelFormat::Rgba);
+    assert_eq!(one.cells.len(), (SIGNATURE_SIZE * SIGNATURE_SIZE) as usize);
+    let empty = FrameSignature::new(&[], 0, 0, 0, PixelFormat::Rgba);
+    assert_eq!(empty.difference(&empty), 0.0);
+    assert_eq!(empty.difference(&one), 1.0);
+  }
+}
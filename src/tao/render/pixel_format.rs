@@ -0,0 +1,164 @@
+//! Source pixel formats
+//!
+//! This module describes the byte layouts accepted for source buffers and
+//! converts their pixels to the RGBA layout of the frame.
+
+use crate::tao::enums::PixelFormat;
+use napi_derive::napi;
+
+/// Every pixel format with the name of its `PixelFormat` member
+const PIXEL_FORMAT_NAMES: &[(PixelFormat, &str)] = &[
+  (PixelFormat::Rgba, "Rgba"),
+  (PixelFormat::Bgra, "Bgra"),
+  (PixelFormat::Rgb, "Rgb"),
+  (PixelFormat::Bgr, "Bgr"),
+  (PixelFormat::Gray8, "Gray8"),
+  (PixelFormat::Indexed8, "Indexed8"),
+  (PixelFormat::NativeArgb, "NativeArgb"),
+];
+
+/// Returns the names of the supported source pixel formats, as members of
+/// `PixelFormat` (e.g. `PixelFormat[name]`)
+#[napi]
+pub fn supported_pixel_formats() -> Vec<String> {
+  PIXEL_FORMAT_NAMES
+    .iter()
+    .map(|&(_, name)| name.to_string())
+    .collect()
+}
+
+/// Number of colors in an `Indexed8` palette
+pub const PALETTE_LEN: usize = 256;
+
+/// RGBA colors looked up by `Indexed8` pixels
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Palette([[u8; 4]; PALETTE_LEN]);
+
+impl Palette {
+  /// Builds a palette from `PALETTE_LEN` RGBA colors (1024 bytes)
+  pub fn from_rgba(bytes: &[u8]) -> napi::Result<Self> {
+    if bytes.len() != PALETTE_LEN * 4 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Palette size mismatch: got {} bytes, expected {} bytes ({} RGBA colors)",
+          bytes.len(),
+          PALETTE_LEN * 4,
+          PALETTE_LEN
+        ),
+      ));
+    }
+    let mut colors = [[0u8; 4]; PALETTE_LEN];
+    for (color, rgba) in colors.iter_mut().zip(bytes.chunks_exact(4)) {
+      color.copy_from_slice(rgba);
+    }
+    Ok(Self(colors))
+  }
+
+  /// Color of a palette index
+  #[inline]
+  pub fn color(&self, index: u8) -> [u8; 4] {
+    self.0[index as usize]
+  }
+}
+
+impl PixelFormat {
+  /// Number of bytes each pixel occupies in a source buffer
+  pub fn bytes_per_pixel(self) -> u32 {
+    match self {
+      PixelFormat::Rgba | PixelFormat::Bgra | PixelFormat::NativeArgb => 4,
+      PixelFormat::Rgb | PixelFormat::Bgr => 3,
+      PixelFormat::Gray8 | PixelFormat::Indexed8 => 1,
+    }
+  }
+
+  /// Converts one pixel (`bytes_per_pixel` bytes) to RGBA
+  ///
+  /// Formats without alpha are fully opaque. `Indexed8` pixels need a
+  /// [`Palette`]; without one the index is shown as a gray level.
+  pub fn to_rgba(self, pixel: &[u8]) -> [u8; 4] {
+    match self {
+      PixelFormat::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+      PixelFormat::Bgra => [pixel[2], pixel[1], pixel[0], pixel[3]],
+      PixelFormat::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+      PixelFormat::Bgr => [pixel[2], pixel[1], pixel[0], 255],
+      PixelFormat::Gray8 | PixelFormat::Indexed8 => [pixel[0], pixel[0], pixel[0], 255],
+      PixelFormat::NativeArgb => {
+        unpack_argb(u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]))
+      }
+    }
+  }
+}
+
+/// Packs an RGBA color into a 0x00RRGGBB word, ignoring alpha
+///
+/// The layout softbuffer-style surfaces expect. Channels are placed with
+/// shifts, so the word's value is the same on little- and big-endian hosts;
+/// only its in-memory byte order follows the host.
+#[inline]
+pub fn pack_xrgb([r, g, b, _]: [u8; 4]) -> u32 {
+  (r as u32) << 16 | (g as u32) << 8 | b as u32
+}
+
+/// Splits a 0xAARRGGBB word into an RGBA color, regardless of host endianness
+#[inline]
+pub fn unpack_argb(word: u32) -> [u8; 4] {
+  [
+    (word >> 16) as u8,
+    (word >> 8) as u8,
+    word as u8,
+    (word >> 24) as u8,
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bytes_per_pixel() {
+    assert_eq!(PixelFormat::Rgba.bytes_per_pixel(), 4);
+    assert_eq!(PixelFormat::Bgra.bytes_per_pixel(), 4);
+    assert_eq!(PixelFormat::Rgb.bytes_per_pixel(), 3);
+    assert_eq!(PixelFormat::Bgr.bytes_per_pixel(), 3);
+    assert_eq!(PixelFormat::Gray8.bytes_per_pixel(), 1);
+  }
+
+  #[test]
+  fn test_to_rgba_reorders_channels() {
+    assert_eq!(PixelFormat::Rgba.to_rgba(&[1, 2, 3, 4]), [1, 2, 3, 4]);
+    assert_eq!(PixelFormat::Bgra.to_rgba(&[1, 2, 3, 4]), [3, 2, 1, 4]);
+    assert_eq!(PixelFormat::Rgb.to_rgba(&[1, 2, 3]), [1, 2, 3, 255]);
+    assert_eq!(PixelFormat::Bgr.to_rgba(&[1, 2, 3]), [3, 2, 1, 255]);
+    assert_eq!(PixelFormat::Gray8.to_rgba(&[7]), [7, 7, 7, 255]);
+    assert_eq!(
+      PixelFormat::NativeArgb.to_rgba(&0x80112233u32.to_ne_bytes()),
+      [0x11, 0x22, 0x33, 0x80]
+    );
+  }
+
+  #[test]
+  fn test_pack_xrgb_is_endian_independent() {
+    assert_eq!(pack_xrgb([0x11, 0x22, 0x33, 0x80]), 0x00112233);
+    assert_eq!(unpack_argb(0x80112233), [0x11, 0x22, 0x33, 0x80]);
+  }
+
+  #[test]
+  fn test_supported_pixel_formats_name_members() {
+    let names = supported_pixel_formats();
+    assert_eq!(names.len(), PIXEL_FORMAT_NAMES.len());
+    assert!(PIXEL_FORMAT_NAMES
+      .iter()
+      .all(|(format, name)| format!("{:?}", format) == *name));
+  }
+
+  #[test]
+  fn test_palette_from_rgba() {
+    let mut bytes = vec![0u8; PALETTE_LEN * 4];
+    bytes[4..8].copy_from_slice(&[1, 2, 3, 4]);
+    let palette = Palette::from_rgba(&bytes).unwrap();
+    assert_eq!(palette.color(1), [1, 2, 3, 4]);
+    assert_eq!(palette.color(255), [0, 0, 0, 0]);
+    assert!(Palette::from_rgba(&bytes[..1020]).is_err());
+  }
+}
@@ -0,0 +1,110 @@
+//! Source buffer rotation
+//!
+//! Rotation happens in source space: the buffer is turned first, and the scale
+//! mode then lays out the rotated image (so a 90 degree turn of a landscape
+//! buffer fits as portrait). Flips apply to the buffer before it is turned.
+
+use crate::tao::enums::Rotation;
+
+impl Rotation {
+  /// Whether the rotated image's width and height are swapped
+  pub fn swaps_axes(self) -> bool {
+    matches!(self, Rotation::Cw90 | Rotation::Cw270)
+  }
+
+  /// Size of a `width` x `height` image after rotating it
+  pub fn rotated_size(self, width: u32, height: u32) -> (u32, u32) {
+    if self.swaps_axes() {
+      (height, width)
+    } else {
+      (width, height)
+    }
+  }
+
+  /// Maps a pixel of the rotated image to the pixel of the `width` x `height`
+  /// image it came from
+  pub(crate) fn source_pixel(self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+    match self {
+      Rotation::None => (x, y),
+      Rotation::Cw90 => (y, height - 1 - x),
+      Rotation::Cw180 => (width - 1 - x, height - 1 - y),
+      Rotation::Cw270 => (width - 1 - y, x),
+    }
+  }
+
+  /// Maps a rectangle (left, top, width, height) of a `width` x `height` image
+  /// to where it lands in the rotated image
+  pub(crate) fn rotate_rect(
+    self,
+    rect: (f64, f64, f64, f64),
+    width: f64,
+    height: f64,
+  ) -> (f64, f64, f64, f64) {
+    let (left, top, rect_width, rect_height) = rect;
+    match self {
+      Rotation::None => rect,
+      Rotation::Cw90 => (height - top - rect_height, left, rect_height, rect_width),
+      Rotation::Cw180 => (
+        width - left - rect_width,
+        height - top - rect_height,
+        rect_width,
+        rect_height,
+      ),
+      Rotation::Cw270 => (top, width - left - rect_width, rect_height, rect_width),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ROTATIONS: [Rotation; 4] = [
+    Rotation::None,
+    Rotation::Cw90,
+    Rotation::Cw180,
+    Rotation::Cw270,
+  ];
+
+  #[test]
+  fn test_rotated_size() {
+    assert_eq!(Rotation::Cw90.rotated_size(4, 3), (3, 4));
+    assert_eq!(Rotation::Cw180.rotated_size(4, 3), (4, 3));
+  }
+
+  #[test]
+  fn test_source_pixel_moves_top_left_corner_clockwise() {
+    // Where the source's top-left pixel ends up in the rotated 3x2 source image
+    let (width, height) = (3, 2);
+    let corner_after = |rotation: Rotation| {
+      let (rotated_width, rotated_height) = rotation.rotated_size(width, height);
+      (0..rotated_height)
+        .flat_map(|y| (0..rotated_width).map(move |x| (x, y)))
+        .find(|&(x, y)| rotation.source_pixel(x, y, width, height) == (0, 0))
+        .unwrap()
+    };
+    assert_eq!(corner_after(Rotation::None), (0, 0));
+    assert_eq!(corner_after(Rotation::Cw90), (1, 0));
+    assert_eq!(corner_after(Rotation::Cw180), (2, 1));
+    assert_eq!(corner_after(Rotation::Cw270), (0, 2));
+  }
+
+  #[test]
+  fn test_rotate_rect_matches_source_pixel() {
+    // A 1x1 rect must land on the pixel that samples it
+    let (width, height) = (3u32, 2u32);
+    for rotation in ROTATIONS {
+      let (rotated_width, rotated_height) = rotation.rotated_size(width, height);
+      for y in 0..rotated_height {
+        for x in 0..rotated_width {
+          let (sx, sy) = rotation.source_pixel(x, y, width, height);
+          let rect = (sx as f64, sy as f64, 1.0, 1.0);
+          assert_eq!(
+            rotation.rotate_rect(rect, width as f64, height as f64),
+            (x as f64, y as f64, 1.0, 1.0)
+          );
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,109 @@
+//! Unscaled row copies
+//!
+//! When a copy is 1:1 and the source is displayed exactly as stored, whole rows
+//! can be moved at once instead of sampling pixel by pixel. RGBA rows are plain
+//! memory copies; BGRA rows (common for video) and native ARGB words on
+//! little-endian machines are channel-swapped, 4 pixels at a time with SSSE3 on
+//! x86_64 when the CPU supports it.
+
+use super::scaler::visible_span;
+use super::{FrameRegion, ScaledCopy, SourceImage};
+use crate::tao::enums::PixelFormat;
+
+/// Copies an unscaled rectangle row by row, returning false when the source
+/// needs per-pixel conversion and the caller should sample it instead
+pub(crate) fn copy_unscaled_rows(
+  frame: &mut [u8],
+  source: &SourceImage,
+  region: FrameRegion,
+  copy: ScaledCopy,
+) -> bool {
+  let swap = match source.format {
+    PixelFormat::Rgba => false,
+    PixelFormat::Bgra => true,
+    // 0xAARRGGBB words are stored as B, G, R, A bytes on little-endian machines
+    PixelFormat::NativeArgb if cfg!(target_endian = "little") => true,
+    _ => return false,
+  };
+  if !source.is_stored_layout() {
+    return false;
+  }
+  let Some((columns, rows)) = visible_span(region, copy) else {
+    return true;
+  };
+  let len = columns.end - columns.start;
+  for y in rows {
+    let Some(src) = source.stored_row(copy.src_x + columns.start, copy.src_y + y, len) else {
+      continue;
+    };
+    let dst_idx = region.index(copy.offset_x + columns.start, copy.offset_y + y);
+    let Some(dst) = frame.get_mut(dst_idx..dst_idx + src.len()) else {
+      continue;
+    };
+    if swap {
+      swap_red_blue(dst, src);
+    } else {
+      dst.copy_from_slice(src);
+    }
+  }
+  true
+}
+
+/// Copies BGRA pixels to RGBA (or back); `dst` and `src` must be the same length
+pub fn swap_red_blue(dst: &mut [u8], src: &[u8]) {
+  #[cfg(target_arch = "x86_64")]
+  if is_x86_feature_detected!("ssse3") {
+    // SAFETY: the CPU supports SSSE3, checked just above
+    unsafe { swap_red_blue_ssse3(dst, src) };
+    return;
+  }
+  swap_red_blue_scalar(dst, src);
+}
+
+/// Reference implementation of [`swap_red_blue`]
+fn swap_red_blue_scalar(dst: &mut [u8], src: &[u8]) {
+  for (out, pixel) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+    out.copy_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+  }
+}
+
+/// [`swap_red_blue`] for 4 pixels per instruction
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn swap_red_blue_ssse3(dst: &mut [u8], src: &[u8]) {
+  use std::arch::x86_64::{
+    __m128i, _mm_loadu_si128, _mm_setr_epi8, _mm_shuffle_epi8, _mm_storeu_si128,
+  };
+
+  let mask = _mm_setr_epi8(2, 1, 0, 3, 6, 5, 4, 7, 10, 9, 8, 11, 14, 13, 12, 15);
+  let mut dst_chunks = dst.chunks_exact_mut(16);
+  let mut src_chunks = src.chunks_exact(16);
+  for (out, pixels) in (&mut dst_chunks).zip(&mut src_chunks) {
+    // SAFETY: both chunks are 16 bytes long and the loads/stores are unaligned
+    unsafe {
+      let value = _mm_loadu_si128(pixels.as_ptr() as *const __m128i);
+      _mm_storeu_si128(
+        out.as_mut_ptr() as *mut __m128i,
+        _mm_shuffle_epi8(value, mask),
+      );
+    }
+  }
+  swap_red_blue_scalar(dst_chunks.into_remainder(), src_chunks.remainder());
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_swap_red_blue_matches_scalar() {
+    // 7 pixels exercise both the vector loop and the remainder
+    let src: Vec<u8> = (0..28).collect();
+    let mut fast = vec![0u8; 28];
+    let mut reference = vec![0u8; 28];
+    swap_red_blue(&mut fast, &src);
+    swap_red_blue_scalar(&mut reference, &src);
+    assert_eq!(fast, reference);
+    assert_eq!(&fast[..8], &[2, 1, 0, 3, 6, 5, 4, 7]);
+  }
+}
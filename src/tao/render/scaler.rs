@@ -0,0 +1,478 @@
+//! Pluggable scaling implementations
+//!
+//! Scaled copies dispatch through the [`Scaler`] trait, so a new implementation
+//! (SIMD, higher quality filters, ...) can be added without touching the present
+//! path. Implementations are registered by name in [`builtin_scalers`] and
+//! selected from JavaScript with `PixelRenderer.setScaler`.
+
+use super::dither::Quantizer;
+use super::scratch::FrameScratch;
+use super::{FrameRegion, ScaledCopy, SourceImage};
+use crate::tao::enums::{DitherMode, FilterMode, WrapMode};
+use napi_derive::napi;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Copies a rectangle of the source into a scaled rectangle of the frame
+pub(crate) trait Scaler: Send + Sync {
+  /// Name used to select this scaler from JavaScript
+  fn name(&self) -> &'static str;
+
+  /// Scales `copy`'s source rectangle into `region`, clipping to the region bounds
+  ///
+  /// `dither` applies to scalers that blend pixels into values between 8-bit levels.
+  /// `scratch` holds working memory reused across frames.
+  fn scale(
+    &self,
+    frame: &mut [u8],
+    source: &SourceImage,
+    region: FrameRegion,
+    copy: ScaledCopy,
+    dither: DitherMode,
+    scratch: &mut FrameScratch,
+  );
+}
+
+/// Nearest neighbor sampling (exact copies, blocky when upscaling)
+pub(crate) struct NearestScaler;
+
+/// Bilinear interpolation of the four nearest source pixels
+pub(crate) struct BilinearScaler;
+
+/// Box filter averaging every source pixel behind a destination pixel when
+/// downscaling, nearest neighbor when upscaling on both axes
+pub(crate) struct AreaAverageScaler;
+
+/// Lanczos-3 filter when downscaling, bilinear when upscaling on both axes
+///
+/// Weighs every source pixel within three (scaled) lobes of the destination
+/// pixel's center, so a 4x downscale reads 24 source pixels per pixel along
+/// each axis.
+pub(crate) struct LanczosScaler;
+
+/// Lobes on each side of the Lanczos kernel
+const LANCZOS_LOBES: f32 = 3.0;
+
+impl Scaler for NearestScaler {
+  fn name(&self) -> &'static str {
+    "nearest"
+  }
+
+  fn scale(
+    &self,
+    frame: &mut [u8],
+    source: &SourceImage,
+    region: FrameRegion,
+    copy: ScaledCopy,
+    _dither: DitherMode,
+    _scratch: &mut FrameScratch,
+  ) {
+    let Some((columns, rows)) = visible_span(region, copy) else {
+      return;
+    };
+    let scale_x = copy.src_width as f32 / copy.scaled_width as f32;
+    let scale_y = copy.src_height as f32 / copy.scaled_height as f32;
+    let max_x = copy.src_width as f32 - 1.0;
+    let max_y = copy.src_height as f32 - 1.0;
+
+    for y in rows {
+      let src_y = copy.src_y + (y as f32 * scale_y).min(max_y) as u32;
+      for x in columns.clone() {
+        let src_x = copy.src_x + (x as f32 * scale_x).min(max_x) as u32;
+        let dst_idx = region.index(copy.offset_x + x, copy.offset_y + y);
+        if let Some(pixel) = source.pixel(src_x, src_y) {
+          if dst_idx + 4 <= frame.len() {
+            frame[dst_idx..dst_idx + 4].copy_from_slice(&pixel);
+          }
+        }
+      }
+    }
+  }
+}
+
+impl Scaler for BilinearScaler {
+  fn name(&self) -> &'static str {
+    "bilinear"
+  }
+
+  fn scale(
+    &self,
+    frame: &mut [u8],
+    source: &SourceImage,
+    region: FrameRegion,
+    copy: ScaledCopy,
+    dither: DitherMode,
+    scratch: &mut FrameScratch,
+  ) {
+    let Some((columns, rows)) = visible_span(region, copy) else {
+      return;
+    };
+    let scale_x = copy.src_width as f32 / copy.scaled_width as f32;
+    let scale_y = copy.src_height as f32 / copy.scaled_height as f32;
+    let max_x = copy.src_width as f32 - 1.0;
+    let max_y = copy.src_height as f32 - 1.0;
+    let rows_memory = std::mem::take(&mut scratch.dither_rows);
+    let mut quantizer = Quantizer::with_rows(dither, columns.end, rows_memory);
+
+    // Clamping keeps samples inside the source rectangle; other wrap modes let
+    // edge samples reach across to the neighbors the wrap mode picks
+    let bound = |value: f32, max: f32| match source.wrap {
+      WrapMode::Clamp => value.clamp(0.0, max),
+      _ => value,
+    };
+
+    // Sample at destination pixel centers; column taps are shared by every row
+    let column_taps = &mut scratch.column_taps;
+    column_taps.clear();
+    column_taps.extend(
+      columns
+        .clone()
+        .map(|x| source.tap_x(copy.src_x as f32 + bound((x as f32 + 0.5) * scale_x - 0.5, max_x))),
+    );
+
+    for y in rows {
+      let tap_y = source.tap_y(copy.src_y as f32 + bound((y as f32 + 0.5) * scale_y - 0.5, max_y));
+      for (x, &tap_x) in columns.clone().zip(column_taps.iter()) {
+        let pixel = source.blend_taps(tap_x, tap_y);
+        let dst_idx = region.index(copy.offset_x + x, copy.offset_y + y);
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&quantizer.quantize(x, y, pixel));
+        }
+      }
+      quantizer.end_row();
+    }
+    column_taps.clear();
+    scratch.dither_rows = quantizer.into_rows();
+  }
+}
+
+impl Scaler for AreaAverageScaler {
+  fn name(&self) -> &'static str {
+    "area-average"
+  }
+
+  fn scale(
+    &self,
+    frame: &mut [u8],
+    source: &SourceImage,
+    region: FrameRegion,
+    copy: ScaledCopy,
+    dither: DitherMode,
+    scratch: &mut FrameScratch,
+  ) {
+    let scale_x = copy.src_width as f32 / copy.scaled_width as f32;
+    let scale_y = copy.src_height as f32 / copy.scaled_height as f32;
+    if scale_x <= 1.0 && scale_y <= 1.0 {
+      // Every destination pixel covers at most one source pixel
+      NearestScaler.scale(frame, source, region, copy, dither, scratch);
+      return;
+    }
+    let Some((columns, rows)) = visible_span(region, copy) else {
+      return;
+    };
+    let rows_memory = std::mem::take(&mut scratch.dither_rows);
+    let mut quantizer = Quantizer::with_rows(dither, columns.end, rows_memory);
+
+    for y in rows {
+      let (y0, y1) = box_bounds(y, scale_y, copy.src_height);
+      for x in columns.clone() {
+        let (x0, x1) = box_bounds(x, scale_x, copy.src_width);
+        let mut sum = [0u64; 4];
+        let mut count = 0u64;
+        for src_y in y0..y1 {
+          for src_x in x0..x1 {
+            if let Some(pixel) = source.pixel(copy.src_x + src_x, copy.src_y + src_y) {
+              for (total, value) in sum.iter_mut().zip(pixel) {
+                *total += value as u64;
+              }
+              count += 1;
+            }
+          }
+        }
+        let dst_idx = region.index(copy.offset_x + x, copy.offset_y + y);
+        if count > 0 && dst_idx + 4 <= frame.len() {
+          let pixel = sum.map(|total| total as f32 / count as f32);
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&quantizer.quantize(x, y, pixel));
+        }
+      }
+      quantizer.end_row();
+    }
+    scratch.dither_rows = quantizer.into_rows();
+  }
+}
+
+impl Scaler for LanczosScaler {
+  fn name(&self) -> &'static str {
+    "lanczos"
+  }
+
+  fn scale(
+    &self,
+    frame: &mut [u8],
+    source: &SourceImage,
+    region: FrameRegion,
+    copy: ScaledCopy,
+    dither: DitherMode,
+    scratch: &mut FrameScratch,
+  ) {
+    let scale_x = copy.src_width as f32 / copy.scaled_width as f32;
+    let scale_y = copy.src_height as f32 / copy.scaled_height as f32;
+    if scale_x <= 1.0 && scale_y <= 1.0 {
+      // Magnifying; the wide kernel would only add ringing
+      BilinearScaler.scale(frame, source, region, copy, dither, scratch);
+      return;
+    }
+    let Some((columns, rows)) = visible_span(region, copy) else {
+      return;
+    };
+    let rows_memory = std::mem::take(&mut scratch.dither_rows);
+    let mut quantizer = Quantizer::with_rows(dither, columns.end, rows_memory);
+
+    // The kernel is separable: filter the source rows horizontally once, then
+    // combine the filtered rows vertically for each destination row
+    let column_taps: Vec<_> = columns
+      .clone()
+      .map(|x| lanczos_taps(x, scale_x, copy.src_width))
+      .collect();
+    let row_taps: Vec<_> = rows
+      .clone()
+      .map(|y| lanczos_taps(y, scale_y, copy.src_height))
+      .collect();
+    let source_rows = row_taps.iter().flatten().map(|&(src_y, _)| src_y);
+    let (Some(first_row), Some(last_row)) = (source_rows.clone().min(), source_rows.max()) else {
+      return;
+    };
+    let width = column_taps.len();
+    let mut filtered = vec![[0f32; 4]; (last_row - first_row + 1) as usize * width];
+    for (src_y, row) in (first_row..=last_row).zip(filtered.chunks_exact_mut(width)) {
+      for (sum, taps) in row.iter_mut().zip(&column_taps) {
+        for &(src_x, weight) in taps {
+          if let Some(pixel) = source.pixel(copy.src_x + src_x, copy.src_y + src_y) {
+            for (total, value) in sum.iter_mut().zip(pixel) {
+              *total += value as f32 * weight;
+            }
+          }
+        }
+      }
+    }
+
+    for (y, taps_y) in rows.zip(&row_taps) {
+      for (column, x) in columns.clone().enumerate() {
+        let mut sum = [0f32; 4];
+        for &(src_y, weight) in taps_y {
+          let pixel = filtered[(src_y - first_row) as usize * width + column];
+          for (total, value) in sum.iter_mut().zip(pixel) {
+            *total += value * weight;
+          }
+        }
+        let dst_idx = region.index(copy.offset_x + x, copy.offset_y + y);
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&quantizer.quantize(x, y, sum));
+        }
+      }
+      quantizer.end_row();
+    }
+    scratch.dither_rows = quantizer.into_rows();
+  }
+}
+
+/// The Lanczos kernel: a sinc windowed by a wider sinc, zero beyond the lobes
+fn lanczos_kernel(x: f32) -> f32 {
+  if x == 0.0 {
+    return 1.0;
+  }
+  if x.abs() >= LANCZOS_LOBES {
+    return 0.0;
+  }
+  let px = std::f32::consts::PI * x;
+  LANCZOS_LOBES * px.sin() * (px / LANCZOS_LOBES).sin() / (px * px)
+}
+
+/// Source pixels and normalized weights behind destination pixel `index`, at
+/// `scale` source pixels per destination pixel along an axis `len` pixels long
+///
+/// The kernel is stretched by the scale so it filters out detail too fine for
+/// the destination. Taps past the edges repeat the edge pixel.
+fn lanczos_taps(index: u32, scale: f32, len: u32) -> Vec<(u32, f32)> {
+  let filter_scale = scale.max(1.0);
+  let center = (index as f32 + 0.5) * scale - 0.5;
+  let radius = LANCZOS_LOBES * filter_scale;
+  let first = (center - radius).ceil() as i64;
+  let last = (center + radius).floor() as i64;
+  let mut taps: Vec<(u32, f32)> = (first..=last)
+    .map(|i| {
+      let weight = lanczos_kernel((i as f32 - center) / filter_scale);
+      (i.clamp(0, len as i64 - 1) as u32, weight)
+    })
+    .filter(|&(_, weight)| weight != 0.0)
+    .collect();
+  let total: f32 = taps.iter().map(|&(_, weight)| weight).sum();
+  for (_, weight) in &mut taps {
+    *weight /= total;
+  }
+  taps
+}
+
+/// Source pixels `start..end` behind destination pixel `index`, at `scale`
+/// source pixels per destination pixel along an axis `len` pixels long
+///
+/// Always covers at least one pixel, so magnified axes pick the nearest one.
+fn box_bounds(index: u32, scale: f32, len: u32) -> (u32, u32) {
+  let start = ((index as f32 * scale) as u32).min(len - 1);
+  let end = (((index + 1) as f32 * scale) as u32).clamp(start + 1, len);
+  (start, end)
+}
+
+/// Columns and rows of the scaled image that land inside the region and its clip
+///
+/// Both ranges are relative to the scaled image's top-left corner. Returns
+/// `None` when nothing would be drawn.
+pub(crate) fn visible_span(
+  region: FrameRegion,
+  copy: ScaledCopy,
+) -> Option<(Range<u32>, Range<u32>)> {
+  if copy.src_width == 0 || copy.src_height == 0 {
+    return None;
+  }
+  let width = copy
+    .scaled_width
+    .min(region.width.saturating_sub(copy.offset_x));
+  let height = copy
+    .scaled_height
+    .min(region.height.saturating_sub(copy.offset_y));
+  let (mut columns, mut rows) = (0..width, 0..height);
+  if let Some(clip) = region.clip {
+    let clip_x = clip.x.max(0) as u32;
+    let clip_y = clip.y.max(0) as u32;
+    columns.start = clip_x.saturating_sub(copy.offset_x);
+    columns.end = width.min((clip_x + clip.width).saturating_sub(copy.offset_x));
+    rows.start = clip_y.saturating_sub(copy.offset_y);
+    rows.end = height.min((clip_y + clip.height).saturating_sub(copy.offset_y));
+  }
+  (!columns.is_empty() && !rows.is_empty()).then_some((columns, rows))
+}
+
+/// All scalers that can be selected by name
+pub(crate) fn builtin_scalers() -> Vec<Arc<dyn Scaler>> {
+  vec![
+    Arc::new(NearestScaler),
+    Arc::new(BilinearScaler),
+    Arc::new(AreaAverageScaler),
+    Arc::new(LanczosScaler),
+  ]
+}
+
+/// Finds a registered scaler by name
+pub(crate) fn find_scaler(name: &str) -> Option<Arc<dyn Scaler>> {
+  builtin_scalers()
+    .into_iter()
+    .find(|scaler| scaler.name() == name)
+}
+
+/// Default scaler implementing a filter mode
+pub(crate) fn scaler_for_filter(filter: FilterMode) -> &'static dyn Scaler {
+  match filter {
+    FilterMode::Nearest => &NearestScaler,
+    FilterMode::Bilinear => &BilinearScaler,
+    FilterMode::AreaAverage => &AreaAverageScaler,
+    FilterMode::Lanczos => &LanczosScaler,
+  }
+}
+
+/// Returns the names of the scalers that can be passed to `PixelRenderer.setScaler`
+#[napi]
+pub fn available_scalers() -> Vec<String> {
+  builtin_scalers()
+    .iter()
+    .map(|scaler| scaler.name().to_string())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tao::render::FrameSize;
+  use crate::wry::structs::Rect;
+
+  #[test]
+  fn test_find_scaler_by_name() {
+    assert_eq!(find_scaler("nearest").unwrap().name(), "nearest");
+    assert_eq!(find_scaler("bilinear").unwrap().name(), "bilinear");
+    assert!(find_scaler("bicubic").is_none());
+  }
+
+  #[test]
+  fn test_available_scalers_lists_builtins() {
+    assert_eq!(
+      available_scalers(),
+      vec!["nearest", "bilinear", "area-average", "lanczos"]
+    );
+  }
+
+  #[test]
+  fn test_box_bounds_cover_the_source() {
+    // 10 source pixels into 4: boxes of 2.5 pixels, truncated
+    let boxes: Vec<_> = (0..4).map(|i| box_bounds(i, 2.5, 10)).collect();
+    assert_eq!(boxes, vec![(0, 2), (2, 5), (5, 7), (7, 10)]);
+    // Magnified axes cover the nearest pixel
+    assert_eq!(box_bounds(3, 0.5, 2), (1, 2));
+  }
+
+  #[test]
+  fn test_lanczos_taps_are_normalized_and_clamped() {
+    let taps = lanczos_taps(0, 4.0, 16);
+    let total: f32 = taps.iter().map(|&(_, weight)| weight).sum();
+    assert!((total - 1.0).abs() < 1e-5);
+    assert!(taps.iter().all(|&(i, _)| i < 16));
+    // The edge pixel repeats for taps before the start
+    assert!(taps.iter().filter(|&&(i, _)| i == 0).count() > 1);
+    assert_eq!(lanczos_kernel(0.0), 1.0);
+    assert_eq!(lanczos_kernel(3.0), 0.0);
+  }
+
+  #[test]
+  fn test_visible_span_clips_to_region() {
+    let region = FrameRegion::full(FrameSize::packed(10, 10));
+    let copy = ScaledCopy {
+      src_x: 0,
+      src_y: 0,
+      src_width: 4,
+      src_height: 4,
+      offset_x: 6,
+      offset_y: 8,
+      scaled_width: 8,
+      scaled_height: 8,
+    };
+    assert_eq!(visible_span(region, copy), Some((0..4, 0..2)));
+    let clipped = FrameRegion {
+      clip: Some(Rect {
+        x: 7,
+        y: 0,
+        width: 2,
+        height: 9,
+      }),
+      ..region
+    };
+    assert_eq!(visible_span(clipped, copy), Some((1..3, 0..1)));
+    assert_eq!(
+      visible_span(
+        region,
+        ScaledCopy {
+          offset_x: 10,
+          ..copy
+        }
+      ),
+      None
+    );
+    assert_eq!(
+      visible_span(
+        region,
+        ScaledCopy {
+          src_width: 0,
+          ..copy
+        }
+      ),
+      None
+    );
+  }
+}
@@ -3,7 +3,201 @@
 //! This module provides functions for calculating scaled dimensions
 //! and offsets based on different scaling modes.
 
-use crate::tao::enums::ScaleMode;
+use crate::tao::enums::{Anchor, ScaleMode};
+use napi_derive::napi;
+use std::fmt;
+use std::str::FromStr;
+
+/// Default relative aspect ratio difference below which `Auto` stretches
+pub const DEFAULT_AUTO_ASPECT_TOLERANCE: f64 = 0.05;
+
+/// Canonical config names of the scale modes
+const SCALE_MODE_NAMES: &[(ScaleMode, &str)] = &[
+  (ScaleMode::Stretch, "stretch"),
+  (ScaleMode::Fit, "fit"),
+  (ScaleMode::Fill, "fill"),
+  (ScaleMode::Integer, "integer"),
+  (ScaleMode::None, "none"),
+  (ScaleMode::Auto, "auto"),
+  (ScaleMode::FitDownStretchUp, "fit-down-stretch-up"),
+  (ScaleMode::Tile, "tile"),
+  (ScaleMode::IntegerFit, "integer-fit"),
+  (ScaleMode::FitWidth, "fit-width"),
+  (ScaleMode::FitHeight, "fit-height"),
+  (ScaleMode::IntegerTile, "integer-tile"),
+];
+
+/// Lowercases a name and drops word separators, so "FitDownStretchUp",
+/// "fit_down_stretch_up" and "fit-down-stretch-up" compare equal
+fn normalize_name(name: &str) -> String {
+  name
+    .chars()
+    .filter(|c| !matches!(c, '-' | '_' | ' '))
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+impl FromStr for ScaleMode {
+  type Err = napi::Error;
+
+  /// Parses a scale mode name, ignoring case and word separators
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    let normalized = normalize_name(name.trim());
+    SCALE_MODE_NAMES
+      .iter()
+      .find(|(_, canonical)| normalize_name(canonical) == normalized)
+      .map(|&(mode, _)| mode)
+      .ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Unknown scale mode: {}", name),
+        )
+      })
+  }
+}
+
+impl fmt::Display for ScaleMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = SCALE_MODE_NAMES
+      .iter()
+      .find(|(mode, _)| mode == self)
+      .map_or("unknown", |&(_, name)| name);
+    f.write_str(name)
+  }
+}
+
+/// Parses a scale mode name from a config, e.g. "fit" or "FitDownStretchUp"
+///
+/// Case and word separators ("-", "_", spaces) are ignored. Fails for unknown names.
+#[napi]
+pub fn scale_mode_from_string(name: String) -> napi::Result<ScaleMode> {
+  name.parse()
+}
+
+/// Returns the canonical config name of a scale mode, e.g. "fit-down-stretch-up"
+#[napi]
+pub fn scale_mode_to_string(mode: ScaleMode) -> String {
+  mode.to_string()
+}
+
+/// Returns the canonical config names of every supported scale mode, e.g. for
+/// building a settings menu; each parses with `scaleModeFromString`
+#[napi]
+pub fn supported_scale_modes() -> Vec<String> {
+  SCALE_MODE_NAMES
+    .iter()
+    .map(|&(_, name)| name.to_string())
+    .collect()
+}
+
+/// Picks the concrete scale mode `Auto` uses for the given sizes
+///
+/// Returns `Stretch` when the buffer and window aspect ratios differ by at most
+/// `tolerance` (relative, e.g. 0.05 = 5%), so near-matching content fills the
+/// window without visible distortion. Otherwise returns `Fit` to avoid
+/// distorting content. Empty sizes resolve to `Fit`.
+pub fn resolve_auto_mode(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  tolerance: f64,
+) -> ScaleMode {
+  if buffer_width == 0 || buffer_height == 0 || window_width == 0 || window_height == 0 {
+    return ScaleMode::Fit;
+  }
+  let buffer_aspect = buffer_width as f64 / buffer_height as f64;
+  let window_aspect = window_width as f64 / window_height as f64;
+  if (buffer_aspect / window_aspect - 1.0).abs() <= tolerance {
+    ScaleMode::Stretch
+  } else {
+    ScaleMode::Fit
+  }
+}
+
+/// Picks the concrete scale mode `FitDownStretchUp` uses for the given sizes
+///
+/// Returns `Fit` when the buffer exceeds the window in either dimension and
+/// `Stretch` otherwise.
+pub fn resolve_fit_down_stretch_up(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+) -> ScaleMode {
+  if buffer_width > window_width || buffer_height > window_height {
+    ScaleMode::Fit
+  } else {
+    ScaleMode::Stretch
+  }
+}
+
+/// Calculates Stretch dimensions with the aspect distortion capped at `max_skew`
+///
+/// With per-axis scales `sx = window_width / buffer_width` and
+/// `sy = window_height / buffer_height`, the skew is `max(sx, sy) / min(sx, sy) - 1`.
+/// Up to `max_skew` this is a plain Stretch filling the window; beyond it the
+/// larger scale is reduced to `min(sx, sy) * (1 + max_skew)` and the image is
+/// centered with bars on that axis. A `max_skew` of 0 is the same as Fit.
+///
+/// Returns a tuple of (offset_x, offset_y, scaled_width, scaled_height)
+pub fn calculate_capped_stretch(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  max_skew: f64,
+) -> (u32, u32, u32, u32) {
+  if buffer_width == 0 || buffer_height == 0 || window_width == 0 || window_height == 0 {
+    return (0, 0, window_width, window_height);
+  }
+  let scale_x = window_width as f64 / buffer_width as f64;
+  let scale_y = window_height as f64 / buffer_height as f64;
+  let max_scale = scale_x.min(scale_y) * (1.0 + max_skew);
+  let scaled_width = if scale_x > max_scale {
+    (buffer_width as f64 * max_scale) as u32
+  } else {
+    window_width
+  };
+  let scaled_height = if scale_y > max_scale {
+    (buffer_height as f64 * max_scale) as u32
+  } else {
+    window_height
+  };
+  let offset_x = (window_width - scaled_width) / 2;
+  let offset_y = (window_height - scaled_height) / 2;
+  (offset_x, offset_y, scaled_width, scaled_height)
+}
+
+/// Start and length of a span `length` pixels long (possibly fractional)
+/// centered in `outer` pixels, with both ends rounded to the nearest pixel
+///
+/// The gaps on either side differ by at most one pixel and follow the exact
+/// center, unlike truncating the length and then halving the remainder.
+pub fn balanced_span(outer: u32, length: f64) -> (u32, u32) {
+  let length = length.clamp(0.0, outer as f64);
+  let gap = (outer as f64 - length) / 2.0;
+  let start = gap.round();
+  let end = (gap + length).round();
+  (start as u32, (end - start) as u32)
+}
+
+/// Calculates Fit dimensions with the bars balanced by [`balanced_span`]
+///
+/// Returns a tuple of (offset_x, offset_y, scaled_width, scaled_height)
+pub fn calculate_balanced_fit(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+) -> (u32, u32, u32, u32) {
+  let scale_x = window_width as f64 / buffer_width as f64;
+  let scale_y = window_height as f64 / buffer_height as f64;
+  let scale = scale_x.min(scale_y);
+  let (offset_x, scaled_width) = balanced_span(window_width, buffer_width as f64 * scale);
+  let (offset_y, scaled_height) = balanced_span(window_height, buffer_height as f64 * scale);
+  (offset_x, offset_y, scaled_width, scaled_height)
+}
 
 /// Calculates scaled dimensions based on the render options
 ///
@@ -14,7 +208,8 @@ use crate::tao::enums::ScaleMode;
 /// * `buffer_height` - Height of the source buffer in pixels
 /// * `window_width` - Width of the target window in pixels
 /// * `window_height` - Height of the target window in pixels
-/// * `scale_mode` - The scaling mode to use
+/// * `scale_mode` - The scaling mode to use (`Auto` uses
+///   [`DEFAULT_AUTO_ASPECT_TOLERANCE`]; resolve it first for a custom tolerance)
 pub fn calculate_scaled_dimensions(
   buffer_width: u32,
   buffer_height: u32,
@@ -23,7 +218,29 @@ pub fn calculate_scaled_dimensions(
   scale_mode: ScaleMode,
 ) -> (u32, u32, u32, u32) {
   match scale_mode {
-    ScaleMode::Stretch => (0, 0, window_width, window_height),
+    ScaleMode::Auto => calculate_scaled_dimensions(
+      buffer_width,
+      buffer_height,
+      window_width,
+      window_height,
+      resolve_auto_mode(
+        buffer_width,
+        buffer_height,
+        window_width,
+        window_height,
+        DEFAULT_AUTO_ASPECT_TOLERANCE,
+      ),
+    ),
+    ScaleMode::FitDownStretchUp => calculate_scaled_dimensions(
+      buffer_width,
+      buffer_height,
+      window_width,
+      window_height,
+      resolve_fit_down_stretch_up(buffer_width, buffer_height, window_width, window_height),
+    ),
+    ScaleMode::Stretch | ScaleMode::Tile | ScaleMode::IntegerTile => {
+      (0, 0, window_width, window_height)
+    }
     ScaleMode::Fit => {
       let scale_x = window_width as f64 / buffer_width as f64;
       let scale_y = window_height as f64 / buffer_height as f64;
@@ -48,16 +265,42 @@ pub fn calculate_scaled_dimensions(
       (offset_x, offset_y, scaled_width, scaled_height)
     }
     ScaleMode::Integer => {
-      let scale_x = window_width as f64 / buffer_width as f64;
-      let scale_y = window_height as f64 / buffer_height as f64;
-      let scale = scale_x.min(scale_y).floor() as u32;
-      let scale = scale.max(1);
+      let scale = integer_scale(buffer_width, buffer_height, window_width, window_height);
       let scaled_width = buffer_width * scale;
       let scaled_height = buffer_height * scale;
       let offset_x = (window_width.saturating_sub(scaled_width)) / 2;
       let offset_y = (window_height.saturating_sub(scaled_height)) / 2;
       (offset_x, offset_y, scaled_width, scaled_height)
     }
+    ScaleMode::IntegerFit => {
+      let scale_x = window_width / buffer_width;
+      let scale_y = window_height / buffer_height;
+      let (scaled_width, scaled_height) = match scale_x.min(scale_y) {
+        0 => {
+          // Too large at 1x: shrink by the smallest divisor that fits
+          let divisor = buffer_width
+            .div_ceil(window_width.max(1))
+            .max(buffer_height.div_ceil(window_height.max(1)));
+          (buffer_width / divisor, buffer_height / divisor)
+        }
+        scale => (buffer_width * scale, buffer_height * scale),
+      };
+      let offset_x = (window_width.saturating_sub(scaled_width)) / 2;
+      let offset_y = (window_height.saturating_sub(scaled_height)) / 2;
+      (offset_x, offset_y, scaled_width, scaled_height)
+    }
+    ScaleMode::FitWidth => {
+      let scale = window_width as f64 / buffer_width as f64;
+      let scaled_height = (buffer_height as f64 * scale) as u32;
+      let offset_y = (window_height.saturating_sub(scaled_height)) / 2;
+      (0, offset_y, window_width, scaled_height)
+    }
+    ScaleMode::FitHeight => {
+      let scale = window_height as f64 / buffer_height as f64;
+      let scaled_width = (buffer_width as f64 * scale) as u32;
+      let offset_x = (window_width.saturating_sub(scaled_width)) / 2;
+      (offset_x, 0, scaled_width, window_height)
+    }
     ScaleMode::None => {
       let offset_x = (window_width.saturating_sub(buffer_width)) / 2;
       let offset_y = (window_height.saturating_sub(buffer_height)) / 2;
@@ -66,10 +309,337 @@ pub fn calculate_scaled_dimensions(
   }
 }
 
+/// Calculates scaled dimensions with signed, centered offsets
+///
+/// [`calculate_scaled_dimensions`] clamps offsets at 0, which loses where an
+/// image larger than the window sits. Here modes that crop (`Fill`, `None`,
+/// `FitWidth`, `FitHeight`, and `Integer` at its minimum scale) report the
+/// negative position of the image's top-left corner, so the overflow is split
+/// evenly between both sides (the extra pixel of an odd overflow goes to the
+/// right or bottom).
+///
+/// Returns a tuple of (offset_x, offset_y, scaled_width, scaled_height)
+pub fn calculate_scaled_placement(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  scale_mode: ScaleMode,
+) -> (i64, i64, u32, u32) {
+  let (_, _, scaled_width, scaled_height) = calculate_scaled_dimensions(
+    buffer_width,
+    buffer_height,
+    window_width,
+    window_height,
+    scale_mode,
+  );
+  let (offset_x, offset_y) = anchor_position(
+    window_width,
+    window_height,
+    scaled_width,
+    scaled_height,
+    Anchor::Center,
+  );
+  (offset_x, offset_y, scaled_width, scaled_height)
+}
+
+impl Anchor {
+  /// Horizontal and vertical alignment in halves: 0 = start, 1 = center, 2 = end
+  fn halves(self) -> (i64, i64) {
+    match self {
+      Anchor::TopLeft => (0, 0),
+      Anchor::Top => (1, 0),
+      Anchor::TopRight => (2, 0),
+      Anchor::Left => (0, 1),
+      Anchor::Center => (1, 1),
+      Anchor::Right => (2, 1),
+      Anchor::BottomLeft => (0, 2),
+      Anchor::Bottom => (1, 2),
+      Anchor::BottomRight => (2, 2),
+    }
+  }
+}
+
+/// Largest integer scale at which the buffer fits the window (at least 1),
+/// used by `Integer` and `IntegerTile`
+pub fn integer_scale(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+) -> u32 {
+  let scale_x = window_width as f64 / buffer_width as f64;
+  let scale_y = window_height as f64 / buffer_height as f64;
+  (scale_x.min(scale_y).floor() as u32).max(1)
+}
+
+/// Whether a scale mode places the image with an anchor (it may leave bars)
+pub fn is_anchored_mode(scale_mode: ScaleMode) -> bool {
+  matches!(
+    scale_mode,
+    ScaleMode::Fit
+      | ScaleMode::Integer
+      | ScaleMode::IntegerFit
+      | ScaleMode::None
+      | ScaleMode::FitWidth
+      | ScaleMode::FitHeight
+  )
+}
+
+/// Position of an `image_width` x `image_height` image aligned to `anchor`
+/// within a `window_width` x `window_height` target
+///
+/// Negative when the image is larger than the target and gets cropped.
+/// `Anchor::Center` matches the centering of [`calculate_scaled_dimensions`].
+pub fn anchor_position(
+  window_width: u32,
+  window_height: u32,
+  image_width: u32,
+  image_height: u32,
+  anchor: Anchor,
+) -> (i64, i64) {
+  let (halves_x, halves_y) = anchor.halves();
+  (
+    (window_width as i64 - image_width as i64) * halves_x / 2,
+    (window_height as i64 - image_height as i64) * halves_y / 2,
+  )
+}
+
+/// Maps buffer pixel coordinates to target (window or viewport) coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferTransform {
+  /// Horizontal scale from buffer to target pixels
+  pub scale_x: f64,
+  /// Vertical scale from buffer to target pixels
+  pub scale_y: f64,
+  /// Target X of the buffer's left edge (negative when cropped)
+  pub offset_x: f64,
+  /// Target Y of the buffer's top edge (negative when cropped)
+  pub offset_y: f64,
+}
+
+impl BufferTransform {
+  /// Maps a point in buffer coordinates to target coordinates
+  pub fn map_point(&self, x: f64, y: f64) -> (f64, f64) {
+    (
+      self.offset_x + x * self.scale_x,
+      self.offset_y + y * self.scale_y,
+    )
+  }
+}
+
+/// Calculates how buffer pixels land in the target for the given scale mode
+///
+/// Unlike [`calculate_scaled_dimensions`], offsets may be negative so that
+/// cropping modes (`Fill`, `None`) map coordinates exactly.
+pub fn calculate_buffer_transform(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  scale_mode: ScaleMode,
+) -> BufferTransform {
+  let (bw, bh) = (buffer_width.max(1) as f64, buffer_height.max(1) as f64);
+  let (ww, wh) = (window_width as f64, window_height as f64);
+  match scale_mode {
+    ScaleMode::Auto => calculate_buffer_transform(
+      buffer_width,
+      buffer_height,
+      window_width,
+      window_height,
+      resolve_auto_mode(
+        buffer_width,
+        buffer_height,
+        window_width,
+        window_height,
+        DEFAULT_AUTO_ASPECT_TOLERANCE,
+      ),
+    ),
+    ScaleMode::FitDownStretchUp => calculate_buffer_transform(
+      buffer_width,
+      buffer_height,
+      window_width,
+      window_height,
+      resolve_fit_down_stretch_up(buffer_width, buffer_height, window_width, window_height),
+    ),
+    ScaleMode::Stretch => BufferTransform {
+      scale_x: ww / bw,
+      scale_y: wh / bh,
+      offset_x: 0.0,
+      offset_y: 0.0,
+    },
+    ScaleMode::Fill => {
+      let scale = (ww / bw).max(wh / bh);
+      BufferTransform {
+        scale_x: scale,
+        scale_y: scale,
+        offset_x: (ww - bw * scale) / 2.0,
+        offset_y: (wh - bh * scale) / 2.0,
+      }
+    }
+    // The first tile, ignoring the tile offset
+    ScaleMode::Tile => BufferTransform {
+      scale_x: 1.0,
+      scale_y: 1.0,
+      offset_x: 0.0,
+      offset_y: 0.0,
+    },
+    ScaleMode::IntegerTile => {
+      let scale = integer_scale(buffer_width, buffer_height, window_width, window_height) as f64;
+      BufferTransform {
+        scale_x: scale,
+        scale_y: scale,
+        offset_x: 0.0,
+        offset_y: 0.0,
+      }
+    }
+    ScaleMode::None => BufferTransform {
+      scale_x: 1.0,
+      scale_y: 1.0,
+      offset_x: window_width.saturating_sub(buffer_width) as f64 / 2.0
+        - buffer_width.saturating_sub(window_width) as f64 / 2.0,
+      offset_y: window_height.saturating_sub(buffer_height) as f64 / 2.0
+        - buffer_height.saturating_sub(window_height) as f64 / 2.0,
+    },
+    // Placed like the renderer draws them, which may crop one axis
+    ScaleMode::FitWidth | ScaleMode::FitHeight => {
+      let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_placement(
+        buffer_width,
+        buffer_height,
+        window_width,
+        window_height,
+        scale_mode,
+      );
+      BufferTransform {
+        scale_x: scaled_width as f64 / bw,
+        scale_y: scaled_height as f64 / bh,
+        offset_x: offset_x as f64,
+        offset_y: offset_y as f64,
+      }
+    }
+    ScaleMode::Fit | ScaleMode::Integer | ScaleMode::IntegerFit => {
+      let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions(
+        buffer_width,
+        buffer_height,
+        window_width,
+        window_height,
+        scale_mode,
+      );
+      BufferTransform {
+        scale_x: scaled_width as f64 / bw,
+        scale_y: scaled_height as f64 / bh,
+        offset_x: offset_x as f64,
+        offset_y: offset_y as f64,
+      }
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_supported_scale_modes_parse() {
+    let names = supported_scale_modes();
+    assert!(names.contains(&"integer-tile".to_string()));
+    for name in names {
+      assert_eq!(name.parse::<ScaleMode>().unwrap().to_string(), name);
+    }
+  }
+
+  #[test]
+  fn test_balanced_fit_centers_the_exact_image() {
+    // 3x2 at 2.5x is 7.5 pixels wide: truncating leaves bars of 1 and 2
+    assert_eq!(
+      calculate_scaled_dimensions(3, 2, 10, 5, ScaleMode::Fit),
+      (1, 0, 7, 5)
+    );
+    assert_eq!(calculate_balanced_fit(3, 2, 10, 5), (1, 0, 8, 5));
+    assert_eq!(calculate_balanced_fit(3, 2, 11, 5), (2, 0, 7, 5));
+    // Exact fits are unchanged
+    assert_eq!(
+      calculate_balanced_fit(800, 600, 1920, 1080),
+      calculate_scaled_dimensions(800, 600, 1920, 1080, ScaleMode::Fit)
+    );
+    assert_eq!(balanced_span(0, 4.0), (0, 0));
+  }
+
+  #[test]
+  fn test_fit_width_and_height_with_wide_buffer_in_tall_window() {
+    // Fit the width: 100x50, letterboxed above and below
+    assert_eq!(
+      calculate_scaled_dimensions(200, 100, 100, 200, ScaleMode::FitWidth),
+      (0, 75, 100, 50)
+    );
+    // Fit the height: 400x200, cropped left and right
+    assert_eq!(
+      calculate_scaled_dimensions(200, 100, 100, 200, ScaleMode::FitHeight),
+      (0, 0, 400, 200)
+    );
+    let t = calculate_buffer_transform(200, 100, 100, 200, ScaleMode::FitHeight);
+    assert_eq!((t.scale_x, t.scale_y), (2.0, 2.0));
+    assert_eq!((t.offset_x, t.offset_y), (-150.0, 0.0));
+    assert_eq!(
+      "fit-width".parse::<ScaleMode>().unwrap(),
+      ScaleMode::FitWidth
+    );
+  }
+
+  #[test]
+  fn test_anchor_position() {
+    assert_eq!(anchor_position(100, 50, 40, 20, Anchor::TopLeft), (0, 0));
+    assert_eq!(
+      anchor_position(100, 50, 40, 20, Anchor::BottomRight),
+      (60, 30)
+    );
+    assert_eq!(anchor_position(100, 50, 40, 20, Anchor::Top), (30, 0));
+    // Larger images are cropped from the far side
+    assert_eq!(anchor_position(10, 10, 15, 10, Anchor::Right), (-5, 0));
+  }
+
+  #[test]
+  fn test_center_anchor_matches_scaled_dimensions() {
+    for &(window, buffer) in &[((101, 51), (40, 20)), ((333, 200), (64, 48))] {
+      let (offset_x, offset_y, scaled_width, scaled_height) =
+        calculate_scaled_dimensions(buffer.0, buffer.1, window.0, window.1, ScaleMode::Fit);
+      assert_eq!(
+        anchor_position(
+          window.0,
+          window.1,
+          scaled_width,
+          scaled_height,
+          Anchor::Center
+        ),
+        (offset_x as i64, offset_y as i64)
+      );
+    }
+    // Cropped centering matches the crop of ScaleMode.None
+    assert_eq!(anchor_position(10, 10, 15, 13, Anchor::Center), (-2, -1));
+  }
+
+  #[test]
+  fn test_scale_mode_names_round_trip() {
+    for &(mode, name) in SCALE_MODE_NAMES {
+      assert_eq!(scale_mode_from_string(mode.to_string()).unwrap(), mode);
+      assert_eq!(scale_mode_to_string(mode), name);
+    }
+  }
+
+  #[test]
+  fn test_scale_mode_from_string_ignores_case_and_separators() {
+    assert_eq!("FIT".parse::<ScaleMode>().unwrap(), ScaleMode::Fit);
+    assert_eq!(
+      "FitDownStretchUp".parse::<ScaleMode>().unwrap(),
+      ScaleMode::FitDownStretchUp
+    );
+    assert_eq!(
+      "fit_down_stretch_up".parse::<ScaleMode>().unwrap(),
+      ScaleMode::FitDownStretchUp
+    );
+    assert!("zoom".parse::<ScaleMode>().is_err());
+  }
+
   // ============================================================================
   // ScaleMode::Fit Tests
   // ============================================================================
@@ -205,6 +775,26 @@ mod tests {
     assert_eq!(offset_y, 0);
   }
 
+  #[test]
+  fn test_fill_crops_wide_buffer_evenly() {
+    // 200x100 buffer in a 100x100 window: 200x100 at 1x, 50 pixels cropped per side
+    assert_eq!(
+      calculate_scaled_dimensions(200, 100, 100, 100, ScaleMode::Fill),
+      (0, 0, 200, 100)
+    );
+    assert_eq!(
+      calculate_scaled_placement(200, 100, 100, 100, ScaleMode::Fill),
+      (-50, 0, 200, 100)
+    );
+    let t = calculate_buffer_transform(200, 100, 100, 100, ScaleMode::Fill);
+    assert_eq!((t.offset_x, t.offset_y), (-50.0, 0.0));
+    // Letterboxing modes keep their positive offsets
+    assert_eq!(
+      calculate_scaled_placement(200, 100, 100, 100, ScaleMode::Fit),
+      (0, 25, 100, 50)
+    );
+  }
+
   // ============================================================================
   // ScaleMode::Integer Tests
   // ============================================================================
@@ -256,6 +846,28 @@ mod tests {
     assert_eq!(offset_y, 48); // (768 - 672) / 2
   }
 
+  // ============================================================================
+  // ScaleMode::IntegerFit Tests
+  // ============================================================================
+
+  #[test]
+  fn test_integer_fit_partial_scale() {
+    // Same as Integer while the buffer fits at 1x
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(320, 240, 800, 600, ScaleMode::IntegerFit);
+    assert_eq!((scaled_w, scaled_h), (640, 480));
+    assert_eq!((offset_x, offset_y), (80, 60));
+  }
+
+  #[test]
+  fn test_integer_fit_shrinks_by_divisor() {
+    // 3840x2160 needs a divisor of max(ceil(3840/640), ceil(2160/480)) = 6
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(3840, 2160, 640, 480, ScaleMode::IntegerFit);
+    assert_eq!((scaled_w, scaled_h), (640, 360));
+    assert_eq!((offset_x, offset_y), (0, 60));
+  }
+
   // ============================================================================
   // ScaleMode::None Tests
   // ============================================================================
@@ -293,6 +905,30 @@ mod tests {
     assert_eq!(offset_y, 0); // saturating_sub
   }
 
+  // ============================================================================
+  // ScaleMode::Tile Tests
+  // ============================================================================
+
+  #[test]
+  fn test_tile_covers_window() {
+    assert_eq!(
+      calculate_scaled_dimensions(16, 16, 800, 600, ScaleMode::Tile),
+      (0, 0, 800, 600)
+    );
+  }
+
+  #[test]
+  fn test_integer_tile_covers_window_at_integer_scale() {
+    assert_eq!(
+      calculate_scaled_dimensions(16, 16, 100, 70, ScaleMode::IntegerTile),
+      (0, 0, 100, 70)
+    );
+    let transform = calculate_buffer_transform(16, 16, 100, 70, ScaleMode::IntegerTile);
+    assert_eq!((transform.scale_x, transform.scale_y), (4.0, 4.0));
+    // Larger than the window: tiles stay at 1x
+    assert_eq!(integer_scale(200, 200, 100, 70), 1);
+  }
+
   // ============================================================================
   // ScaleMode::Stretch Tests
   // ============================================================================
@@ -318,6 +954,107 @@ mod tests {
     assert_eq!(offset_y, 0);
   }
 
+  // ============================================================================
+  // ScaleMode::Auto Tests
+  // ============================================================================
+
+  #[test]
+  fn test_auto_stretches_close_aspect() {
+    // 1920x1080 (1.778) vs 1280x768 (1.667) differ by ~6.7%
+    assert_eq!(
+      resolve_auto_mode(1920, 1080, 1280, 768, 0.1),
+      ScaleMode::Stretch
+    );
+    assert_eq!(
+      resolve_auto_mode(1920, 1080, 1280, 768, 0.05),
+      ScaleMode::Fit
+    );
+  }
+
+  #[test]
+  fn test_auto_fits_different_aspect() {
+    // 4:3 buffer in a 16:9 window
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(800, 600, 1920, 1080, ScaleMode::Auto);
+    assert_eq!(
+      (offset_x, offset_y, scaled_w, scaled_h),
+      (240, 0, 1440, 1080)
+    );
+  }
+
+  #[test]
+  fn test_auto_default_tolerance_stretches_near_match() {
+    // 1920x1080 into 1920x1060 is within 2%
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(1920, 1080, 1920, 1060, ScaleMode::Auto);
+    assert_eq!((offset_x, offset_y, scaled_w, scaled_h), (0, 0, 1920, 1060));
+  }
+
+  #[test]
+  fn test_auto_empty_sizes_resolve_to_fit() {
+    assert_eq!(resolve_auto_mode(0, 0, 800, 600, 0.05), ScaleMode::Fit);
+    assert_eq!(resolve_auto_mode(800, 600, 800, 0, 0.05), ScaleMode::Fit);
+  }
+
+  // ============================================================================
+  // ScaleMode::FitDownStretchUp Tests
+  // ============================================================================
+
+  #[test]
+  fn test_fit_down_stretch_up_downscales_with_letterbox() {
+    // 1920x1080 thumbnail in a 400x400 slot
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(1920, 1080, 400, 400, ScaleMode::FitDownStretchUp);
+    assert_eq!((offset_x, offset_y, scaled_w, scaled_h), (0, 87, 400, 225));
+  }
+
+  #[test]
+  fn test_fit_down_stretch_up_stretches_small_content() {
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions(100, 50, 400, 400, ScaleMode::FitDownStretchUp);
+    assert_eq!((offset_x, offset_y, scaled_w, scaled_h), (0, 0, 400, 400));
+  }
+
+  #[test]
+  fn test_fit_down_stretch_up_one_dimension_too_large() {
+    // Taller than the slot, narrower than it
+    assert_eq!(
+      resolve_fit_down_stretch_up(100, 500, 400, 400),
+      ScaleMode::Fit
+    );
+    assert_eq!(
+      resolve_fit_down_stretch_up(400, 400, 400, 400),
+      ScaleMode::Stretch
+    );
+  }
+
+  // ============================================================================
+  // Capped Stretch Tests
+  // ============================================================================
+
+  #[test]
+  fn test_capped_stretch_within_skew_fills() {
+    // sx = 1.05, sy = 1.0: skew 0.05 is within 0.1
+    assert_eq!(
+      calculate_capped_stretch(400, 300, 420, 300, 0.1),
+      (0, 0, 420, 300)
+    );
+  }
+
+  #[test]
+  fn test_capped_stretch_limits_wider_axis() {
+    // sx = 2.0, sy = 1.0: width is capped at 400 * 1.25 = 500
+    assert_eq!(
+      calculate_capped_stretch(400, 300, 800, 300, 0.25),
+      (150, 0, 500, 300)
+    );
+    // A skew of 0 fits
+    assert_eq!(
+      calculate_capped_stretch(400, 300, 800, 300, 0.0),
+      calculate_scaled_dimensions(400, 300, 800, 300, ScaleMode::Fit)
+    );
+  }
+
   // ============================================================================
   // Edge Cases
   // ============================================================================
@@ -389,4 +1126,35 @@ mod tests {
     assert_eq!(offset_x, 397); // (800 - 6) / 2
     assert_eq!(offset_y, 0);
   }
+
+  // ============================================================================
+  // Buffer Transform Tests
+  // ============================================================================
+
+  #[test]
+  fn test_transform_fit_matches_scaled_dimensions() {
+    let t = calculate_buffer_transform(800, 600, 1920, 1080, ScaleMode::Fit);
+    assert_eq!(t.map_point(0.0, 0.0), (240.0, 0.0));
+    assert_eq!(t.map_point(800.0, 600.0), (1680.0, 1080.0));
+  }
+
+  #[test]
+  fn test_transform_stretch() {
+    let t = calculate_buffer_transform(100, 100, 200, 50, ScaleMode::Stretch);
+    assert_eq!(t.map_point(50.0, 50.0), (100.0, 25.0));
+  }
+
+  #[test]
+  fn test_transform_fill_has_negative_offset() {
+    // 200x100 buffer filling a 100x100 window crops 50px from each side
+    let t = calculate_buffer_transform(200, 100, 100, 100, ScaleMode::Fill);
+    assert_eq!(t.map_point(0.0, 0.0), (-50.0, 0.0));
+    assert_eq!(t.map_point(100.0, 50.0), (50.0, 50.0));
+  }
+
+  #[test]
+  fn test_transform_none_centers_and_crops() {
+    let t = calculate_buffer_transform(100, 400, 300, 200, ScaleMode::None);
+    assert_eq!(t.map_point(0.0, 0.0), (100.0, -100.0));
+  }
 }
@@ -0,0 +1,99 @@
+//! Scheduled presentation
+//!
+//! Lets players present frames at target times on a shared monotonic clock
+//! (e.g. derived from an audio clock), for A/V synced video playback.
+
+use napi_derive::napi;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+/// Default lateness after which a scheduled frame is dropped (one 60 Hz frame)
+pub const DEFAULT_MAX_LATENESS_MICROS: u32 = 16_667;
+
+/// Furthest ahead a frame may be scheduled (six 60 Hz frames)
+pub const MAX_LEAD_MICROS: u32 = 100_000;
+
+/// Start of the clock used for presentation target times
+static CLOCK_ORIGIN: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Returns the current time on the presentation clock, in microseconds
+///
+/// Target times passed to `PixelRenderer.presentAt` are on this clock. It is
+/// monotonic and starts near zero when the module is first used.
+#[napi]
+pub fn monotonic_time_micros() -> i64 {
+  now_micros()
+}
+
+pub(crate) fn now_micros() -> i64 {
+  CLOCK_ORIGIN.elapsed().as_micros() as i64
+}
+
+/// Converts an instant to microseconds on the presentation clock
+pub(crate) fn instant_micros(instant: Instant) -> i64 {
+  let origin = *CLOCK_ORIGIN;
+  match instant.checked_duration_since(origin) {
+    Some(elapsed) => elapsed.as_micros() as i64,
+    None => -((origin - instant).as_micros() as i64),
+  }
+}
+
+/// What to do with a frame scheduled for a target time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Schedule {
+  /// Wait this long, then present
+  Wait(Duration),
+  /// The target has passed but the frame is still useful; present now
+  Late,
+  /// The target has passed by more than the allowed lateness
+  Drop,
+}
+
+/// Decides how to handle a frame targeted at `target_micros` when it is `now_micros`
+pub(crate) fn schedule(target_micros: i64, now_micros: i64, max_lateness_micros: u32) -> Schedule {
+  let early = target_micros - now_micros;
+  if early >= 0 {
+    Schedule::Wait(Duration::from_micros(early as u64))
+  } else if -early <= max_lateness_micros as i64 {
+    Schedule::Late
+  } else {
+    Schedule::Drop
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_schedule_waits_for_future_target() {
+    assert_eq!(
+      schedule(1_500, 1_000, 100),
+      Schedule::Wait(Duration::from_micros(500))
+    );
+    assert_eq!(schedule(1_000, 1_000, 100), Schedule::Wait(Duration::ZERO));
+  }
+
+  #[test]
+  fn test_schedule_late_within_threshold() {
+    assert_eq!(schedule(1_000, 1_100, 100), Schedule::Late);
+  }
+
+  #[test]
+  fn test_schedule_drops_stale_frame() {
+    assert_eq!(schedule(1_000, 1_101, 100), Schedule::Drop);
+  }
+
+  #[test]
+  fn test_instant_micros_matches_clock() {
+    let before = now_micros();
+    let micros = instant_micros(Instant::now());
+    assert!(micros >= before && micros <= now_micros());
+  }
+
+  #[test]
+  fn test_clock_is_monotonic() {
+    let first = monotonic_time_micros();
+    assert!(monotonic_time_micros() >= first);
+  }
+}
@@ -0,0 +1,51 @@
+//! Per-window scratch memory
+//!
+//! A [`FrameScratch`] holds the working buffers that scaling needs for every
+//! frame. It lives in the window's cached render state, so steady-state
+//! rendering reuses the same allocations instead of making new ones each frame.
+
+use super::dither::ErrorRows;
+use super::BilinearTap;
+
+/// Working buffers reused across the frames drawn into one window
+#[derive(Debug, Default)]
+pub(crate) struct FrameScratch {
+  /// Horizontal sample taps of the bilinear scaler, one per column
+  pub column_taps: Vec<BilinearTap>,
+  /// Error rows carried between rows by Floyd-Steinberg dithering
+  pub dither_rows: ErrorRows,
+  /// Frame contents before drawing, blended under the buffer by `SourceOver`
+  pub backdrop: Vec<u8>,
+}
+
+impl FrameScratch {
+  /// Creates scratch memory for frames `width` pixels wide
+  pub fn for_width(width: u32) -> Self {
+    let mut scratch = Self::default();
+    scratch.resize(width);
+    scratch
+  }
+
+  /// Makes room for frames `width` pixels wide, called when the window resizes
+  ///
+  /// Capacity only grows, so shrinking and growing back doesn't reallocate.
+  pub fn resize(&mut self, width: u32) {
+    // Taps are cleared after every copy, so reserving `width` sets the capacity
+    self.column_taps.reserve(width as usize);
+    self.dither_rows.reserve(width as usize);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_resize_only_grows() {
+    let mut scratch = FrameScratch::for_width(64);
+    assert!(scratch.column_taps.capacity() >= 64);
+    let capacity = scratch.column_taps.capacity();
+    scratch.resize(16);
+    assert_eq!(scratch.column_taps.capacity(), capacity);
+  }
+}
@@ -0,0 +1,130 @@
+//! Asynchronous rendering
+//!
+//! A [`RenderTask`] scales a frame on the libuv thread pool and presents it
+//! back on the JavaScript thread, backing `PixelRenderer.renderAsync`.
+
+use super::error::{into_napi_error, RenderError};
+use super::scratch::FrameScratch;
+use super::{window_lock_error, FrameSize, PixelRenderer, StageTimer};
+use napi::bindgen_prelude::*;
+use napi::{Env, Task};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Scales a buffer off the JavaScript thread, then presents it on completion
+pub struct RenderTask {
+  /// Snapshot of the renderer's settings when the task was created
+  renderer: PixelRenderer,
+  buffer: Buffer,
+  window: Arc<Mutex<crate::tao::structs::NativeWindow>>,
+  window_id: u64,
+  /// Window size the frame is composed for
+  size: FrameSize,
+  min_present_interval: Duration,
+}
+
+impl RenderTask {
+  pub(crate) fn new(
+    renderer: PixelRenderer,
+    buffer: Buffer,
+    window: Arc<Mutex<crate::tao::structs::NativeWindow>>,
+    min_present_interval: Duration,
+  ) -> napi::Result<Self, RenderError> {
+    let (window_id, window_size) = {
+      let guard = window.lock().map_err(|_| window_lock_error())?;
+      (
+        crate::tao::structs::window_id_to_u64(guard.id()),
+        guard.inner_size(),
+      )
+    };
+    Ok(Self {
+      renderer,
+      buffer,
+      window,
+      window_id,
+      size: FrameSize::packed(window_size.width, window_size.height),
+      min_present_interval,
+    })
+  }
+}
+
+impl Task for RenderTask {
+  type Output = Vec<u8>;
+  type JsValue = ();
+
+  /// Composes the frame on a worker thread
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let mut frame = vec![0u8; self.size.width as usize * self.size.height as usize * 4];
+    let mut timer = StageTimer::new(false);
+    let mut scratch = FrameScratch::for_width(self.size.width);
+    self.renderer.draw_frame(
+      &mut frame,
+      &self.buffer,
+      self.size,
+      &mut timer,
+      &mut scratch,
+    );
+    Ok(frame)
+  }
+
+  /// Presents the composed frame on the JavaScript thread
+  fn resolve(&mut self, _env: Env, composed: Self::Output) -> napi::Result<Self::JsValue> {
+    let window = self
+      .window
+      .lock()
+      .map_err(|_| into_napi_error(window_lock_error()))?;
+    let window_size = window.inner_size();
+    self
+      .renderer
+      .render_cached(
+        self.window_id,
+        Some(&window),
+        window_size.width,
+        window_size.height,
+        self.min_present_interval,
+        |frame, size, timer, scratch| {
+          if (size.width, size.height) == (self.size.width, self.size.height) {
+            copy_rows(frame, size, &composed);
+          } else {
+            // The window was resized while composing, so compose again at the new size
+            self
+              .renderer
+              .draw_frame(frame, &self.buffer, size, timer, scratch);
+          }
+        },
+      )
+      .map(|_| ())
+      .map_err(into_napi_error)
+  }
+}
+
+/// Copies a packed frame into a frame with the same dimensions but possibly padded rows
+pub(super) fn copy_rows(frame: &mut [u8], size: FrameSize, packed: &[u8]) {
+  let row_len = size.width as usize * 4;
+  if row_len == 0 {
+    return;
+  }
+  for (row, source) in frame
+    .chunks_mut(size.stride as usize * 4)
+    .zip(packed.chunks_exact(row_len))
+  {
+    row[..row_len].copy_from_slice(source);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_copy_rows_skips_padding() {
+    let mut frame = [0u8; 16];
+    let size = FrameSize {
+      width: 1,
+      height: 2,
+      stride: 2,
+    };
+    copy_rows(&mut frame, size, &[1, 1, 1, 1, 2, 2, 2, 2]);
+    assert_eq!(frame, [1, 1, 1, 1, 0, 0, 0, 0, 2, 2, 2, 2, 0, 0, 0, 0]);
+  }
+}
@@ -0,0 +1,146 @@
+//! Shared render thread
+//!
+//! `PixelRenderer.renderThreaded` hands frames to one process-wide thread that
+//! scales and presents them in submission order, for every window. Surfaces
+//! are still created on the window's thread before a frame is queued, so the
+//! render thread only ever presents to surfaces that already exist. Frames
+//! never own their window: a native window must be destroyed on its own
+//! thread, so the render thread only knows windows by ID.
+
+use super::error::RenderError;
+use super::{render_state_poisoned_error, PixelRenderer};
+use std::sync::mpsc::{channel, sync_channel, Sender, SyncSender};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// A frame waiting to be presented by the render thread
+pub(crate) struct ThreadedFrame {
+  /// Snapshot of the renderer's settings when the frame was queued
+  pub renderer: PixelRenderer,
+  pub buffer: Vec<u8>,
+  pub window_id: u64,
+  /// Window size when the frame was queued, read on the window's thread
+  pub window_width: u32,
+  pub window_height: u32,
+  pub min_present_interval: Duration,
+}
+
+/// Work for the render thread, processed in order
+enum Job {
+  Present(Box<ThreadedFrame>),
+  /// Acknowledged once every job queued before it has finished
+  Flush(SyncSender<()>),
+}
+
+/// Sender feeding the render thread (None until the first frame is queued)
+static RENDER_THREAD: LazyLock<Mutex<Option<Sender<Job>>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Error from the most recent failed present on the render thread
+static LAST_ERROR: LazyLock<Mutex<Option<napi::Error<RenderError>>>> =
+  LazyLock::new(|| Mutex::new(None));
+
+/// Queues a frame for the render thread, starting the thread if needed
+///
+/// The queue is unbounded: frames are never dropped, so producers that outpace
+/// presentation should `flush` now and then.
+pub(crate) fn enqueue(frame: ThreadedFrame) -> napi::Result<(), RenderError> {
+  take_error()?;
+  let mut sender = RENDER_THREAD
+    .lock()
+    .map_err(|_| render_state_poisoned_error())?;
+  if sender.is_none() {
+    *sender = Some(spawn()?);
+  }
+  let job = Job::Present(Box::new(frame));
+  if let Err(failed) = sender.as_ref().unwrap().send(job) {
+    // The thread exited after a panic; start a new one for this frame
+    let restarted = spawn()?;
+    let _ = restarted.send(failed.0);
+    *sender = Some(restarted);
+  }
+  Ok(())
+}
+
+/// Blocks until every frame queued so far has been presented
+///
+/// Returns the error of the most recent failed present, if any, and clears it.
+pub(crate) fn flush() -> napi::Result<(), RenderError> {
+  let acknowledged = {
+    let sender = RENDER_THREAD
+      .lock()
+      .map_err(|_| render_state_poisoned_error())?;
+    sender.as_ref().and_then(|sender| {
+      let (ack, acknowledged) = sync_channel(1);
+      sender.send(Job::Flush(ack)).ok().map(|_| acknowledged)
+    })
+  };
+  if let Some(acknowledged) = acknowledged {
+    // A disconnect means the thread exited, which also ends its queue
+    let _ = acknowledged.recv();
+  }
+  take_error()
+}
+
+/// Returns and clears the last render thread error
+fn take_error() -> napi::Result<(), RenderError> {
+  match LAST_ERROR.lock().ok().and_then(|mut error| error.take()) {
+    Some(error) => Err(error),
+    None => Ok(()),
+  }
+}
+
+/// Starts the render thread
+fn spawn() -> napi::Result<Sender<Job>, RenderError> {
+  let (sender, receiver) = channel::<Job>();
+  std::thread::Builder::new()
+    .name("pixel-render-thread".to_string())
+    .spawn(move || {
+      for job in receiver {
+        match job {
+          Job::Present(frame) => {
+            if let Err(e) = present(&frame) {
+              if let Ok(mut error) = LAST_ERROR.lock() {
+                *error = Some(e);
+              }
+            }
+          }
+          Job::Flush(ack) => {
+            let _ = ack.send(());
+          }
+        }
+      }
+    })
+    .map_err(|e| {
+      RenderError::SurfaceUnavailable.error(format!("Failed to spawn render thread: {}", e))
+    })?;
+  Ok(sender)
+}
+
+/// Presents one queued frame at the window size it was queued with
+fn present(frame: &ThreadedFrame) -> napi::Result<bool, RenderError> {
+  frame.renderer.render_cached(
+    frame.window_id,
+    None,
+    frame.window_width,
+    frame.window_height,
+    frame.min_present_interval,
+    |out, size, timer, scratch| {
+      frame
+        .renderer
+        .draw_frame(out, &frame.buffer, size, timer, scratch);
+    },
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_flush_reports_the_last_error_once() {
+    *LAST_ERROR.lock().unwrap() = Some(RenderError::PresentFailed.error("lost"));
+    let error = flush().unwrap_err();
+    assert_eq!(error.status, RenderError::PresentFailed);
+    assert!(flush().is_ok());
+  }
+}
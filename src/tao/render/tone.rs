@@ -0,0 +1,137 @@
+//! Gamma, brightness and tint adjustment
+//!
+//! A [`ToneCurve`] maps each 8-bit color channel through a 256-entry lookup
+//! table, so adjusting a frame costs one lookup per channel while it is copied.
+//! The table is rebuilt only when the gamma or brightness changes. A tint is
+//! then multiplied into each channel with [`apply_tint`].
+
+/// Lookup table applying gamma, then brightness, to color channels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ToneCurve {
+  gamma: f64,
+  brightness: f64,
+  lut: [u8; 256],
+}
+
+impl ToneCurve {
+  /// Builds the curve, or returns `None` when it would leave every value unchanged
+  ///
+  /// Each channel becomes `255 * (value / 255)^(1 / gamma) * brightness`, so a
+  /// gamma above 1 lifts the midtones.
+  pub fn new(gamma: f64, brightness: f64) -> Option<Self> {
+    if gamma == 1.0 && brightness == 1.0 {
+      return None;
+    }
+    let mut lut = [0u8; 256];
+    for (value, entry) in lut.iter_mut().enumerate() {
+      let level = (value as f64 / 255.0).powf(1.0 / gamma) * brightness;
+      *entry = (level * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    Some(Self {
+      gamma,
+      brightness,
+      lut,
+    })
+  }
+
+  pub fn gamma(&self) -> f64 {
+    self.gamma
+  }
+
+  pub fn brightness(&self) -> f64 {
+    self.brightness
+  }
+
+  /// Adjusts the color channels of an RGBA pixel, leaving alpha alone
+  #[inline]
+  pub fn apply(&self, pixel: [u8; 4]) -> [u8; 4] {
+    [
+      self.lut[pixel[0] as usize],
+      self.lut[pixel[1] as usize],
+      self.lut[pixel[2] as usize],
+      pixel[3],
+    ]
+  }
+}
+
+/// Multiplies each channel of an RGBA pixel, alpha included, by `tint / 255`
+#[inline]
+pub(crate) fn apply_tint(pixel: [u8; 4], tint: [u8; 4]) -> [u8; 4] {
+  let mut out = [0u8; 4];
+  for ((out, value), factor) in out.iter_mut().zip(pixel).zip(tint) {
+    *out = ((value as u32 * factor as u32 + 127) / 255) as u8;
+  }
+  out
+}
+
+/// Validates a gamma exponent
+pub(crate) fn validate_gamma(gamma: f64) -> napi::Result<()> {
+  if !gamma.is_finite() || gamma <= 0.0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid gamma: {}", gamma),
+    ));
+  }
+  Ok(())
+}
+
+/// Validates a brightness multiplier
+pub(crate) fn validate_brightness(brightness: f64) -> napi::Result<()> {
+  if !brightness.is_finite() || brightness < 0.0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid brightness: {}", brightness),
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_identity_curve_is_skipped() {
+    assert!(ToneCurve::new(1.0, 1.0).is_none());
+  }
+
+  #[test]
+  fn test_curve_keeps_alpha() {
+    let curve = ToneCurve::new(1.0, 0.5).unwrap();
+    assert_eq!(curve.apply([200, 100, 0, 77]), [100, 50, 0, 77]);
+  }
+
+  #[test]
+  fn test_gamma_lifts_midtones_and_brightness_clamps() {
+    let curve = ToneCurve::new(2.0, 1.0).unwrap();
+    assert_eq!(curve.apply([0, 64, 255, 255]), [0, 128, 255, 255]);
+
+    let curve = ToneCurve::new(1.0, 2.0).unwrap();
+    assert_eq!(curve.apply([100, 200, 255, 255]), [200, 255, 255, 255]);
+  }
+
+  #[test]
+  fn test_tint_multiplies_channels() {
+    assert_eq!(
+      apply_tint([255, 255, 255, 255], [128, 0, 0, 255]),
+      [128, 0, 0, 255]
+    );
+    assert_eq!(
+      apply_tint([200, 100, 50, 255], [255; 4]),
+      [200, 100, 50, 255]
+    );
+    assert_eq!(
+      apply_tint([200, 100, 50, 200], [255, 255, 255, 128]),
+      [200, 100, 50, 100]
+    );
+  }
+
+  #[test]
+  fn test_validation() {
+    assert!(validate_gamma(2.2).is_ok());
+    assert!(validate_gamma(0.0).is_err());
+    assert!(validate_gamma(f64::NAN).is_err());
+    assert!(validate_brightness(0.0).is_ok());
+    assert!(validate_brightness(-1.0).is_err());
+  }
+}
@@ -0,0 +1,179 @@
+//! Input validation and diagnostics for pixel buffer rendering
+//!
+//! This module provides helpers for validating source buffers and producing
+//! actionable error messages when they don't match the renderer configuration.
+
+/// Common frame resolutions, used to recognize buffers sized for a different image
+const COMMON_RESOLUTIONS: &[(u32, u32)] = &[
+  (160, 144),
+  (256, 224),
+  (256, 240),
+  (320, 180),
+  (320, 200),
+  (320, 240),
+  (640, 360),
+  (640, 400),
+  (640, 480),
+  (720, 480),
+  (720, 576),
+  (800, 450),
+  (800, 480),
+  (800, 600),
+  (854, 480),
+  (960, 540),
+  (1024, 576),
+  (1024, 600),
+  (1024, 768),
+  (1152, 864),
+  (1280, 720),
+  (1280, 800),
+  (1280, 960),
+  (1280, 1024),
+  (1366, 768),
+  (1440, 900),
+  (1440, 1080),
+  (1600, 900),
+  (1600, 1200),
+  (1680, 1050),
+  (1920, 1080),
+  (1920, 1200),
+  (2048, 1080),
+  (2560, 1080),
+  (2560, 1440),
+  (2560, 1600),
+  (3440, 1440),
+  (3840, 2160),
+  (4096, 2160),
+  (7680, 4320),
+];
+
+/// Finds a common resolution whose size matches the given buffer length
+///
+/// Returns `None` if no known resolution produces exactly `len` bytes.
+///
+/// # Arguments
+/// * `len` - Length of the buffer in bytes
+/// * `bytes_per_pixel` - Number of bytes used by each pixel
+pub fn guess_dimensions(len: usize, bytes_per_pixel: usize) -> Option<(u32, u32)> {
+  if bytes_per_pixel == 0 || len == 0 || !len.is_multiple_of(bytes_per_pixel) {
+    return None;
+  }
+  let pixel_count = len / bytes_per_pixel;
+  COMMON_RESOLUTIONS
+    .iter()
+    .copied()
+    .find(|&(w, h)| w as usize * h as usize == pixel_count)
+}
+
+/// Builds the error message for a buffer whose length doesn't match the renderer
+///
+/// When the length corresponds to a common resolution, the message names it so
+/// dimension mismatches between a decoder and the renderer are easy to spot.
+pub fn buffer_size_mismatch_message(
+  len: usize,
+  expected_len: usize,
+  buffer_width: u32,
+  buffer_height: u32,
+  bytes_per_pixel: usize,
+) -> String {
+  let mut message = format!(
+    "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
+    len, expected_len, buffer_width, buffer_height
+  );
+  if let Some((w, h)) = guess_dimensions(len, bytes_per_pixel) {
+    message.push_str(&format!(
+      " (length matches {}x{}; renderer expects {}x{})",
+      w, h, buffer_width, buffer_height
+    ));
+  }
+  message
+}
+
+/// Length in bytes of a `width` x `height` buffer of `bytes_per_pixel`-byte pixels
+///
+/// Returns `None` when a row's length overflows a `u32` or the buffer's
+/// overflows a `usize`; no buffer can have such a length.
+pub fn buffer_len(width: u32, height: u32, bytes_per_pixel: u32) -> Option<usize> {
+  let row_len = width.checked_mul(bytes_per_pixel)?;
+  (row_len as usize).checked_mul(height as usize)
+}
+
+/// Builds the error message for dimensions whose buffer length overflows
+pub fn buffer_too_large_message(width: u32, height: u32, bytes_per_pixel: u32) -> String {
+  format!(
+    "Buffer dimensions {}x{} are too large: {} bytes per pixel overflows the buffer length",
+    width, height, bytes_per_pixel
+  )
+}
+
+/// Rejects buffer dimensions of 0, which leave nothing to scale, and
+/// dimensions whose RGBA buffer length would overflow
+pub(crate) fn validate_buffer_dimensions(width: u32, height: u32) -> napi::Result<()> {
+  if width == 0 || height == 0 {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Invalid buffer dimensions {}x{}: width and height must be at least 1",
+        width, height
+      ),
+    ));
+  }
+  if buffer_len(width, height, 4).is_none() {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      buffer_too_large_message(width, height, 4),
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_guess_dimensions_common_resolution() {
+    assert_eq!(guess_dimensions(640 * 480 * 4, 4), Some((640, 480)));
+    assert_eq!(guess_dimensions(1920 * 1080 * 4, 4), Some((1920, 1080)));
+  }
+
+  #[test]
+  fn test_guess_dimensions_unknown_length() {
+    assert_eq!(guess_dimensions(123 * 457 * 4, 4), None);
+    // Not a whole number of pixels
+    assert_eq!(guess_dimensions(640 * 480 * 4 + 1, 4), None);
+    assert_eq!(guess_dimensions(0, 4), None);
+    assert_eq!(guess_dimensions(16, 0), None);
+  }
+
+  #[test]
+  fn test_buffer_len_checks_overflow() {
+    assert_eq!(buffer_len(640, 480, 4), Some(640 * 480 * 4));
+    // The row alone overflows a u32
+    assert_eq!(buffer_len(u32::MAX / 2, 1, 4), None);
+    assert!(validate_buffer_dimensions(u32::MAX, 2).is_err());
+    assert!(validate_buffer_dimensions(40000, 40000).is_ok());
+  }
+
+  #[test]
+  fn test_mismatch_message_names_matching_resolution() {
+    let message = buffer_size_mismatch_message(640 * 480 * 4, 800 * 600 * 4, 800, 600, 4);
+    assert!(message.starts_with("Buffer size mismatch: got 1228800 bytes"));
+    assert!(message.contains("length matches 640x480; renderer expects 800x600"));
+  }
+
+  #[test]
+  fn test_mismatch_message_uses_bytes_per_pixel() {
+    let message = buffer_size_mismatch_message(640 * 480 * 3, 800 * 600 * 3, 800, 600, 3);
+    assert!(message.contains("length matches 640x480"));
+  }
+
+  #[test]
+  fn test_mismatch_message_without_match() {
+    let message = buffer_size_mismatch_message(10, 800 * 600 * 4, 800, 600, 4);
+    assert_eq!(
+      message,
+      "Buffer size mismatch: got 10 bytes, expected 1920000 bytes for 800x600"
+    );
+  }
+}
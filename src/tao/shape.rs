@@ -0,0 +1,139 @@
+//! Non-rectangular windows
+//!
+//! Clips a window to the set pixels of a mask with the windowing system's shape
+//! support (the XShape extension on X11), so input and drawing outside the shape
+//! are absent rather than just transparent. Only Linux (GTK) is supported for now.
+
+/// Horizontal run of set mask pixels: (x, y, width)
+pub(crate) type MaskRun = (u32, u32, u32);
+
+/// Collects the runs of nonzero bytes in each row of a one-byte-per-pixel mask
+pub(crate) fn mask_runs(mask: &[u8], width: u32, height: u32) -> Vec<MaskRun> {
+  let mut runs = Vec::new();
+  if width == 0 {
+    return runs;
+  }
+  for (y, row) in mask
+    .chunks_exact(width as usize)
+    .take(height as usize)
+    .enumerate()
+  {
+    let mut start = None;
+    for (x, &value) in row.iter().chain(std::iter::once(&0)).enumerate() {
+      match (start, value != 0) {
+        (None, true) => start = Some(x),
+        (Some(left), false) => {
+          runs.push((left as u32, y as u32, (x - left) as u32));
+          start = None;
+        }
+        _ => {}
+      }
+    }
+  }
+  runs
+}
+
+/// Validates that a mask holds one byte per pixel
+pub(crate) fn validate_mask(mask: &[u8], width: u32, height: u32) -> napi::Result<()> {
+  let expected_len = width as usize * height as usize;
+  if mask.len() != expected_len {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Shape mask size mismatch: got {} bytes, expected {} bytes for {}x{}",
+        mask.len(),
+        expected_len,
+        width,
+        height
+      ),
+    ));
+  }
+  Ok(())
+}
+
+pub(crate) use platform::{clear_shape, set_shape};
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::MaskRun;
+  use gtk::cairo;
+  use gtk::prelude::WidgetExt;
+  use tao::platform::unix::WindowExtUnix;
+
+  pub fn set_shape(window: &tao::window::Window, runs: &[MaskRun]) -> napi::Result<()> {
+    let region = cairo::Region::create();
+    for &(x, y, width) in runs {
+      region
+        .union_rectangle(&cairo::RectangleInt::new(
+          x as i32,
+          y as i32,
+          width as i32,
+          1,
+        ))
+        .map_err(|e| {
+          napi::Error::new(
+            napi::Status::GenericFailure,
+            format!("Failed to build shape region: {}", e),
+          )
+        })?;
+    }
+    window.gtk_window().shape_combine_region(Some(&region));
+    Ok(())
+  }
+
+  pub fn clear_shape(window: &tao::window::Window) -> napi::Result<()> {
+    window.gtk_window().shape_combine_region(None);
+    Ok(())
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+  use super::MaskRun;
+
+  fn unsupported() -> napi::Error {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      "Window shapes are not supported on this platform yet".to_string(),
+    )
+  }
+
+  pub fn set_shape(_window: &tao::window::Window, _runs: &[MaskRun]) -> napi::Result<()> {
+    Err(unsupported())
+  }
+
+  pub fn clear_shape(_window: &tao::window::Window) -> napi::Result<()> {
+    Err(unsupported())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_mask_runs_per_row() {
+    #[rustfmt::skip]
+    let mask = [
+      0, 1, 1, 0,
+      1, 0, 0, 255,
+      0, 0, 0, 0,
+    ];
+    assert_eq!(
+      mask_runs(&mask, 4, 3),
+      vec![(1, 0, 2), (0, 1, 1), (3, 1, 1)]
+    );
+  }
+
+  #[test]
+  fn test_mask_runs_full_row() {
+    assert_eq!(mask_runs(&[9, 9, 9], 3, 1), vec![(0, 0, 3)]);
+    assert!(mask_runs(&[], 0, 0).is_empty());
+  }
+
+  #[test]
+  fn test_validate_mask() {
+    assert!(validate_mask(&[0; 6], 3, 2).is_ok());
+    assert!(validate_mask(&[0; 24], 3, 2).is_err());
+  }
+}
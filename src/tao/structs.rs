@@ -3,13 +3,19 @@
 //! This module contains all structs from the tao crate.
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, Weak};
 
+use crate::tao::cursor::CursorFrame;
 use crate::tao::enums::{
-  CursorIcon, ModifiersState, MouseButton, MouseButtonState, TaoTheme, WindowEvent,
+  CursorIcon, ModifiersState, MouseButton, MouseButtonState, ProgressState, RawHandleKind,
+  TaoTheme, WindowEvent,
 };
 use crate::tao::types::Result;
+use tao::rwh_06::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
 
 #[cfg(target_os = "macos")]
 use tao::platform::macos::WindowBuilderExtMacOS;
@@ -35,6 +41,62 @@ pub struct MonitorInfo {
   pub position: Position,
   /// The scale factor of monitor.
   pub scale_factor: f64,
+  /// Whether this is the primary monitor (null when unknown).
+  pub is_primary: Option<bool>,
+}
+
+/// Monitor layout returned by `monitors()`.
+#[napi(object)]
+pub struct MonitorLayout {
+  /// The display server the layout was read from: "x11", "wayland", "windows", "cocoa" or "unknown".
+  pub display_server: String,
+  /// Whether windows can be placed at absolute positions (false on Wayland).
+  pub supports_positioning: bool,
+  /// The monitors, in the order the platform reports them.
+  pub monitors: Vec<MonitorInfo>,
+}
+
+/// Native handle of a window returned by `Window.rawHandle()`.
+///
+/// Only valid while the window is alive: once it is closed or garbage
+/// collected, the handle may dangle or be reused by another window.
+#[napi(object, object_from_js = false)]
+pub struct RawHandleInfo {
+  /// The windowing API the handle belongs to.
+  pub kind: RawHandleKind,
+  /// The XID, `HWND`, `wl_surface*` or `NSView*`, as an integer.
+  pub handle: u64,
+  /// The connection the handle belongs to: Xlib `Display*`, `xcb_connection_t*`
+  /// or `wl_display*` (null on Win32 and AppKit, or when unknown).
+  pub display: Option<u64>,
+}
+
+impl RawHandleInfo {
+  /// Flattens raw-window-handle's handles to integers
+  // An XID is a `c_ulong`, which is only 32 bits on some targets
+  #[allow(clippy::useless_conversion)]
+  fn new(window: RawWindowHandle, display: Option<RawDisplayHandle>) -> Self {
+    let pointer = |ptr: std::ptr::NonNull<std::ffi::c_void>| ptr.as_ptr() as usize as u64;
+    let (kind, handle) = match window {
+      RawWindowHandle::Xlib(handle) => (RawHandleKind::Xlib, u64::from(handle.window)),
+      RawWindowHandle::Xcb(handle) => (RawHandleKind::Xcb, u64::from(handle.window.get())),
+      RawWindowHandle::Wayland(handle) => (RawHandleKind::Wayland, pointer(handle.surface)),
+      RawWindowHandle::Win32(handle) => (RawHandleKind::Win32, handle.hwnd.get() as usize as u64),
+      RawWindowHandle::AppKit(handle) => (RawHandleKind::AppKit, pointer(handle.ns_view)),
+      _ => (RawHandleKind::Other, 0),
+    };
+    let display = match display {
+      Some(RawDisplayHandle::Xlib(display)) => display.display.map(pointer),
+      Some(RawDisplayHandle::Xcb(display)) => display.connection.map(pointer),
+      Some(RawDisplayHandle::Wayland(display)) => Some(pointer(display.display)),
+      _ => None,
+    };
+    Self {
+      kind,
+      handle,
+      display,
+    }
+  }
 }
 
 /// 2D position.
@@ -401,9 +463,16 @@ impl EventLoop {
   #[napi]
   pub fn run(&mut self) -> Result<()> {
     if let Some(event_loop) = self.inner.take() {
-      event_loop.run(move |event, _, control_flow| {
+      event_loop.run(move |event, event_loop_target, control_flow| {
         *control_flow = tao::event_loop::ControlFlow::Wait;
         match event {
+          tao::event::Event::WindowEvent {
+            window_id,
+            event: tao::event::WindowEvent::Moved(position),
+            ..
+          } => {
+            dispatch_window_moved(event_loop_target, window_id, position);
+          }
           // Handle redraw requests to ensure window content is painted
           tao::event::Event::RedrawRequested(_) => {
             // Window content will be drawn by the window's renderer
@@ -443,9 +512,16 @@ impl EventLoop {
       ))]
       {
         use tao::platform::run_return::EventLoopExtRunReturn;
-        event_loop.run_return(|event, _, control_flow| {
+        event_loop.run_return(|event, event_loop_target, control_flow| {
           *control_flow = tao::event_loop::ControlFlow::Poll;
           match event {
+            tao::event::Event::WindowEvent {
+              window_id,
+              event: tao::event::WindowEvent::Moved(position),
+              ..
+            } => {
+              dispatch_window_moved(event_loop_target, window_id, position);
+            }
             tao::event::Event::WindowEvent {
               event: tao::event::WindowEvent::CloseRequested,
               ..
@@ -559,7 +635,209 @@ pub struct EventLoopWindowTarget {
 #[napi]
 pub struct Window {
   #[allow(dead_code)]
-  pub(crate) inner: Option<Arc<Mutex<tao::window::Window>>>,
+  pub(crate) inner: Option<Arc<Mutex<NativeWindow>>>,
+  /// Minimum spacing between presents by a `PixelRenderer`, in microseconds (0 = unlimited).
+  pub(crate) min_present_interval_micros: AtomicU64,
+  /// Properties of the current monitor, refreshed by the event loop when the window moves.
+  pub(crate) display: Arc<WindowDisplay>,
+}
+
+/// Display state of a window that the event loop keeps up to date.
+#[derive(Default)]
+pub(crate) struct WindowDisplay {
+  /// Callbacks registered through `Window::on_moved`.
+  moved_handlers: Mutex<Vec<ThreadsafeFunction<Position>>>,
+  /// Scale factor of the current monitor as `f64` bits (0 = unknown).
+  scale_factor_bits: AtomicU64,
+  /// Refresh rate of the current monitor in millihertz (0 = unknown).
+  refresh_rate_millihertz: AtomicU32,
+}
+
+impl WindowDisplay {
+  /// Scale factor of the monitor the window was last seen on.
+  pub(crate) fn scale_factor(&self) -> Option<f64> {
+    let bits = self.scale_factor_bits.load(Ordering::Relaxed);
+    (bits != 0).then(|| f64::from_bits(bits))
+  }
+
+  /// Refresh rate in millihertz of the monitor the window was last seen on.
+  pub(crate) fn refresh_rate_millihertz(&self) -> Option<u32> {
+    let millihertz = self.refresh_rate_millihertz.load(Ordering::Relaxed);
+    (millihertz != 0).then_some(millihertz)
+  }
+
+  /// Caches the scale factor and refresh rate of a monitor.
+  fn update_from_monitor(&self, monitor: &tao::monitor::MonitorHandle) {
+    self
+      .scale_factor_bits
+      .store(monitor.scale_factor().to_bits(), Ordering::Relaxed);
+    self.refresh_rate_millihertz.store(
+      monitor_refresh_rate_millihertz(monitor).unwrap_or(0),
+      Ordering::Relaxed,
+    );
+  }
+}
+
+/// Refresh rate of a monitor in millihertz, if the platform reports one.
+#[cfg(target_os = "linux")]
+fn monitor_refresh_rate_millihertz(monitor: &tao::monitor::MonitorHandle) -> Option<u32> {
+  use gtk::gdk::prelude::MonitorExt;
+  use tao::platform::unix::MonitorHandleExtUnix;
+
+  // GDK reports 0 when the backend doesn't know, as on some Wayland compositors
+  u32::try_from(monitor.gdk_monitor().refresh_rate())
+    .ok()
+    .filter(|&millihertz| millihertz != 0)
+}
+
+/// Refresh rate of a monitor in millihertz, if the platform reports one.
+#[cfg(not(target_os = "linux"))]
+fn monitor_refresh_rate_millihertz(monitor: &tao::monitor::MonitorHandle) -> Option<u32> {
+  // tao doesn't expose the active mode, so use the fastest mode at the monitor's
+  // size; video modes only carry whole hertz
+  let size = monitor.size();
+  monitor
+    .video_modes()
+    .filter(|mode| mode.size() == size)
+    .map(|mode| mode.refresh_rate() as u32 * 1000)
+    .max()
+    .filter(|&millihertz| millihertz != 0)
+}
+
+/// Display state of every live window, keyed by window ID, for event loop dispatch.
+static WINDOW_DISPLAYS: LazyLock<Mutex<HashMap<u64, Weak<WindowDisplay>>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Numeric IDs assigned to tao window IDs, keyed by the tao ID itself.
+static WINDOW_IDS: LazyLock<Mutex<HashMap<tao::window::WindowId, u64>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Next numeric window ID to assign (0 is reserved for uninitialized windows).
+static NEXT_WINDOW_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Converts a tao window ID to the numeric ID exposed to JavaScript.
+///
+/// IDs are assigned in creation order the first time a window is seen, so they
+/// never collide and stay the same for the window's lifetime; this is the key
+/// of the render cache (`clearRenderCache`) and of every per-window table.
+pub(crate) fn window_id_to_u64(id: tao::window::WindowId) -> u64 {
+  *WINDOW_IDS
+    .lock()
+    .unwrap()
+    .entry(id)
+    .or_insert_with(|| NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Numeric ID of a window that has one, without assigning a new ID.
+fn known_window_id(id: tao::window::WindowId) -> Option<u64> {
+  WINDOW_IDS.lock().unwrap().get(&id).copied()
+}
+
+/// A native window, shared by a `Window` and anything presenting to it.
+///
+/// Renderer handles and async renders keep their own reference, so whichever
+/// reference goes last releases the window's render state and numeric ID.
+pub(crate) struct NativeWindow {
+  /// Declared first so it's released while the native window still exists
+  _release: WindowRelease,
+  window: tao::window::Window,
+}
+
+impl NativeWindow {
+  fn new(window: tao::window::Window) -> Self {
+    Self {
+      _release: WindowRelease {
+        id: window.id(),
+        window_id: window_id_to_u64(window.id()),
+      },
+      window,
+    }
+  }
+}
+
+impl std::ops::Deref for NativeWindow {
+  type Target = tao::window::Window;
+
+  fn deref(&self) -> &Self::Target {
+    &self.window
+  }
+}
+
+/// Releases what the renderer and ID registry keep for a window when dropped.
+struct WindowRelease {
+  id: tao::window::WindowId,
+  window_id: u64,
+}
+
+impl Drop for WindowRelease {
+  fn drop(&mut self) {
+    let _ = crate::tao::render::evict_render_state(self.window_id);
+    if let Ok(mut ids) = WINDOW_IDS.lock() {
+      ids.remove(&self.id);
+    }
+  }
+}
+
+/// Handles a `Moved` window event from any event loop.
+///
+/// Re-queries the monitor under the new position so cached scale factor and
+/// refresh rate follow the window across displays, then calls `on_moved` handlers.
+pub(crate) fn dispatch_window_moved(
+  target: &tao::event_loop::EventLoopWindowTarget<()>,
+  window_id: tao::window::WindowId,
+  position: tao::dpi::PhysicalPosition<i32>,
+) {
+  let Some(key) = known_window_id(window_id) else {
+    return;
+  };
+  let display = {
+    let mut displays = WINDOW_DISPLAYS.lock().unwrap();
+    displays.retain(|_, display| display.strong_count() > 0);
+    displays.get(&key).and_then(Weak::upgrade)
+  };
+  let Some(display) = display else {
+    return;
+  };
+
+  if let Some(monitor) = target.monitor_from_point(position.x as f64, position.y as f64) {
+    display.update_from_monitor(&monitor);
+  }
+
+  for handler in display.moved_handlers.lock().unwrap().iter() {
+    let _ = handler.call(
+      Ok(Position {
+        x: position.x as f64,
+        y: position.y as f64,
+      }),
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  }
+}
+
+impl Window {
+  /// Wraps a native tao window.
+  pub(crate) fn from_tao(window: tao::window::Window) -> Self {
+    let display = Arc::new(WindowDisplay::default());
+    if let Some(monitor) = window.current_monitor() {
+      display.update_from_monitor(&monitor);
+    }
+    WINDOW_DISPLAYS
+      .lock()
+      .unwrap()
+      .insert(window_id_to_u64(window.id()), Arc::downgrade(&display));
+
+    Self {
+      #[allow(clippy::arc_with_non_send_sync)]
+      inner: Some(Arc::new(Mutex::new(NativeWindow::new(window)))),
+      min_present_interval_micros: AtomicU64::new(0),
+      display,
+    }
+  }
+
+  /// Gets the minimum spacing between presents for this window.
+  pub(crate) fn min_present_interval(&self) -> std::time::Duration {
+    std::time::Duration::from_micros(self.min_present_interval_micros.load(Ordering::Relaxed))
+  }
 }
 
 #[napi]
@@ -567,23 +845,18 @@ impl Window {
   /// Creates a new window with default attributes.
   #[napi(constructor)]
   pub fn new() -> Result<Self> {
-    Ok(Self { inner: None })
+    Ok(Self {
+      inner: None,
+      min_present_interval_micros: AtomicU64::new(0),
+      display: Arc::new(WindowDisplay::default()),
+    })
   }
 
   /// Gets the window ID.
   #[napi(getter)]
   pub fn id(&self) -> Result<u64> {
     if let Some(inner) = &self.inner {
-      let id = inner.lock().unwrap().id();
-      let mut id_val: u64 = 0;
-      unsafe {
-        std::ptr::copy_nonoverlapping(
-          &id as *const _ as *const u8,
-          &mut id_val as *mut _ as *mut u8,
-          std::mem::size_of_val(&id).min(8),
-        );
-      }
-      Ok(id_val)
+      Ok(window_id_to_u64(inner.lock().unwrap().id()))
     } else {
       Ok(0)
     }
@@ -687,8 +960,8 @@ impl Window {
   #[napi]
   pub fn set_outer_position(&self, x: f64, y: f64) -> Result<()> {
     // Wayland compositors own window placement and ignore absolute positioning,
-    // so only apply this on X11.
-    if crate::tao::platform::platform_info().is_x11() {
+    // so only apply this where the platform supports it.
+    if crate::tao::platform::platform_info().supports_positioning {
       if let Some(inner) = &self.inner {
         inner
           .lock()
@@ -757,6 +1030,21 @@ impl Window {
     }
   }
 
+  /// Gets whether the window's contents can't be seen: it's hidden or minimized.
+  ///
+  /// Tao reports no occlusion events, so a visible window fully covered by
+  /// other windows still counts as not occluded. `PixelRenderer.render` skips
+  /// occluded windows; render loops can check this to skip producing frames.
+  #[napi]
+  pub fn is_occluded(&self) -> Result<bool> {
+    if let Some(inner) = &self.inner {
+      let inner = inner.lock().unwrap();
+      Ok(!inner.is_visible() || inner.is_minimized())
+    } else {
+      Ok(false)
+    }
+  }
+
   /// Sets whether the window is minimized.
   #[napi]
   pub fn set_minimized(&self, minimized: bool) -> Result<()> {
@@ -848,6 +1136,70 @@ impl Window {
     Ok(())
   }
 
+  /// Cycles the cursor through RGBA frames while it is over the window.
+  ///
+  /// Frames advance on a native timer at `fps`, replacing any running animation.
+  /// The hotspot is clamped to each frame. Call `clearAnimatedCursor` to stop
+  /// the animation and restore the system cursor.
+  #[napi]
+  pub fn set_animated_cursor(
+    &self,
+    frames: Vec<CursorFrame>,
+    fps: f64,
+    hotspot_x: u32,
+    hotspot_y: u32,
+  ) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      crate::tao::cursor::start_animation(
+        &inner.lock().unwrap(),
+        &frames,
+        fps,
+        (hotspot_x, hotspot_y),
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Stops the animated cursor and restores the cursor icon.
+  ///
+  /// Returns true if an animation was running.
+  #[napi]
+  pub fn clear_animated_cursor(&self) -> Result<bool> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      if crate::tao::cursor::stop_animation(window_id_to_u64(window.id())) {
+        window.set_cursor_icon(tao::window::CursorIcon::Default);
+        return Ok(true);
+      }
+    }
+    Ok(false)
+  }
+
+  /// Clips the window to the set pixels of a mask.
+  ///
+  /// `mask` holds one byte per pixel (width * height bytes) in logical window
+  /// coordinates; nonzero bytes are inside the shape. Input and drawing outside
+  /// the shape are removed, not just made transparent. Uses the XShape extension
+  /// on X11; unsupported elsewhere.
+  #[napi]
+  pub fn set_shape(&self, mask: Buffer, width: u32, height: u32) -> Result<()> {
+    crate::tao::shape::validate_mask(&mask, width, height)?;
+    if let Some(inner) = &self.inner {
+      let runs = crate::tao::shape::mask_runs(&mask, width, height);
+      crate::tao::shape::set_shape(&inner.lock().unwrap(), &runs)?;
+    }
+    Ok(())
+  }
+
+  /// Restores the window's rectangular shape.
+  #[napi]
+  pub fn clear_shape(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      crate::tao::shape::clear_shape(&inner.lock().unwrap())?;
+    }
+    Ok(())
+  }
+
   /// Sets the cursor position.
   #[napi]
   pub fn set_cursor_position(&self, x: f64, y: f64) -> Result<()> {
@@ -939,6 +1291,130 @@ impl Window {
     Ok(())
   }
 
+  /// Sets where the text cursor is, in physical window coordinates.
+  ///
+  /// `x` and `y` are the caret's top-left corner and `height` its height. The IME
+  /// candidate window and screen magnifiers follow the caret; tao positions them
+  /// at a single point, so the candidate window is placed at the caret's bottom
+  /// edge, at (x, y + height), where it doesn't cover the caret.
+  #[napi]
+  pub fn set_ime_cursor_area(&self, x: f64, y: f64, height: f64) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      inner
+        .lock()
+        .unwrap()
+        .set_ime_position(tao::dpi::Position::Physical(
+          tao::dpi::PhysicalPosition::new(x as i32, (y + height) as i32),
+        ));
+    }
+    Ok(())
+  }
+
+  /// Registers a callback invoked with the new outer position when the window moves.
+  ///
+  /// Moving a window can put it on a monitor with a different scale factor or
+  /// refresh rate without changing its size; the cached monitor properties are
+  /// refreshed before callbacks run.
+  #[napi(ts_args_type = "callback: (error: Error | null, position: Position) => void")]
+  pub fn on_moved(&self, callback: ThreadsafeFunction<Position>) -> Result<()> {
+    self.display.moved_handlers.lock().unwrap().push(callback);
+    Ok(())
+  }
+
+  /// Gets the scale factor of the monitor the window is on.
+  #[napi]
+  pub fn scale_factor(&self) -> Result<f64> {
+    if let Some(scale_factor) = self.display.scale_factor() {
+      return Ok(scale_factor);
+    }
+    if let Some(inner) = &self.inner {
+      Ok(inner.lock().unwrap().scale_factor())
+    } else {
+      Ok(1.0)
+    }
+  }
+
+  /// Gets the refresh rate in hertz of the monitor the window is on, if known.
+  ///
+  /// On Linux this is the rate GDK reports for the monitor, keeping fractional
+  /// rates such as 59.94 Hz, or `null` when the compositor doesn't report one
+  /// (as on some Wayland setups). Other platforms don't expose the active mode,
+  /// so there it's the whole-hertz rate of the fastest video mode at the
+  /// monitor's current resolution, which may be above the rate in use.
+  #[napi]
+  pub fn refresh_rate(&self) -> Result<Option<f64>> {
+    let millihertz = match self.display.refresh_rate_millihertz() {
+      Some(millihertz) => Some(millihertz),
+      None => self.inner.as_ref().and_then(|inner| {
+        let monitor = inner.lock().unwrap().current_monitor()?;
+        monitor_refresh_rate_millihertz(&monitor)
+      }),
+    };
+    Ok(millihertz.map(|millihertz| millihertz as f64 / 1000.0))
+  }
+
+  /// Sets the taskbar/dock progress indicator.
+  ///
+  /// `value` ranges from 0 to 1 and is ignored for the `None` and `Indeterminate`
+  /// states. Uses the Windows taskbar, the macOS dock and the Unity launcher on
+  /// Linux; other platforms ignore it.
+  #[napi]
+  pub fn set_progress_bar(&self, state: ProgressState, value: Option<f64>) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      let state = match state {
+        ProgressState::None => tao::window::ProgressState::None,
+        ProgressState::Normal => tao::window::ProgressState::Normal,
+        ProgressState::Indeterminate => tao::window::ProgressState::Indeterminate,
+        ProgressState::Paused => tao::window::ProgressState::Paused,
+        ProgressState::Error => tao::window::ProgressState::Error,
+      };
+      inner
+        .lock()
+        .unwrap()
+        .set_progress_bar(tao::window::ProgressBarState {
+          state: Some(state),
+          progress: value.map(|v| (v.clamp(0.0, 1.0) * 100.0).round() as u64),
+          desktop_filename: None,
+        });
+    }
+    Ok(())
+  }
+
+  /// Sets the minimum time between presents for this window, in microseconds.
+  ///
+  /// Frames submitted through a `PixelRenderer` before the interval has elapsed since
+  /// the last present are dropped, which keeps rarely-changing windows from presenting
+  /// more often than the compositor needs. Use 0 to remove the limit.
+  #[napi]
+  pub fn set_min_present_interval_micros(&self, micros: u32) -> Result<()> {
+    self
+      .min_present_interval_micros
+      .store(micros as u64, Ordering::Relaxed);
+    Ok(())
+  }
+
+  /// Limits how often a `PixelRenderer` presents to this window, in frames per second.
+  ///
+  /// Sets the minimum present interval to `1 / fps` seconds. Use 0 to remove the limit.
+  #[napi]
+  pub fn set_target_fps(&self, fps: f64) -> Result<()> {
+    if !fps.is_finite() || fps < 0.0 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Invalid target FPS: {}", fps),
+      ));
+    }
+    let micros = if fps == 0.0 {
+      0
+    } else {
+      (1_000_000.0 / fps).round() as u64
+    };
+    self
+      .min_present_interval_micros
+      .store(micros, Ordering::Relaxed);
+    Ok(())
+  }
+
   /// Requests a redrawing of the window.
   #[napi]
   pub fn request_redraw(&self) -> Result<()> {
@@ -949,16 +1425,52 @@ impl Window {
   }
 
   /// Closes the window.
-  /// This hides the window immediately. The underlying window handle is still held
-  /// until the Window struct is dropped.
+  /// This hides the window immediately and releases its cached render surface.
+  /// The underlying window handle is still held until the Window struct is dropped.
   #[napi]
   pub fn close(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().set_visible(false);
+      let window = inner.lock().unwrap();
+      window.set_visible(false);
+      crate::tao::render::evict_render_state(window_id_to_u64(window.id()))
+        .map_err(crate::tao::render::error::into_napi_error)?;
     }
     Ok(())
   }
 
+  /// Gets the key of the window's cached render state.
+  ///
+  /// This is the ID `PixelRenderer` caches surfaces under, as listed by
+  /// `cachedRenderWindows()` and accepted by `clearRenderCache()`. It is the
+  /// same value as `id`.
+  #[napi]
+  pub fn render_cache_id(&self) -> Result<u64> {
+    self.id()
+  }
+
+  /// Gets the native window handle, for passing to other native libraries.
+  ///
+  /// Returns null if the window hasn't been created or its native window isn't
+  /// available yet. The handle is borrowed, not owned: it is only valid while
+  /// this window is alive, so the other library must stop using it before the
+  /// window is closed. Presenting to it from elsewhere competes with any
+  /// `PixelRenderer` rendering to the same window.
+  #[napi]
+  pub fn raw_handle(&self) -> Result<Option<RawHandleInfo>> {
+    let Some(inner) = &self.inner else {
+      return Ok(None);
+    };
+    let window = inner.lock().unwrap();
+    let Ok(window_handle) = window.window_handle() else {
+      return Ok(None);
+    };
+    let display_handle = window.display_handle().ok().map(|handle| handle.as_raw());
+    Ok(Some(RawHandleInfo::new(
+      window_handle.as_raw(),
+      display_handle,
+    )))
+  }
+
   /// Returns true if the window is marked as closed (hidden via close()).
   #[napi]
   pub fn is_closed(&self) -> Result<bool> {
@@ -1182,8 +1694,8 @@ impl WindowBuilder {
       .with_focused(self.attributes.focused);
 
     // Set position if provided. Wayland does not support absolute client-side
-    // window positioning, so only apply it on X11.
-    if platform_info.is_x11() {
+    // window positioning, so only apply it where the platform supports it.
+    if platform_info.supports_positioning {
       if let Some(x) = self.attributes.x {
         if let Some(y) = self.attributes.y {
           builder = builder.with_position(tao::dpi::LogicalPosition::new(x, y));
@@ -1212,8 +1724,25 @@ impl WindowBuilder {
       )
     })?;
 
-    Ok(Window {
-      inner: Some(Arc::new(Mutex::new(window))),
-    })
+    Ok(Window::from_tao(window))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_window_release_waits_for_a_handle_outliving_the_window() {
+    // SAFETY: the dummy ID is only used as a registry key, never passed to tao
+    let id = unsafe { tao::window::WindowId::dummy() };
+    let window_id = window_id_to_u64(id);
+    let window = Arc::new(WindowRelease { id, window_id });
+    let handle = window.clone();
+
+    drop(window);
+    assert_eq!(known_window_id(id), Some(window_id));
+    drop(handle);
+    assert_eq!(known_window_id(id), None);
   }
 }
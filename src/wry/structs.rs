@@ -85,6 +85,7 @@ pub struct ProxyEndpoint {
 
 /// A rectangle area.
 #[napi(object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rect {
   /// The X coordinate of the top-left corner.
   pub x: i32,
@@ -96,6 +97,28 @@ pub struct Rect {
   pub height: u32,
 }
 
+impl Rect {
+  /// Clips the rectangle to a `width` x `height` area anchored at the origin.
+  ///
+  /// Returns `None` when the intersection is empty, including for rectangles with
+  /// zero width or height and rectangles entirely outside the area.
+  pub fn clip_to(&self, width: u32, height: u32) -> Option<Rect> {
+    let left = (self.x as i64).clamp(0, width as i64);
+    let top = (self.y as i64).clamp(0, height as i64);
+    let right = (self.x as i64 + self.width as i64).clamp(0, width as i64);
+    let bottom = (self.y as i64 + self.height as i64).clamp(0, height as i64);
+    if right <= left || bottom <= top {
+      return None;
+    }
+    Some(Rect {
+      x: left as i32,
+      y: top as i32,
+      width: (right - left) as u32,
+      height: (bottom - top) as u32,
+    })
+  }
+}
+
 /// A responder for a request.
 #[napi(object)]
 pub struct RequestAsyncResponder {
@@ -1528,3 +1551,75 @@ fn setup_ipc_handler(
 
   (webview_builder, ipc_listeners)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rect(x: i32, y: i32, width: u32, height: u32) -> Rect {
+    Rect {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  #[test]
+  fn test_clip_to_inside_is_unchanged() {
+    assert_eq!(
+      rect(10, 20, 30, 40).clip_to(100, 100),
+      Some(rect(10, 20, 30, 40))
+    );
+  }
+
+  #[test]
+  fn test_clip_to_partially_outside() {
+    assert_eq!(
+      rect(-10, -5, 30, 20).clip_to(100, 100),
+      Some(rect(0, 0, 20, 15))
+    );
+    assert_eq!(
+      rect(90, 95, 30, 20).clip_to(100, 100),
+      Some(rect(90, 95, 10, 5))
+    );
+  }
+
+  #[test]
+  fn test_clip_to_covering_area() {
+    assert_eq!(
+      rect(-10, -10, 200, 200).clip_to(100, 50),
+      Some(rect(0, 0, 100, 50))
+    );
+  }
+
+  #[test]
+  fn test_clip_to_zero_size_is_none() {
+    assert_eq!(rect(10, 10, 0, 20).clip_to(100, 100), None);
+    assert_eq!(rect(10, 10, 20, 0).clip_to(100, 100), None);
+  }
+
+  #[test]
+  fn test_clip_to_entirely_outside_is_none() {
+    assert_eq!(rect(100, 0, 10, 10).clip_to(100, 100), None);
+    assert_eq!(rect(0, -10, 10, 10).clip_to(100, 100), None);
+    assert_eq!(rect(-20, 50, 10, 10).clip_to(100, 100), None);
+  }
+
+  #[test]
+  fn test_clip_to_empty_area_is_none() {
+    assert_eq!(rect(0, 0, 10, 10).clip_to(0, 0), None);
+  }
+
+  #[test]
+  fn test_clip_to_extreme_values_do_not_overflow() {
+    assert_eq!(
+      rect(i32::MAX, i32::MAX, u32::MAX, u32::MAX).clip_to(100, 100),
+      None
+    );
+    assert_eq!(
+      rect(i32::MIN, i32::MIN, u32::MAX, u32::MAX).clip_to(100, 100),
+      Some(rect(0, 0, 100, 100))
+    );
+  }
+}